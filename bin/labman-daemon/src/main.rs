@@ -1,18 +1,25 @@
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
 use std::path::PathBuf;
 use std::process;
 use std::sync::Arc;
 use std::time::Duration;
 
-use clap::{ArgAction, Parser};
-use labman_config::{load_default, load_from_path, LabmanConfig};
+use clap::{ArgAction, Parser, ValueEnum};
+use labman_config::{default_config_path, load_layered, LabmanConfig, LoadedConfig};
 use labman_core::LabmanError;
 use labman_endpoints::{EndpointRegistry, EndpointRegistryBuilder};
 use labman_proxy::{ProxyConfig as LabmanProxyConfig, ProxyServer as LabmanProxyServer};
 use labman_server::{LabmanServer, ServerConfig};
-use labman_telemetry;
 use labman_ws_portman::{run_portman_ws_server, PortmanWsConfig};
-use tracing::warn;
+
+mod reload;
+
+/// Output format of `--dump-config`.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum DumpFormat {
+    Toml,
+    Json,
+}
 
 /// labmand - labman daemon
 ///
@@ -45,7 +52,7 @@ struct Cli {
     #[arg(long = "config", short = 'c', value_name = "PATH")]
     config: Option<PathBuf>,
 
-    /// Log level for labmand (overrides RUST_LOG if set).
+    /// Log level for labmand (overrides RUST_LOG and `telemetry.log_level`).
     ///
     /// Accepts standard tracing levels (trace, debug, info, warn, error) or a
     /// full filter expression (e.g. "info,labmand=debug").
@@ -54,7 +61,8 @@ struct Cli {
 
     /// Print loaded configuration summary and exit without starting the daemon.
     ///
-    /// This is primarily useful for debugging configuration issues.
+    /// This is primarily useful for debugging configuration issues. Use
+    /// --dump-config for the complete effective configuration.
     #[arg(long = "print-config", action = ArgAction::SetTrue)]
     print_config: bool,
 
@@ -72,74 +80,156 @@ struct Cli {
 
     /// Validate configuration and exit without starting the daemon.
     ///
-    /// This is useful for CI and deployment pipelines to ensure configuration
-    /// is structurally sound before rollout.
+    /// Prints every error and warning (including unknown keys) with its TOML
+    /// path and exits non-zero if there are errors. This is useful for CI and
+    /// deployment pipelines to ensure configuration is structurally sound
+    /// before rollout.
     #[arg(long = "check-config", action = ArgAction::SetTrue)]
     check_config: bool,
+
+    /// Print the effective configuration (defaults filled in, secrets
+    /// redacted, control-plane values annotated) as TOML or JSON and exit.
+    ///
+    /// Diff the output against what was deployed to see what a node is
+    /// actually running.
+    #[arg(
+        long = "dump-config",
+        value_name = "FORMAT",
+        value_enum,
+        num_args = 0..=1,
+        default_missing_value = "toml"
+    )]
+    dump_config: Option<DumpFormat>,
+
+    /// Print the JSON Schema of the configuration file and exit.
+    ///
+    /// Point editors at the output to validate and complete `labman.toml`.
+    #[arg(long = "print-schema", action = ArgAction::SetTrue)]
+    print_schema: bool,
+
+    /// Print a commented reference configuration listing every setting with
+    /// its default, and exit.
+    #[arg(long = "print-reference-config", action = ArgAction::SetTrue)]
+    print_reference_config: bool,
 }
 
 fn main() {
     let cli = Cli::parse();
 
-    // Initialise telemetry as early as possible so subsequent logs use the
-    // configured subscriber. CLI-provided log level, if any, takes precedence
-    // over RUST_LOG.
-    if let Err(err) = labman_telemetry::init(cli.log_level.as_deref()) {
+    // Documentation output needs neither telemetry nor a configuration.
+    if cli.print_schema {
+        println!("{:#}", labman_config::schema::json_schema());
+        return;
+    }
+    if cli.print_reference_config {
+        print!("{}", labman_config::schema::reference_toml());
+        return;
+    }
+
+    // Remember where the configuration came from so it can be reloaded.
+    let config_path = cli.config.clone().or_else(default_config_path);
+
+    // Load and check the configuration before initialising telemetry, since
+    // `[telemetry]` decides where logs go. Settings the control plane
+    // supplied earlier fill in whatever the operator left unset.
+    let config_result: Result<LoadedConfig, LabmanError> = match config_path.as_deref() {
+        Some(path) => load_layered(path).map(|mut loaded| {
+            loaded.apply_control_plane_state();
+            loaded
+        }),
+        None => Err(LabmanError::config(
+            "no configuration file found; provide a path explicitly or create /etc/labman/labman.toml or ./labman.toml",
+        )),
+    };
+    let report = config_result
+        .as_ref()
+        .ok()
+        .map(LoadedConfig::validation_report);
+
+    // CLI-provided log level, if any, takes precedence over RUST_LOG and
+    // telemetry.log_level. Logs share stdout with --dump-config output, so
+    // dumps only log errors unless a level is given. One-shot commands and
+    // configurations that fail validation log to stdout as text.
+    let log_level = cli
+        .log_level
+        .as_deref()
+        .or(cli.dump_config.map(|_| "error"));
+    let telemetry = match (&config_result, &report) {
+        (Ok(loaded), Some(report))
+            if !report.has_errors() && !cli.check_config && cli.dump_config.is_none() =>
+        {
+            loaded.config.telemetry.as_ref()
+        }
+        _ => None,
+    };
+    if let Err(err) = labman_telemetry::init(telemetry, log_level) {
         eprintln!("labmand: failed to initialise telemetry: {}", err);
         process::exit(1);
     }
 
-    let config_result: Result<LabmanConfig, LabmanError> = if let Some(ref path) = cli.config {
-        match load_from_path(&path) {
-            Ok(cfg) => {
-                tracing::info!("loaded configuration from {}", path.display());
-                Ok(cfg)
-            }
-            Err(err) => {
-                tracing::error!(
-                    "failed to load configuration from {}: {}",
-                    path.display(),
-                    err
-                );
-                Err(err)
+    let loaded = match (config_result, config_path.as_deref()) {
+        (Ok(loaded), _) => {
+            tracing::info!("loaded configuration from {}", loaded.files[0].display());
+            for drop_in in loaded.files.iter().skip(1) {
+                tracing::info!("applied configuration drop-in {}", drop_in.display());
             }
+            loaded
         }
-    } else {
-        match load_default() {
-            Ok(cfg) => {
-                tracing::info!("loaded configuration from default locations");
-                Ok(cfg)
-            }
-            Err(err) => {
-                tracing::error!("failed to load configuration from default locations: {err}");
-                Err(err)
-            }
+        (Err(err), Some(path)) => {
+            tracing::error!(
+                "failed to load configuration from {}: {}",
+                path.display(),
+                err
+            );
+            process::exit(1);
         }
-    };
-
-    let config = match config_result {
-        Ok(cfg) => cfg,
-        Err(_) => {
-            // Error already printed above; exit with a non-zero status code.
+        (Err(err), None) => {
+            tracing::error!("failed to load configuration from default locations: {err}");
             process::exit(1);
         }
     };
 
-    // Perform structural validation before any further processing.
-    if let Err(err) = config.validate() {
-        tracing::error!("configuration validation failed: {}", err);
-        process::exit(1);
+    if let Some(format) = cli.dump_config {
+        let dump = match format {
+            DumpFormat::Toml => loaded.to_toml(),
+            DumpFormat::Json => loaded.to_json().map(|json| json + "\n"),
+        };
+        match dump {
+            Ok(dump) => print!("{}", dump),
+            Err(err) => {
+                tracing::error!("failed to dump configuration: {}", err);
+                process::exit(1);
+            }
+        }
+        return;
     }
 
+    // Check the whole configuration before any further processing.
+    let report = report.expect("validated after loading");
     if cli.check_config {
-        // Configuration loaded and validated successfully; exit cleanly.
-        tracing::info!("configuration is valid");
-        return;
+        print_validation_report(&report, &loaded.sources);
+        process::exit(if report.has_errors() { 1 } else { 0 });
+    }
+    for issue in report.notes() {
+        tracing::info!("configuration {}", issue);
     }
+    for issue in report.warnings() {
+        tracing::warn!("configuration {}", issue);
+    }
+    if report.has_errors() {
+        for issue in report.errors() {
+            tracing::error!("configuration {}", issue);
+        }
+        tracing::error!("configuration validation failed; run with --check-config for details");
+        process::exit(1);
+    }
+    let LoadedConfig {
+        config, sources, ..
+    } = loaded;
 
     if cli.print_config {
         tracing::info!("starting labmand with loaded configuration");
-        print_config_summary(&config);
+        print_config_summary(&config, &sources);
         // For now we just exit after printing.
         // Note: printing config does not currently start the HTTP server.
     }
@@ -158,7 +248,7 @@ fn main() {
     let registry_config = config.clone();
 
     // Use a Tokio runtime to run the HTTP server and background tasks to completion.
-    if let Err(err) = run_server_blocking(bind_addr, registry_config) {
+    if let Err(err) = run_server_blocking(bind_addr, registry_config, config_path) {
         tracing::error!("labman HTTP server terminated with error: {}", err);
         process::exit(1);
     }
@@ -168,8 +258,9 @@ fn main() {
 ///
 /// Priority:
 /// 1. `--bind-addr` CLI flag if provided.
-/// 2. `[telemetry].metrics_port` from configuration, bound on 0.0.0.0.
-///    (In later stages, this may be refined to prefer the WireGuard address.)
+/// 2. `[telemetry].metrics_addr` and `metrics_port` from configuration,
+///    bound on 0.0.0.0 unless an address is configured. (In later stages,
+///    this may be refined to prefer the WireGuard address.)
 fn resolve_bind_addr(cli: &Cli, cfg: &LabmanConfig) -> Result<SocketAddr, String> {
     if let Some(addr_str) = cli.bind_addr.as_deref() {
         return addr_str
@@ -177,8 +268,8 @@ fn resolve_bind_addr(cli: &Cli, cfg: &LabmanConfig) -> Result<SocketAddr, String
             .map_err(|e| format!("failed to parse --bind-addr '{}': {}", addr_str, e));
     }
 
-    // Fallback: use metrics_port from config, bind on all interfaces, but only
-    // if metrics are not explicitly disabled. This allows:
+    // Fallback: use metrics_addr/metrics_port from config, by default on all
+    // interfaces, but only if metrics are not explicitly disabled. This allows:
     // - Control plane to reach the node over WireGuard (if routing allows).
     // - Operators to scrape from their network, subject to firewall config.
     //
@@ -203,7 +294,23 @@ fn resolve_bind_addr(cli: &Cli, cfg: &LabmanConfig) -> Result<SocketAddr, String
         .map(|t| t.metrics_port)
         .unwrap_or(9090);
 
-    Ok(SocketAddr::from(([0, 0, 0, 0], port)))
+    let ip = match cfg
+        .telemetry
+        .as_ref()
+        .and_then(|t| t.metrics_addr.as_deref())
+    {
+        Some(addr) => parse_ip(addr, "telemetry.metrics_addr")?,
+        None => IpAddr::from([0, 0, 0, 0]),
+    };
+
+    Ok(SocketAddr::new(ip, port))
+}
+
+/// Parse a listener IP address from the configuration.
+fn parse_ip(addr: &str, field: &str) -> Result<IpAddr, String> {
+    addr.trim()
+        .parse::<IpAddr>()
+        .map_err(|e| format!("failed to parse {} '{}': {}", field, addr, e))
 }
 
 /// Run the labman HTTP server and proxy using a Tokio runtime.
@@ -213,6 +320,7 @@ fn resolve_bind_addr(cli: &Cli, cfg: &LabmanConfig) -> Result<SocketAddr, String
 fn run_server_blocking(
     bind_addr: SocketAddr,
     config: LabmanConfig,
+    config_path: Option<PathBuf>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     tracing::debug!("run_server_blocking: constructing Tokio runtime");
     let rt = tokio::runtime::Builder::new_multi_thread()
@@ -252,8 +360,7 @@ fn run_server_blocking(
             }
             Err(err) => {
                 tracing::error!("failed to build endpoint registry from config: {}", err);
-                return Err::<(), Box<dyn std::error::Error>>(Box::new(std::io::Error::new(
-                    std::io::ErrorKind::Other,
+                return Err::<(), Box<dyn std::error::Error>>(Box::new(std::io::Error::other(
                     err.to_string(),
                 )));
             }
@@ -266,44 +373,65 @@ fn run_server_blocking(
         // components (proxy, control-plane reporting) can rely on basic health
         // status, followed by an initial model discovery pass so that routing
         // decisions and capability reporting have model information.
-        {
-            let mut guard = registry.lock().await;
-            if let Err(err) = guard.health_check_all_http().await {
-                tracing::error!("initial endpoint HTTP health check failed: {}", err);
-                return Err::<(), Box<dyn std::error::Error>>(Box::new(std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    err.to_string(),
-                )));
-            }
+        if let Err(err) = EndpointRegistry::health_check_all_http(&registry).await {
+            tracing::error!("initial endpoint HTTP health check failed: {}", err);
+            return Err::<(), Box<dyn std::error::Error>>(Box::new(std::io::Error::other(
+                err.to_string(),
+            )));
+        }
 
-            if let Err(err) = guard.discover_models_all_http().await {
-                tracing::error!("initial endpoint model discovery failed: {}", err);
-                return Err::<(), Box<dyn std::error::Error>>(Box::new(std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    err.to_string(),
-                )));
-            }
+        if let Err(err) = EndpointRegistry::discover_models_all_http(&registry).await {
+            tracing::error!("initial endpoint model discovery failed: {}", err);
+            return Err::<(), Box<dyn std::error::Error>>(Box::new(std::io::Error::other(
+                err.to_string(),
+            )));
         }
 
-        // Spawn periodic health checks. For now we use a fixed interval; later
-        // this can be made configurable.
+        // Spawn periodic health checks and model discovery on the intervals
+        // from `[health]`.
         EndpointRegistry::spawn_periodic_health_check(
             registry.clone(),
-            Duration::from_secs(30),
-            async {
-                // Shutdown is currently tied to server lifetime; when the
-                // server future completes, this future will also complete.
-            },
+            Duration::from_secs(config.health.interval_secs),
+            Duration::from_secs(config.health.discovery_interval_secs),
+            // Shutdown is currently tied to process lifetime; the task is
+            // dropped together with the runtime.
+            std::future::pending(),
         );
 
-        // Derive proxy listen address from configuration. For now we bind on
-        // 127.0.0.1 and use the configured proxy.listen_port so that the proxy
-        // is reachable locally even before WireGuard integration is complete.
-        let proxy_port = config.proxy.listen_port;
-        let proxy_addr = SocketAddr::from(([127, 0, 0, 1], proxy_port));
+        // Scrape runtime-reported load (vLLM, llama.cpp) so that routing and
+        // capability reporting account for traffic from other clients.
+        EndpointRegistry::spawn_periodic_load_scrape(registry.clone(), std::future::pending());
+
+        // Opt-in synthetic probes catch models that crashed behind a live
+        // HTTP server; endpoints without `probe` configured are skipped.
+        EndpointRegistry::spawn_periodic_probes(registry.clone(), std::future::pending());
+
+        // Apply endpoint changes from the configuration file on SIGHUP or
+        // when the file changes, without restarting.
+        match config_path {
+            Some(path) => {
+                if let Err(err) =
+                    reload::spawn_config_reloader(path, config.clone(), registry.clone())
+                {
+                    tracing::warn!("configuration reload disabled: {}", err);
+                }
+            }
+            None => tracing::warn!("configuration file path unknown; reload disabled"),
+        }
+
+        // Derive proxy listen address from configuration. Unless
+        // proxy.listen_addr is set we bind on 127.0.0.1 so that the proxy is
+        // reachable locally even before WireGuard integration is complete.
+        let proxy_ip = match config.proxy.listen_addr.as_deref() {
+            Some(addr) => parse_ip(addr, "proxy.listen_addr").map_err(std::io::Error::other)?,
+            None => IpAddr::from([127, 0, 0, 1]),
+        };
+        let proxy_addr = SocketAddr::new(proxy_ip, config.proxy.listen_port);
 
         let proxy_cfg = LabmanProxyConfig {
             listen_addr: proxy_addr,
+            // The control plane authenticates with the node token it issued.
+            catalog_token: Some(config.control_plane.node_token.expose().to_string()),
         };
 
         // Build a proxy server using the shared EndpointRegistry so that
@@ -313,10 +441,11 @@ fn run_server_blocking(
 
         tracing::info!("starting labman proxy server on {}", proxy_addr);
 
-        // For now, bind the Portman WebSocket listener to a fixed loopback
-        // address. This will later become configurable, but the initial
-        // implementation keeps it local-only and simple.
-        let portman_ws_addr = SocketAddr::from(([127, 0, 0, 1], 9100));
+        // Bind the Portman WebSocket listener from `[portman]`; validation
+        // keeps it on a loopback or LAN address.
+        let portman_ip = parse_ip(&config.portman.listen_addr, "portman.listen_addr")
+            .map_err(std::io::Error::other)?;
+        let portman_ws_addr = SocketAddr::new(portman_ip, config.portman.listen_port);
         let portman_ws_cfg = PortmanWsConfig {
             bind_addr: portman_ws_addr,
         };
@@ -351,23 +480,20 @@ fn run_server_blocking(
                     Ok(Ok(())) => {
                         // HTTP server exited cleanly, which is unexpected in normal operation.
                         let _ = shutdown_tx.send(());
-                        return Err::<(), Box<dyn std::error::Error>>(Box::new(std::io::Error::new(
-                            std::io::ErrorKind::Other,
+                        return Err::<(), Box<dyn std::error::Error>>(Box::new(std::io::Error::other(
                             "labman HTTP server exited unexpectedly",
                         )));
                     }
                     Ok(Err(e)) => {
                         tracing::error!("labman HTTP server error: {}", e);
                         let _ = shutdown_tx.send(());
-                        return Err::<(), Box<dyn std::error::Error>>(Box::new(std::io::Error::new(
-                            std::io::ErrorKind::Other,
+                        return Err::<(), Box<dyn std::error::Error>>(Box::new(std::io::Error::other(
                             format!("labman HTTP server error: {}", e),
                         )));
                     }
                     Err(join_err) => {
                         let _ = shutdown_tx.send(());
-                        return Err::<(), Box<dyn std::error::Error>>(Box::new(std::io::Error::new(
-                            std::io::ErrorKind::Other,
+                        return Err::<(), Box<dyn std::error::Error>>(Box::new(std::io::Error::other(
                             format!("labman-server join error: {}", join_err),
                         )));
                     }
@@ -382,15 +508,13 @@ fn run_server_blocking(
                     }
                     Ok(Err(e)) => {
                         let _ = shutdown_tx.send(());
-                        return Err::<(), Box<dyn std::error::Error>>(Box::new(std::io::Error::new(
-                            std::io::ErrorKind::Other,
+                        return Err::<(), Box<dyn std::error::Error>>(Box::new(std::io::Error::other(
                             format!("labman-proxy error: {}", e),
                         )));
                     }
                     Err(join_err) => {
                         let _ = shutdown_tx.send(());
-                        return Err::<(), Box<dyn std::error::Error>>(Box::new(std::io::Error::new(
-                            std::io::ErrorKind::Other,
+                        return Err::<(), Box<dyn std::error::Error>>(Box::new(std::io::Error::other(
                             format!("labman-proxy join error: {}", join_err),
                         )));
                     }
@@ -404,23 +528,20 @@ fn run_server_blocking(
                         // the Portman WS server has exited unexpectedly.
                         tracing::error!("Portman WS server exited unexpectedly");
                         let _ = shutdown_tx.send(());
-                        return Err::<(), Box<dyn std::error::Error>>(Box::new(std::io::Error::new(
-                            std::io::ErrorKind::Other,
+                        return Err::<(), Box<dyn std::error::Error>>(Box::new(std::io::Error::other(
                             "Portman WS server exited unexpectedly",
                         )));
                     }
                     Ok(Err(e)) => {
                         tracing::error!("Portman WS server error: {}", e);
                         let _ = shutdown_tx.send(());
-                        return Err::<(), Box<dyn std::error::Error>>(Box::new(std::io::Error::new(
-                            std::io::ErrorKind::Other,
+                        return Err::<(), Box<dyn std::error::Error>>(Box::new(std::io::Error::other(
                             format!("Portman WS server error: {}", e),
                         )));
                     }
                     Err(join_err) => {
                         let _ = shutdown_tx.send(());
-                        return Err::<(), Box<dyn std::error::Error>>(Box::new(std::io::Error::new(
-                            std::io::ErrorKind::Other,
+                        return Err::<(), Box<dyn std::error::Error>>(Box::new(std::io::Error::other(
                             format!("Portman WS server join error: {}", join_err),
                         )));
                     }
@@ -447,7 +568,7 @@ fn run_server_blocking(
 ///
 /// This is intentionally minimal for now; future stages can expand it or
 /// replace it with structured logging.
-fn print_config_summary(cfg: &LabmanConfig) {
+fn print_config_summary(cfg: &LabmanConfig, sources: &labman_config::ConfigSources) {
     println!("labmand configuration summary:");
     println!("  control_plane.base_url = {}", cfg.control_plane.base_url);
     println!(
@@ -458,32 +579,40 @@ fn print_config_summary(cfg: &LabmanConfig) {
         "  control_plane.description = {}",
         cfg.control_plane.description.as_deref().unwrap_or("-")
     );
+    println!(
+        "  control_plane.heartbeat_interval_secs = {}{}",
+        cfg.control_plane.heartbeat_interval_secs,
+        provenance(sources, "control_plane.heartbeat_interval_secs")
+    );
 
     println!(
         "  wireguard.interface_name = {}",
         cfg.wireguard.interface_name
     );
     println!(
-        "  wireguard.address        = {}",
+        "  wireguard.address        = {}{}",
         cfg.wireguard
             .address
             .as_deref()
-            .unwrap_or("<not set; may be provided by control plane>")
+            .unwrap_or("<not set; may be provided by control plane>"),
+        provenance(sources, "wireguard.address")
     );
     println!(
-        "  wireguard.peer_endpoint  = {}",
+        "  wireguard.peer_endpoint  = {}{}",
         cfg.wireguard
             .peer_endpoint
             .as_deref()
-            .unwrap_or("<not set>")
+            .unwrap_or("<not set>"),
+        provenance(sources, "wireguard.peer_endpoint")
     );
     println!(
-        "  wireguard.allowed_ips    = [{}]",
+        "  wireguard.allowed_ips    = [{}]{}",
         if cfg.wireguard.allowed_ips.is_empty() {
             String::from("<none>")
         } else {
             cfg.wireguard.allowed_ips.join(", ")
-        }
+        },
+        provenance(sources, "wireguard.allowed_ips")
     );
 
     println!("  proxy.listen_port        = {}", cfg.proxy.listen_port);
@@ -492,7 +621,15 @@ fn print_config_summary(cfg: &LabmanConfig) {
         cfg.proxy
             .listen_addr
             .as_deref()
-            .unwrap_or("<default (WG addr)>")
+            .unwrap_or("<default (127.0.0.1)>")
+    );
+    println!(
+        "  portman.listen           = {}:{}",
+        cfg.portman.listen_addr, cfg.portman.listen_port
+    );
+    println!(
+        "  health                   = every {}s, discovery every {}s, timeout {}s",
+        cfg.health.interval_secs, cfg.health.discovery_interval_secs, cfg.health.timeout_secs
     );
 
     println!("  endpoints:");
@@ -502,6 +639,7 @@ fn print_config_summary(cfg: &LabmanConfig) {
         for ep in &cfg.endpoints {
             println!("    - name        = {}", ep.name);
             println!("      base_url    = {}", ep.base_url);
+            println!("      kind        = {:?}", ep.kind);
             if let Some(max) = ep.max_concurrent {
                 println!("      max_concurrent = {}", max);
             } else {
@@ -521,4 +659,50 @@ fn print_config_summary(cfg: &LabmanConfig) {
             }
         }
     }
+
+    println!("  value sources:");
+    for (key, file) in sources.iter() {
+        println!(
+            "    {} <- {}{}",
+            key,
+            file.display(),
+            provenance(sources, key)
+        );
+    }
+}
+
+/// Marker for values supplied by the control plane rather than the operator.
+fn provenance(sources: &labman_config::ConfigSources, key: &str) -> &'static str {
+    if sources.from_control_plane(key) {
+        " (control plane)"
+    } else {
+        ""
+    }
+}
+
+/// Print every validation issue for `--check-config`, with the file that
+/// set the offending value when it is known.
+fn print_validation_report(
+    report: &labman_config::ValidationReport,
+    sources: &labman_config::ConfigSources,
+) {
+    for issue in &report.issues {
+        println!("{}", issue);
+        if let Some(file) = sources.find(&issue.path) {
+            println!("    set in {}", file.display());
+        }
+    }
+
+    let errors = report.errors().count();
+    let warnings = report.warnings().count();
+    if errors > 0 {
+        println!(
+            "configuration is invalid: {} error(s), {} warning(s)",
+            errors, warnings
+        );
+    } else if warnings > 0 {
+        println!("configuration is valid, with {} warning(s)", warnings);
+    } else {
+        println!("configuration is valid");
+    }
 }
@@ -0,0 +1,151 @@
+//! Configuration hot reload.
+//!
+//! `labmand` re-reads its configuration on `SIGHUP` and whenever the base
+//! file or one of its `conf.d` drop-ins is added, removed or modified. The
+//! new configuration is loaded and validated like at startup, compared with
+//! the running configuration, and endpoint and `[[models]]` changes are
+//! applied to the live `EndpointRegistry` without interrupting in-flight
+//! requests or Portman sessions.
+//!
+//! Sections that are only read at startup (`control_plane`, `wireguard`,
+//! `proxy`, `portman`, `telemetry`, `health`) are not reloaded: changes to
+//! them are logged and kept out of the running configuration, so they are
+//! reported again on every reload until the daemon is restarted. Settings
+//! supplied by the control plane are laid over the reloaded configuration
+//! like at startup, so they do not show up as changes.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use labman_config::{config_files, load_layered, ConfigDiff, LabmanConfig};
+use labman_core::LabmanError;
+use labman_endpoints::EndpointRegistry;
+use tokio::signal::unix::{signal, SignalKind};
+
+/// How often the configuration files' modification times are checked.
+pub(crate) const FILE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Spawn the reload task for the configuration at `path`.
+///
+/// `running` is the configuration the daemon was started with.
+pub(crate) fn spawn_config_reloader(
+    path: PathBuf,
+    running: LabmanConfig,
+    registry: Arc<tokio::sync::Mutex<EndpointRegistry>>,
+) -> std::io::Result<()> {
+    let mut hangup = signal(SignalKind::hangup())?;
+
+    tokio::spawn(async move {
+        let mut running = running;
+        let mut last_modified = modified(&path);
+        let mut ticker = tokio::time::interval(FILE_POLL_INTERVAL);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            let trigger = tokio::select! {
+                _ = hangup.recv() => "SIGHUP",
+                _ = ticker.tick() => {
+                    if modified(&path) == last_modified {
+                        continue;
+                    }
+                    "file change"
+                }
+            };
+            // An edit followed by SIGHUP should only reload once.
+            last_modified = modified(&path);
+
+            tracing::info!(
+                "reloading configuration from {} ({})",
+                path.display(),
+                trigger
+            );
+            match reload(&path, &mut running, &registry).await {
+                Ok(diff) if diff.is_empty() => {
+                    tracing::info!("configuration reload: no changes");
+                }
+                Ok(diff) => log_diff(&diff),
+                Err(err) => {
+                    tracing::error!(
+                        "configuration reload failed, keeping the running configuration: {}",
+                        err
+                    );
+                }
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Load, validate and apply the configuration at `path`.
+///
+/// On success `running` is updated to the configuration now in effect.
+async fn reload(
+    path: &Path,
+    running: &mut LabmanConfig,
+    registry: &Arc<tokio::sync::Mutex<EndpointRegistry>>,
+) -> Result<ConfigDiff, LabmanError> {
+    let mut loaded = load_layered(path)?;
+    loaded.apply_control_plane_state();
+    let report = loaded.validation_report();
+    for issue in report.warnings() {
+        tracing::warn!("configuration {}", issue);
+    }
+    report.into_result()?;
+    let new = loaded.config;
+
+    let diff = running.diff(&new);
+    if !diff.has_live_changes() {
+        return Ok(diff);
+    }
+
+    let effective = running.reloaded(&new);
+    registry.lock().await.apply_config(&effective)?;
+    *running = effective;
+
+    // Check new endpoints right away and re-run discovery so that filter
+    // and alias changes take effect without waiting for the next tick. The
+    // registry is only locked briefly, so proxied traffic keeps flowing
+    // while upstreams are queried.
+    if let Err(err) = EndpointRegistry::health_check_all_http(registry).await {
+        tracing::warn!("endpoint health check after reload failed: {}", err);
+    } else if let Err(err) = EndpointRegistry::discover_models_all_http(registry).await {
+        tracing::warn!("model discovery after reload failed: {}", err);
+    }
+
+    Ok(diff)
+}
+
+fn log_diff(diff: &ConfigDiff) {
+    for name in &diff.endpoints_added {
+        tracing::info!("configuration reload: added endpoint '{}'", name);
+    }
+    for name in &diff.endpoints_removed {
+        tracing::info!("configuration reload: removed endpoint '{}'", name);
+    }
+    for name in &diff.endpoints_changed {
+        tracing::info!("configuration reload: updated endpoint '{}'", name);
+    }
+    if diff.models_changed {
+        tracing::info!("configuration reload: updated [[models]]");
+    }
+    for section in &diff.restart_required {
+        tracing::warn!(
+            "configuration reload: changes to [{}] require a restart and were not applied",
+            section
+        );
+    }
+}
+
+/// Modification times of the base file and its drop-ins.
+fn modified(path: &Path) -> Vec<(PathBuf, Option<SystemTime>)> {
+    config_files(path)
+        .unwrap_or_else(|_| vec![path.to_path_buf()])
+        .into_iter()
+        .map(|file| {
+            let mtime = std::fs::metadata(&file).and_then(|m| m.modified()).ok();
+            (file, mtime)
+        })
+        .collect()
+}
@@ -0,0 +1,209 @@
+//! Canonical form of endpoint base URLs.
+//!
+//! Operators write the same endpoint in many ways: `http://host:11434`,
+//! `HTTP://Host:11434/`, `http://host:11434/v1/`. All of them name the same
+//! OpenAI-compatible API, and since the v0 slug scheme hashes the base URL
+//! they must also produce the same model slugs. The loaders therefore rewrite
+//! every `base_url` to a canonical form and report the rewrite:
+//!
+//! - the scheme and host are lowercased and default ports (`:80` for
+//!   `http`, `:443` for `https`) are dropped;
+//! - duplicate and trailing slashes are removed from the path;
+//! - `/v1` is appended unless the path already has a `v1` segment.
+//!
+//! `unix://` URLs keep their socket path as written and get the same
+//! treatment for the HTTP path after the socket, e.g.
+//! `unix:///run/ollama.sock` becomes `unix:///run/ollama.sock:/v1`.
+
+use labman_core::endpoint::{parse_unix_base_url, UNIX_SOCKET_SCHEME};
+
+use crate::validation::endpoint_path;
+use crate::{LabmanConfig, ValidationIssue, ValidationReport};
+
+/// Canonical form of an endpoint `base_url`, or why it can't be used.
+///
+/// ```rust
+/// use labman_config::normalize_base_url;
+///
+/// assert_eq!(
+///     normalize_base_url("HTTP://GPU-Box:80//").unwrap(),
+///     "http://gpu-box/v1"
+/// );
+/// assert_eq!(
+///     normalize_base_url("https://llm.example.com/openai/v1/").unwrap(),
+///     "https://llm.example.com/openai/v1"
+/// );
+/// assert!(normalize_base_url("ftp://host").is_err());
+/// ```
+pub fn normalize_base_url(raw: &str) -> std::result::Result<String, String> {
+    let url = raw.trim();
+    if url.is_empty() {
+        return Err("must not be empty".to_string());
+    }
+
+    if let Some(rest) = strip_scheme(url, UNIX_SOCKET_SCHEME) {
+        let unix = format!("{}{}", UNIX_SOCKET_SCHEME, rest);
+        return match parse_unix_base_url(&unix) {
+            Some((socket_path, path)) if socket_path.starts_with('/') => Ok(format!(
+                "{}{}:{}",
+                UNIX_SOCKET_SCHEME,
+                socket_path,
+                normalize_path(path)
+            )),
+            _ => Err(format!(
+                "must be of the form unix:///path/to.sock:/v1 (got '{}')",
+                url
+            )),
+        };
+    }
+
+    let (scheme, default_port, rest) = if let Some(rest) = strip_scheme(url, "http://") {
+        ("http", ":80", rest)
+    } else if let Some(rest) = strip_scheme(url, "https://") {
+        ("https", ":443", rest)
+    } else {
+        return Err("must start with http://, https:// or unix://".to_string());
+    };
+
+    if rest.contains(['?', '#']) {
+        return Err(format!(
+            "must not contain a query or fragment (got '{}')",
+            url
+        ));
+    }
+
+    let (authority, path) = match rest.find('/') {
+        Some(idx) => rest.split_at(idx),
+        None => (rest, ""),
+    };
+    // User info, if any, is case-sensitive; only the host is lowercased.
+    let (userinfo, host) = match authority.rsplit_once('@') {
+        Some((userinfo, host)) => (format!("{}@", userinfo), host),
+        None => (String::new(), authority),
+    };
+    let host = host.to_ascii_lowercase();
+    let host = host.strip_suffix(default_port).unwrap_or(&host);
+    if host.is_empty() || host.starts_with(':') {
+        return Err(format!("is missing a host (got '{}')", url));
+    }
+
+    Ok(format!(
+        "{}://{}{}{}",
+        scheme,
+        userinfo,
+        host,
+        normalize_path(path)
+    ))
+}
+
+impl LabmanConfig {
+    /// Rewrite every endpoint `base_url` to its canonical form (see
+    /// [`normalize_base_url`]), returning a note for each rewrite.
+    ///
+    /// Unusable URLs are left alone for [`validate`](Self::validate) to
+    /// report.
+    pub fn normalize_base_urls(&mut self) -> Vec<ValidationIssue> {
+        let mut report = ValidationReport::default();
+        for (index, ep) in self.endpoints.iter_mut().enumerate() {
+            let Ok(normalized) = normalize_base_url(&ep.base_url) else {
+                continue;
+            };
+            if normalized != ep.base_url {
+                report.info(
+                    format!("{}.base_url", endpoint_path(ep, index)),
+                    format!("normalised '{}' to '{}'", ep.base_url, normalized),
+                );
+                ep.base_url = normalized;
+            }
+        }
+        report.issues
+    }
+}
+
+fn strip_scheme<'a>(url: &'a str, scheme: &str) -> Option<&'a str> {
+    url.get(..scheme.len())
+        .filter(|prefix| prefix.eq_ignore_ascii_case(scheme))
+        .map(|_| &url[scheme.len()..])
+}
+
+/// Collapse slashes and make sure the path has a `v1` segment.
+fn normalize_path(path: &str) -> String {
+    let mut segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    if !segments.contains(&"v1") {
+        segments.push("v1");
+    }
+    format!("/{}", segments.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EndpointConfig;
+
+    #[test]
+    fn equivalent_urls_normalise_to_the_same_value() {
+        for raw in [
+            "http://gpu-box:11434",
+            " http://gpu-box:11434/ ",
+            "HTTP://GPU-BOX:11434/v1/",
+            "http://gpu-box:11434//v1",
+        ] {
+            assert_eq!(
+                normalize_base_url(raw).as_deref(),
+                Ok("http://gpu-box:11434/v1"),
+                "{}",
+                raw
+            );
+        }
+
+        assert_eq!(
+            normalize_base_url("https://Key@LLM.example.com:443/openai").as_deref(),
+            Ok("https://Key@llm.example.com/openai/v1")
+        );
+        assert_eq!(
+            normalize_base_url("http://[::1]:8000/v1/engines/").as_deref(),
+            Ok("http://[::1]:8000/v1/engines")
+        );
+        assert_eq!(
+            normalize_base_url("unix:///run/Ollama.sock").as_deref(),
+            Ok("unix:///run/Ollama.sock:/v1")
+        );
+        assert_eq!(
+            normalize_base_url("unix:///run/vllm.sock:/v1/").as_deref(),
+            Ok("unix:///run/vllm.sock:/v1")
+        );
+
+        for invalid in [
+            "",
+            "ftp://host",
+            "http://",
+            "http://:8000",
+            "http://host/v1?x=1",
+            "unix://relative.sock",
+        ] {
+            assert!(normalize_base_url(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn config_rewrites_are_reported() {
+        let mut cfg = crate::tests::test_config();
+        for (name, base_url) in [
+            ("a", "http://127.0.0.1:8000/v1"),
+            ("b", "http://127.0.0.1:11434/"),
+            ("c", "gopher://x"),
+        ] {
+            cfg.endpoints.push(EndpointConfig {
+                name: name.to_string(),
+                base_url: base_url.to_string(),
+                ..Default::default()
+            });
+        }
+
+        let notes = cfg.normalize_base_urls();
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].path, "endpoints[b].base_url");
+        assert_eq!(cfg.endpoints[1].base_url, "http://127.0.0.1:11434/v1");
+        assert_eq!(cfg.endpoints[2].base_url, "gopher://x");
+    }
+}
@@ -0,0 +1,156 @@
+//! Differences between two configurations, for reloading `labmand` in place.
+//!
+//! Endpoints and `[[models]]` can be applied to a running registry; the
+//! control-plane identity, the WireGuard tunnel, the proxy and Portman
+//! listeners, telemetry and the health check timings are set up once at
+//! startup and only change on restart.
+
+use crate::LabmanConfig;
+
+/// What changed between a running configuration and a reloaded one.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigDiff {
+    /// Endpoints present only in the new configuration.
+    pub endpoints_added: Vec<String>,
+
+    /// Endpoints present only in the running configuration.
+    pub endpoints_removed: Vec<String>,
+
+    /// Endpoints present in both whose settings differ.
+    pub endpoints_changed: Vec<String>,
+
+    /// Whether the `[[models]]` aliases and overrides differ.
+    pub models_changed: bool,
+
+    /// Top-level sections that differ but can only change on restart
+    /// (`control_plane`, `wireguard`, `proxy`, `portman`, `telemetry`,
+    /// `health`).
+    pub restart_required: Vec<&'static str>,
+}
+
+impl ConfigDiff {
+    /// Whether anything at all changed.
+    pub fn is_empty(&self) -> bool {
+        !self.has_live_changes() && self.restart_required.is_empty()
+    }
+
+    /// Whether any change can be applied without a restart.
+    pub fn has_live_changes(&self) -> bool {
+        !self.endpoints_added.is_empty()
+            || !self.endpoints_removed.is_empty()
+            || !self.endpoints_changed.is_empty()
+            || self.models_changed
+    }
+}
+
+impl LabmanConfig {
+    /// Compare this (running) configuration with a reloaded one.
+    pub fn diff(&self, new: &LabmanConfig) -> ConfigDiff {
+        let mut diff = ConfigDiff::default();
+
+        for ep in &new.endpoints {
+            match self.endpoints.iter().find(|old| old.name == ep.name) {
+                None => diff.endpoints_added.push(ep.name.clone()),
+                Some(old) if old != ep => diff.endpoints_changed.push(ep.name.clone()),
+                Some(_) => {}
+            }
+        }
+        for old in &self.endpoints {
+            if !new.endpoints.iter().any(|ep| ep.name == old.name) {
+                diff.endpoints_removed.push(old.name.clone());
+            }
+        }
+        diff.models_changed = self.models != new.models;
+
+        if self.control_plane != new.control_plane {
+            diff.restart_required.push("control_plane");
+        }
+        if self.wireguard != new.wireguard {
+            diff.restart_required.push("wireguard");
+        }
+        if self.proxy != new.proxy {
+            diff.restart_required.push("proxy");
+        }
+        if self.portman != new.portman {
+            diff.restart_required.push("portman");
+        }
+        if self.telemetry != new.telemetry {
+            diff.restart_required.push("telemetry");
+        }
+        if self.health != new.health {
+            diff.restart_required.push("health");
+        }
+
+        diff
+    }
+
+    /// The configuration that is in effect after reloading `new` into a
+    /// daemon running with `self`: endpoints and models from `new`, the
+    /// restart-only sections unchanged.
+    pub fn reloaded(&self, new: &LabmanConfig) -> LabmanConfig {
+        LabmanConfig {
+            endpoints: new.endpoints.clone(),
+            models: new.models.clone(),
+            ..self.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EndpointConfig;
+
+    fn config(toml: &str) -> LabmanConfig {
+        let base = r#"
+[control_plane]
+base_url = "https://control.example.com/api/v1"
+node_token = "token"
+
+[wireguard]
+
+[proxy]
+listen_port = 8080
+"#;
+        toml::from_str(&format!("{}{}", base, toml)).expect("parse config")
+    }
+
+    #[test]
+    fn diff_separates_live_and_restart_only_changes() {
+        let running = config(
+            r#"
+[[endpoints]]
+name = "a"
+base_url = "http://127.0.0.1:8000/v1"
+
+[[endpoints]]
+name = "b"
+base_url = "http://127.0.0.1:8001/v1"
+"#,
+        );
+        assert!(running.diff(&running).is_empty());
+
+        let mut new = running.clone();
+        new.endpoints.retain(|ep| ep.name != "a");
+        new.endpoints[0].models_include = Some(vec!["llama*".to_string()]);
+        new.endpoints.push(EndpointConfig {
+            name: "c".to_string(),
+            base_url: "http://127.0.0.1:8002/v1".to_string(),
+            ..Default::default()
+        });
+        new.proxy.listen_port = 9000;
+        new.health.interval_secs = 10;
+
+        let diff = running.diff(&new);
+        assert_eq!(diff.endpoints_added, vec!["c"]);
+        assert_eq!(diff.endpoints_removed, vec!["a"]);
+        assert_eq!(diff.endpoints_changed, vec!["b"]);
+        assert!(!diff.models_changed);
+        assert_eq!(diff.restart_required, vec!["proxy", "health"]);
+        assert!(diff.has_live_changes());
+
+        let effective = running.reloaded(&new);
+        assert_eq!(effective.proxy.listen_port, 8080);
+        assert_eq!(effective.endpoints, new.endpoints);
+    }
+}
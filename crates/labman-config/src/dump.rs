@@ -0,0 +1,180 @@
+//! Dumps of the effective configuration.
+//!
+//! [`LoadedConfig::to_toml`] and [`LoadedConfig::to_json`] serialize the
+//! configuration a node actually runs with: drop-ins merged, defaults filled
+//! in, base URLs normalised and control-plane settings applied. Secrets are
+//! redacted (see [`Secret`](crate::Secret)), so a dump can be shared and
+//! diffed against what was deployed.
+//!
+//! Values supplied by the control plane are annotated with a comment in the
+//! TOML dump and listed under `control_plane_supplied` in the JSON dump.
+
+use labman_core::{LabmanError, Result};
+use serde_json::json;
+
+use crate::layers::KEYED_ARRAYS;
+use crate::LoadedConfig;
+
+impl LoadedConfig {
+    /// The effective configuration as TOML.
+    ///
+    /// A header lists the merged files, and every value supplied by the
+    /// control plane is preceded by a comment naming the state file.
+    pub fn to_toml(&self) -> Result<String> {
+        let body = toml::to_string_pretty(&self.config).map_err(|err| {
+            LabmanError::config(format!("failed to serialize configuration: {}", err))
+        })?;
+
+        let mut out = String::from(
+            "# Effective labmand configuration (defaults filled in, secrets redacted).\n",
+        );
+        for file in &self.files {
+            out.push_str(&format!("# Merged from {}\n", file.display()));
+        }
+        out.push('\n');
+
+        // Only keys of plain tables can come from the control plane, so
+        // `[[endpoints]]` and `[[models]]` entries are not annotated.
+        let mut table = Some(String::new());
+        for line in body.lines() {
+            if line.starts_with("[[") {
+                table = None;
+            } else if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                let section = header.split('.').next().unwrap_or(header);
+                table =
+                    (!KEYED_ARRAYS.iter().any(|(s, _)| *s == section)).then(|| header.to_string());
+            } else if let (Some(table), Some((key, _))) = (&table, line.split_once(" = ")) {
+                let path = if table.is_empty() {
+                    key.to_string()
+                } else {
+                    format!("{}.{}", table, key)
+                };
+                if self.sources.from_control_plane(&path) {
+                    if let Some(file) = self.sources.get(&path) {
+                        out.push_str(&format!(
+                            "# supplied by the control plane ({})\n",
+                            file.display()
+                        ));
+                    }
+                }
+            }
+            out.push_str(line);
+            out.push('\n');
+        }
+
+        Ok(out)
+    }
+
+    /// The effective configuration as pretty-printed JSON.
+    ///
+    /// The configuration itself is under `config`; `sources` maps each
+    /// value to the file that set it and `control_plane_supplied` lists the
+    /// values supplied by the control plane.
+    pub fn to_json(&self) -> Result<String> {
+        let sources: serde_json::Map<String, serde_json::Value> = self
+            .sources
+            .iter()
+            .map(|(key, file)| (key.to_string(), json!(file.display().to_string())))
+            .collect();
+        let supplied: Vec<&str> = self
+            .sources
+            .iter()
+            .map(|(key, _)| key)
+            .filter(|key| self.sources.from_control_plane(key))
+            .collect();
+
+        let dump = json!({
+            "config": self.config,
+            "files": self.files,
+            "sources": sources,
+            "control_plane_supplied": supplied,
+        });
+        serde_json::to_string_pretty(&dump).map_err(|err| {
+            LabmanError::config(format!("failed to serialize configuration: {}", err))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::{load_layered, ControlPlaneOverlay, LabmanConfig, REDACTED};
+
+    #[test]
+    fn dumps_are_complete_redacted_and_annotated() {
+        let dir = std::env::temp_dir().join(format!("labman-config-dump-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let base = dir.join("labman.toml");
+        fs::write(
+            &base,
+            r#"
+[control_plane]
+base_url = "https://control.example.com/api/v1"
+node_token = "super-secret-token"
+
+[wireguard]
+
+[proxy]
+listen_port = 8080
+
+[[endpoints]]
+name = "gpu-1"
+base_url = "http://127.0.0.1:8000"
+tenant = "acme"
+policy = { max_tokens = 4096 }
+
+[endpoints.schedule]
+windows = [{ days = ["mon"], start = "08:00", end = "18:00" }]
+
+[[models]]
+id = "llama"
+match = ["llama3*"]
+"#,
+        )
+        .unwrap();
+
+        let mut loaded = load_layered(&base).expect("load config");
+        let state_file = dir.join("control-plane.toml");
+        loaded.apply_overlay(
+            &ControlPlaneOverlay {
+                peer_endpoint: Some("control.example.com:51820".to_string()),
+                ..Default::default()
+            },
+            &state_file,
+        );
+
+        let toml_dump = loaded.to_toml().expect("toml dump");
+        assert!(!toml_dump.contains("super-secret-token"));
+        assert!(toml_dump.contains(&format!(
+            "# supplied by the control plane ({})\npeer_endpoint = ",
+            state_file.display()
+        )));
+
+        // Everything but the secret survives the round trip, defaults
+        // included.
+        let mut parsed: LabmanConfig = toml::from_str(&toml_dump).expect("parse toml dump");
+        assert_eq!(parsed.control_plane.node_token.expose(), REDACTED);
+        parsed.control_plane.node_token = loaded.config.control_plane.node_token.clone();
+        assert_eq!(parsed, loaded.config);
+        assert_eq!(parsed.endpoints[0].base_url, "http://127.0.0.1:8000/v1");
+        assert_eq!(parsed.health.interval_secs, 30);
+
+        let json_dump: serde_json::Value =
+            serde_json::from_str(&loaded.to_json().expect("json dump")).expect("parse json");
+        assert_eq!(json_dump["config"]["control_plane"]["node_token"], REDACTED);
+        assert_eq!(json_dump["config"]["endpoints"][0]["tenant"], "acme");
+        assert_eq!(
+            json_dump["control_plane_supplied"],
+            serde_json::json!(["wireguard.peer_endpoint"])
+        );
+        assert_eq!(
+            json_dump["sources"]["endpoints[gpu-1].tenant"],
+            base.display().to_string()
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,384 @@
+//! Layered configuration: a base file plus `conf.d` drop-ins.
+//!
+//! Next to the base file (e.g. `/etc/labman/labman.toml`) a `conf.d`
+//! directory may hold drop-in files (`/etc/labman/conf.d/*.toml`). They are
+//! applied on top of the base file in lexical order of their file names, so
+//! provisioning tools can manage one file per endpoint or concern.
+//!
+//! Merge rules:
+//! - Tables are merged key by key; a later file's value replaces an earlier
+//!   one.
+//! - Arrays replace each other as a whole, except for `[[endpoints]]` and
+//!   `[[models]]`: an entry whose `name` (endpoints) or `id` (models)
+//!   already exists is merged into the existing entry like a table, other
+//!   entries are appended.
+//!
+//! Files in `conf.d` that don't end in `.toml` or start with a `.` (editor
+//! swap files, disabled drop-ins) are ignored.
+//!
+//! For every effective value the file that set it last is recorded in
+//! [`ConfigSources`].
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use labman_core::{LabmanError, Result};
+use toml::{Table, Value};
+
+use crate::validation::unknown_keys;
+use crate::{LabmanConfig, ValidationIssue, ValidationReport};
+
+/// Name of the drop-in directory next to the base configuration file.
+pub const DROP_IN_DIR: &str = "conf.d";
+
+/// Array-of-tables sections merged by a key field rather than replaced.
+pub(crate) const KEYED_ARRAYS: &[(&str, &str)] = &[("endpoints", "name"), ("models", "id")];
+
+/// A configuration together with where its values came from.
+#[derive(Debug, Clone)]
+pub struct LoadedConfig {
+    /// The merged configuration.
+    pub config: LabmanConfig,
+
+    /// The files that were merged, in order (base file first).
+    pub files: Vec<PathBuf>,
+
+    /// Which file each value came from.
+    pub sources: ConfigSources,
+
+    /// Problems noticed while loading, such as unknown keys, and values
+    /// rewritten to their canonical form.
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl LoadedConfig {
+    /// Validate the configuration, including the problems found while
+    /// loading it.
+    pub fn validation_report(&self) -> ValidationReport {
+        let mut report = self.config.validation_report();
+        report.extend(self.issues.iter().cloned());
+        report
+    }
+}
+
+/// Origin of every value in a merged configuration.
+///
+/// Keys are dotted paths such as `proxy.listen_port`; entries of
+/// `[[endpoints]]` and `[[models]]` are addressed by their name or id, e.g.
+/// `endpoints[gpu-1].max_concurrent`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigSources {
+    values: BTreeMap<String, PathBuf>,
+
+    /// Keys whose value was supplied by the control plane.
+    supplied: BTreeSet<String>,
+}
+
+impl ConfigSources {
+    /// File that set the value at `key`.
+    pub fn get(&self, key: &str) -> Option<&Path> {
+        self.values.get(key).map(PathBuf::as_path)
+    }
+
+    /// File that set the value at `key` or, for elements of array values
+    /// such as `wireguard.allowed_ips[0]`, the enclosing value.
+    pub fn find(&self, key: &str) -> Option<&Path> {
+        let mut key = key;
+        loop {
+            if let Some(file) = self.get(key) {
+                return Some(file);
+            }
+            key = key.strip_suffix(']').and_then(|k| k.rsplit_once('['))?.0;
+        }
+    }
+
+    /// Whether the value at `key` was supplied by the control plane (see
+    /// [`ControlPlaneOverlay`](crate::ControlPlaneOverlay)) rather than set
+    /// by the operator.
+    pub fn from_control_plane(&self, key: &str) -> bool {
+        self.supplied.contains(key)
+    }
+
+    /// Record a value supplied by the control plane, read from `file`.
+    pub(crate) fn supply(&mut self, key: &str, file: &Path) {
+        self.values.insert(key.to_string(), file.to_path_buf());
+        self.supplied.insert(key.to_string());
+    }
+
+    /// All values and their files, ordered by key.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Path)> {
+        self.values.iter().map(|(k, v)| (k.as_str(), v.as_path()))
+    }
+}
+
+/// The base file followed by its drop-ins, in the order they are merged.
+pub fn config_files<P: AsRef<Path>>(path: P) -> Result<Vec<PathBuf>> {
+    let path = path.as_ref();
+    let mut files = vec![path.to_path_buf()];
+
+    let dir = path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join(DROP_IN_DIR);
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(files),
+        Err(err) => {
+            return Err(LabmanError::config(format!(
+                "failed to read drop-in directory '{}': {}",
+                dir.display(),
+                err
+            )))
+        }
+    };
+
+    let mut drop_ins: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| {
+            let name = p.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            !name.starts_with('.') && name.ends_with(".toml") && p.is_file()
+        })
+        .collect();
+    drop_ins.sort();
+    files.extend(drop_ins);
+
+    Ok(files)
+}
+
+/// Load the base file at `path` and merge its drop-ins onto it.
+pub fn load_layered<P: AsRef<Path>>(path: P) -> Result<LoadedConfig> {
+    let files = config_files(&path)?;
+
+    let mut merged = Table::new();
+    let mut sources = ConfigSources::default();
+    for file in &files {
+        let contents = fs::read_to_string(file).map_err(|err| {
+            LabmanError::config(format!(
+                "failed to read config file '{}': {}",
+                file.display(),
+                err
+            ))
+        })?;
+        let layer: Table = toml::from_str(&contents).map_err(|err| {
+            LabmanError::invalid_config(
+                file.display().to_string(),
+                format!("failed to parse config: {}", err),
+            )
+        })?;
+        merge_table(&mut merged, layer, "", file, &mut sources.values);
+    }
+
+    let mut issues = unknown_keys(&merged);
+    let mut config: LabmanConfig = Value::Table(merged).try_into().map_err(|err| {
+        let origin = if files.len() > 1 {
+            format!(
+                "{} (with {} drop-ins)",
+                path.as_ref().display(),
+                files.len() - 1
+            )
+        } else {
+            path.as_ref().display().to_string()
+        };
+        LabmanError::invalid_config(origin, format!("failed to parse config: {}", err))
+    })?;
+    config.resolve_secrets(path.as_ref().parent())?;
+    issues.extend(config.normalize_base_urls());
+
+    Ok(LoadedConfig {
+        config,
+        files,
+        sources,
+        issues,
+    })
+}
+
+fn merge_table(
+    target: &mut Table,
+    layer: Table,
+    prefix: &str,
+    file: &Path,
+    sources: &mut BTreeMap<String, PathBuf>,
+) {
+    for (key, value) in layer {
+        let path = join(prefix, &key);
+        let keyed = KEYED_ARRAYS
+            .iter()
+            .find(|(section, _)| prefix.is_empty() && *section == key)
+            .map(|(_, field)| *field);
+
+        match (target.get_mut(&key), value, keyed) {
+            (Some(Value::Table(existing)), Value::Table(table), _) => {
+                merge_table(existing, table, &path, file, sources);
+            }
+            (Some(Value::Array(existing)), Value::Array(items), Some(field)) => {
+                merge_keyed(existing, items, &key, field, file, sources);
+            }
+            (_, Value::Array(items), Some(field)) => {
+                let mut existing = Vec::new();
+                merge_keyed(&mut existing, items, &key, field, file, sources);
+                target.insert(key, Value::Array(existing));
+            }
+            (_, value, _) => {
+                forget(&path, sources);
+                record(&value, &path, file, sources);
+                target.insert(key, value);
+            }
+        }
+    }
+}
+
+fn merge_keyed(
+    existing: &mut Vec<Value>,
+    items: Vec<Value>,
+    section: &str,
+    field: &str,
+    file: &Path,
+    sources: &mut BTreeMap<String, PathBuf>,
+) {
+    for item in items {
+        let id = item
+            .get(field)
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| existing.len().to_string());
+        let path = format!("{}[{}]", section, id);
+
+        let current = existing
+            .iter_mut()
+            .find(|e| e.get(field).and_then(Value::as_str) == Some(id.as_str()));
+        match (current, item) {
+            (Some(Value::Table(current)), Value::Table(table)) => {
+                merge_table(current, table, &path, file, sources);
+            }
+            (_, item) => {
+                record(&item, &path, file, sources);
+                existing.push(item);
+            }
+        }
+    }
+}
+
+/// Record `file` as the source of `value` and everything nested in it.
+fn record(value: &Value, path: &str, file: &Path, sources: &mut BTreeMap<String, PathBuf>) {
+    match value {
+        Value::Table(table) => {
+            for (key, value) in table {
+                record(value, &join(path, key), file, sources);
+            }
+        }
+        _ => {
+            sources.insert(path.to_string(), file.to_path_buf());
+        }
+    }
+}
+
+/// Drop sources of a value (and its children) that is being replaced.
+fn forget(path: &str, sources: &mut BTreeMap<String, PathBuf>) {
+    let nested = format!("{}.", path);
+    sources.retain(|key, _| key != path && !key.starts_with(&nested));
+}
+
+fn join(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", prefix, key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drop_ins_merge_in_lexical_order() {
+        let dir = std::env::temp_dir().join(format!("labman-config-layers-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join(DROP_IN_DIR)).expect("create conf.d");
+
+        let base = dir.join("labman.toml");
+        fs::write(
+            &base,
+            r#"
+[control_plane]
+base_url = "https://control.example.com/api/v1"
+node_token = "token"
+
+[wireguard]
+allowed_ips = ["10.90.0.1/32"]
+
+[proxy]
+listen_port = 8080
+
+[[endpoints]]
+name = "gpu-1"
+base_url = "http://127.0.0.1:8000/v1"
+max_concurrent = 4
+"#,
+        )
+        .unwrap();
+        let drop_in = |name: &str, contents: &str| {
+            fs::write(dir.join(DROP_IN_DIR).join(name), contents).unwrap();
+        };
+        drop_in(
+            "20-gpu-1.toml",
+            "[[endpoints]]\nname = \"gpu-1\"\nmax_concurrent = 2\n",
+        );
+        drop_in(
+            "10-ollama.toml",
+            "[proxy]\nlisten_port = 8081\n\n[[endpoints]]\nname = \"ollama\"\nbase_url = \"http://127.0.0.1:11434/v1\"\n",
+        );
+        drop_in("30-region.toml", "[control_plane]\nregion = \"eu-west\"\n");
+        drop_in(".40-disabled.toml", "[proxy]\nlisten_port = 1\n");
+        drop_in("50-notes.txt", "not toml");
+
+        let loaded = load_layered(&base).expect("load layered config");
+        let file = |name: &str| dir.join(DROP_IN_DIR).join(name);
+
+        assert_eq!(
+            loaded.files,
+            vec![
+                base.clone(),
+                file("10-ollama.toml"),
+                file("20-gpu-1.toml"),
+                file("30-region.toml")
+            ]
+        );
+
+        let cfg = &loaded.config;
+        assert_eq!(cfg.proxy.listen_port, 8081);
+        assert_eq!(cfg.control_plane.region.as_deref(), Some("eu-west"));
+        assert_eq!(cfg.control_plane.node_token.expose(), "token");
+        let names: Vec<_> = cfg.endpoints.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["gpu-1", "ollama"]);
+        assert_eq!(cfg.endpoints[0].max_concurrent, Some(2));
+        assert_eq!(cfg.endpoints[0].base_url, "http://127.0.0.1:8000/v1");
+
+        let sources = &loaded.sources;
+        assert_eq!(
+            sources.get("proxy.listen_port"),
+            Some(file("10-ollama.toml").as_path())
+        );
+        assert_eq!(
+            sources.get("endpoints[gpu-1].max_concurrent"),
+            Some(file("20-gpu-1.toml").as_path())
+        );
+        assert_eq!(
+            sources.get("endpoints[gpu-1].base_url"),
+            Some(base.as_path())
+        );
+        assert_eq!(sources.get("wireguard.allowed_ips"), Some(base.as_path()));
+        assert_eq!(
+            sources.find("wireguard.allowed_ips[0]"),
+            Some(base.as_path())
+        );
+        assert_eq!(sources.find("endpoints[gpu-1].typo"), None);
+
+        // Without a conf.d directory only the base file is read.
+        fs::remove_dir_all(dir.join(DROP_IN_DIR)).unwrap();
+        assert_eq!(config_files(&base).unwrap(), vec![base.clone()]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
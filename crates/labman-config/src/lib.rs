@@ -2,7 +2,9 @@
 //!
 //! This crate is responsible for:
 //! - Defining the top-level configuration model used by the daemon and other crates
-//! - Loading configuration from TOML files
+//! - Loading configuration from TOML files, layered with `conf.d` drop-ins
+//! - Laying settings supplied by the control plane over the operator's
+//!   configuration, without overriding anything the operator set
 //! - Providing a simple default search strategy (e.g. /etc/labman/labman.toml, ./labman.toml)
 //!
 //! The goal is to keep this crate focused on configuration concerns and to avoid
@@ -15,18 +17,33 @@
 //! protocols. This crate focuses on the subset of configuration that should be
 //! static and operator‑managed.
 
-use std::fs;
 use std::path::{Path, PathBuf};
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use labman_core::{LabmanError, Result};
+use labman_core::{LabmanError, ModelCapability, Result, SlugScheme};
+
+mod base_url;
+mod diff;
+mod dump;
+mod layers;
+mod overlay;
+pub mod schema;
+mod secrets;
+mod validation;
+
+pub use base_url::normalize_base_url;
+pub use diff::ConfigDiff;
+pub use layers::{config_files, load_layered, ConfigSources, LoadedConfig, DROP_IN_DIR};
+pub use overlay::{ControlPlaneOverlay, DEFAULT_STATE_FILE};
+pub use secrets::{Secret, CREDENTIALS_DIRECTORY_ENV, NODE_TOKEN_CREDENTIAL, REDACTED};
+pub use validation::{Severity, ValidationIssue, ValidationReport};
 
 /// Root configuration struct for labman.
 ///
 /// This represents the operator‑supplied configuration that the daemon
 /// and related crates consume.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LabmanConfig {
     /// Control‑plane connectivity and identity configuration.
     pub control_plane: ControlPlaneConfig,
@@ -38,28 +55,30 @@ pub struct LabmanConfig {
     /// Proxy configuration for the local HTTP interface.
     pub proxy: ProxyConfig,
 
+    /// Listener for the local Portman WebSocket connection.
+    #[serde(default)]
+    pub portman: PortmanConfig,
+
     /// Telemetry configuration for logging and metrics.
     #[serde(default)]
     pub telemetry: Option<TelemetryConfig>,
 
+    /// Endpoint health check and model discovery timings.
+    #[serde(default)]
+    pub health: HealthConfig,
+
     /// Logical LLM endpoints this node can use.
     #[serde(default)]
     pub endpoints: Vec<EndpointConfig>,
-}
 
-impl LabmanConfig {
-    /// Perform basic structural validation of the configuration.
+    /// Operator-defined model aliases and metadata overrides.
     ///
-    /// This does not attempt to contact any external systems; it only checks
-    /// for obviously invalid or inconsistent values. More advanced validation
-    /// (e.g., control-plane reachability) belongs in higher-level crates.
-    pub fn validate(&self) -> Result<()> {
-        self.validate_control_plane()?;
-        self.validate_endpoints()?;
-        self.validate_wireguard()?;
-        Ok(())
-    }
+    /// In TOML these are `[[models]]` tables.
+    #[serde(default)]
+    pub models: Vec<ModelConfig>,
+}
 
+impl LabmanConfig {
     /// Build a `NodeInfo` from this configuration and the provided capabilities.
     ///
     /// This helper is intended for use by the control-plane client / daemon when
@@ -79,7 +98,7 @@ impl LabmanConfig {
         // The control plane is free to replace this ID in its response.
         let provisional_id = format!(
             "node-{}",
-            crate::short_token_fingerprint(&self.control_plane.node_token)
+            crate::short_token_fingerprint(self.control_plane.node_token.expose())
         );
 
         let mut info = labman_core::NodeInfo::new(provisional_id, capabilities);
@@ -93,120 +112,27 @@ impl LabmanConfig {
 
         info
     }
-
-    fn validate_control_plane(&self) -> Result<()> {
-        if self.control_plane.base_url.trim().is_empty() {
-            return Err(LabmanError::invalid_config(
-                "control_plane.base_url",
-                "control_plane.base_url must not be empty",
-            ));
-        }
-
-        if self.control_plane.node_token.trim().is_empty() {
-            return Err(LabmanError::invalid_config(
-                "control_plane.node_token",
-                "control_plane.node_token must not be empty",
-            ));
-        }
-
-        // Very lightweight check: URL should look like http(s)://...
-        let url = self.control_plane.base_url.trim();
-        if !(url.starts_with("http://") || url.starts_with("https://")) {
-            return Err(LabmanError::invalid_config(
-                "control_plane.base_url",
-                "control_plane.base_url must start with http:// or https://",
-            ));
-        }
-
-        Ok(())
-    }
-
-    fn validate_endpoints(&self) -> Result<()> {
-        // Check for duplicate endpoint names
-        let mut seen = std::collections::HashSet::new();
-        for ep in &self.endpoints {
-            if ep.name.trim().is_empty() {
-                return Err(LabmanError::invalid_config(
-                    "endpoints.name",
-                    "endpoint name must not be empty",
-                ));
-            }
-
-            if !seen.insert(ep.name.clone()) {
-                return Err(LabmanError::invalid_config(
-                    "endpoints.name",
-                    &format!("duplicate endpoint name: {}", ep.name),
-                ));
-            }
-
-            let base_url = ep.base_url.trim();
-            if base_url.is_empty() {
-                return Err(LabmanError::invalid_config(
-                    "endpoints.base_url",
-                    &format!("endpoint '{}' has an empty base_url", ep.name),
-                ));
-            }
-
-            if !(base_url.starts_with("http://") || base_url.starts_with("https://")) {
-                return Err(LabmanError::invalid_config(
-                    "endpoints.base_url",
-                    &format!(
-                        "endpoint '{}' base_url must start with http:// or https://",
-                        ep.name
-                    ),
-                ));
-            }
-
-            // Allow base URLs that either end with `/v1` or can be normalized to it.
-            if !(base_url.ends_with("/v1") || base_url.contains("/v1/")) {
-                // We do not modify here, just warn via error message context.
-                // Normalisation logic, if any, should live in a higher layer.
-                return Err(LabmanError::invalid_config(
-                    "endpoints.base_url",
-                    &format!(
-                        "endpoint '{}' base_url should typically end with /v1 (got '{}')",
-                        ep.name, base_url
-                    ),
-                ));
-            }
-        }
-
-        Ok(())
-    }
-
-    fn validate_wireguard(&self) -> Result<()> {
-        // For now we only perform very basic checks; stronger invariants
-        // (e.g., CIDR parsing, interface existence) are left to the
-        // wireguard layer.
-        if self.wireguard.interface_name.trim().is_empty() {
-            return Err(LabmanError::invalid_config(
-                "wireguard.interface_name",
-                "wireguard.interface_name must not be empty",
-            ));
-        }
-
-        // Sanity check allowed_ips for obviously bogus entries.
-        for cidr in &self.wireguard.allowed_ips {
-            if cidr.trim().is_empty() {
-                return Err(LabmanError::invalid_config(
-                    "wireguard.allowed_ips",
-                    "wireguard.allowed_ips must not contain empty entries",
-                ));
-            }
-        }
-
-        Ok(())
-    }
 }
 
 /// Control‑plane configuration section.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ControlPlaneConfig {
     /// Base URL of the control‑plane API, e.g. `https://control.example.com/api/v1`.
     pub base_url: String,
 
     /// Node authentication token used when talking to the control plane.
-    pub node_token: String,
+    ///
+    /// May be left out in favour of `node_token_file` or a `node_token`
+    /// systemd credential (see the `secrets` module).
+    #[serde(default)]
+    pub node_token: Secret,
+
+    /// File to read the node token from, instead of writing it inline.
+    ///
+    /// Relative paths are resolved against `$CREDENTIALS_DIRECTORY` when set,
+    /// and against the configuration file's directory otherwise.
+    #[serde(default)]
+    pub node_token_file: Option<String>,
 
     /// Optional region identifier (datacenter, cloud region, campus, etc.).
     #[serde(default)]
@@ -215,6 +141,25 @@ pub struct ControlPlaneConfig {
     /// Optional human‑readable description of this node.
     #[serde(default)]
     pub description: Option<String>,
+
+    /// Seconds between heartbeats sent to the control plane. Defaults to 30.
+    ///
+    /// The control plane may supply this (see the `overlay` module) unless
+    /// the operator sets it.
+    #[serde(default = "default_heartbeat_interval_secs")]
+    pub heartbeat_interval_secs: u64,
+
+    /// File holding the settings supplied by the control plane. Defaults to
+    /// [`DEFAULT_STATE_FILE`].
+    #[serde(default)]
+    pub state_file: Option<String>,
+
+    /// How model slugs are derived (see `labman_core::slug`).
+    ///
+    /// In TOML this is `[control_plane.slug_scheme]`; the default is the
+    /// legacy v0 scheme keyed on the endpoint `base_url`.
+    #[serde(default)]
+    pub slug_scheme: SlugScheme,
 }
 
 /// WireGuard and Rosenpass configuration.
@@ -222,7 +167,7 @@ pub struct ControlPlaneConfig {
 /// These fields describe how this node should establish a secure tunnel
 /// towards the control plane. Some values may be refined or replaced
 /// once control‑plane registration is implemented.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WireGuardConfig {
     /// Interface name (default: `labman0`).
     ///
@@ -263,7 +208,7 @@ pub struct WireGuardConfig {
 }
 
 /// Rosenpass‑related configuration for post‑quantum key exchange.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RosenpassConfig {
     /// Path to this node's Rosenpass private key.
     #[serde(default)]
@@ -279,7 +224,7 @@ pub struct RosenpassConfig {
 }
 
 /// Proxy configuration for the local HTTP interface.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ProxyConfig {
     /// Port to listen on (binds to WireGuard interface by default).
     ///
@@ -287,14 +232,70 @@ pub struct ProxyConfig {
     #[serde(default = "default_listen_port")]
     pub listen_port: u16,
 
-    /// Optional listen address override. This will later be constrained so
-    /// that it only binds on the WireGuard address.
+    /// Optional listen address override (an IP address, without port).
+    ///
+    /// Defaults to 127.0.0.1. This will later be constrained so that it only
+    /// binds on the WireGuard address.
     #[serde(default)]
     pub listen_addr: Option<String>,
 }
 
+/// Listener for the Portman WebSocket connection.
+///
+/// Portman runs on the operator's machine or LAN, so the listener must stay
+/// on a loopback, private or link-local address; it is never exposed on a
+/// public address or over the WireGuard tunnel.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PortmanConfig {
+    /// IP address to listen on. Defaults to 127.0.0.1.
+    #[serde(default = "default_portman_listen_addr")]
+    pub listen_addr: String,
+
+    /// Port to listen on. Defaults to 9100.
+    #[serde(default = "default_portman_listen_port")]
+    pub listen_port: u16,
+}
+
+impl Default for PortmanConfig {
+    fn default() -> Self {
+        Self {
+            listen_addr: default_portman_listen_addr(),
+            listen_port: default_portman_listen_port(),
+        }
+    }
+}
+
+/// How often endpoints are health-checked and their models rediscovered.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthConfig {
+    /// Seconds between endpoint health checks. Defaults to 30.
+    #[serde(default = "default_health_interval_secs")]
+    pub interval_secs: u64,
+
+    /// Seconds between model discovery passes. Discovery runs after a health
+    /// check, so values below `interval_secs` behave like `interval_secs`.
+    /// Defaults to 30.
+    #[serde(default = "default_health_interval_secs")]
+    pub discovery_interval_secs: u64,
+
+    /// Seconds to wait for a health check or model list response before
+    /// treating the endpoint as unreachable. Defaults to 10.
+    #[serde(default = "default_health_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: default_health_interval_secs(),
+            discovery_interval_secs: default_health_interval_secs(),
+            timeout_secs: default_health_timeout_secs(),
+        }
+    }
+}
+
 /// Telemetry configuration for logging and metrics.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TelemetryConfig {
     /// Log level: trace, debug, info, warn, error.
     ///
@@ -303,10 +304,31 @@ pub struct TelemetryConfig {
     #[serde(default)]
     pub log_level: Option<String>,
 
-    /// Log format, e.g. "text" or "json".
+    /// Log format: "text" (default) or "json", one object per line.
+    ///
+    /// Applies to `stdout` and `file` output; journald records are always
+    /// structured.
     #[serde(default)]
     pub log_format: Option<String>,
 
+    /// Where logs go: "stdout" (default), "file" (see `log_file`) or
+    /// "journald".
+    #[serde(default)]
+    pub log_output: Option<String>,
+
+    /// Log file for `log_output = "file"`.
+    #[serde(default)]
+    pub log_file: Option<String>,
+
+    /// Size in MiB at which the log file is rotated. Defaults to 10.
+    #[serde(default = "default_log_file_max_size_mb")]
+    pub log_file_max_size_mb: u64,
+
+    /// Rotated log files to keep (`labmand.log.1` is the newest). Defaults
+    /// to 5.
+    #[serde(default = "default_log_file_max_files")]
+    pub log_file_max_files: usize,
+
     /// Whether to disable the Prometheus metrics endpoint.
     ///
     /// Metrics are enabled by default because they are essential for validating
@@ -322,6 +344,12 @@ pub struct TelemetryConfig {
     /// default when metrics are enabled.
     #[serde(default = "default_metrics_port")]
     pub metrics_port: u16,
+
+    /// IP address the HTTP server (including `/metrics`) binds on. Defaults
+    /// to 0.0.0.0 so operators can scrape from their LAN; the `--bind-addr`
+    /// flag takes precedence.
+    #[serde(default)]
+    pub metrics_addr: Option<String>,
 }
 
 /// Configuration for a single logical endpoint.
@@ -329,19 +357,31 @@ pub struct TelemetryConfig {
 /// The scheduler and endpoint management layer will turn these into
 /// concrete `labman_core::Endpoint` instances and perform health
 /// checks and model discovery.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct EndpointConfig {
     /// Logical name for this endpoint (unique per config file).
     pub name: String,
 
     /// Base URL of the endpoint, typically ending in `/v1`,
     /// e.g. `http://127.0.0.1:11434/v1`.
+    ///
+    /// Runtimes that only listen on a Unix domain socket can be reached with
+    /// `unix://<socket path>:<http path>`, e.g. `unix:///run/ollama.sock:/v1`.
     pub base_url: String,
 
     /// Optional concurrency limit for this endpoint.
     #[serde(default)]
     pub max_concurrent: Option<usize>,
 
+    /// Optional per-model concurrency limits, applied in addition to
+    /// `max_concurrent`.
+    ///
+    /// Each entry matches upstream model IDs by glob pattern; the first
+    /// matching entry applies. Models without a match are only bounded by
+    /// the endpoint limit.
+    #[serde(default)]
+    pub model_limits: Vec<ModelLimitConfig>,
+
     /// Optional list of glob patterns describing which models to expose.
     ///
     /// If provided, only models matching at least one pattern will be
@@ -369,38 +409,440 @@ pub struct EndpointConfig {
     /// operator's default tenant.
     #[serde(default)]
     pub tenant: Option<String>,
+
+    /// Which API flavour labman uses to talk to this endpoint.
+    ///
+    /// Defaults to `openai`, i.e. the OpenAI-compatible `/v1` API.
+    #[serde(default)]
+    pub kind: EndpointKind,
+
+    /// Native Ollama API options; only used when `kind = "ollama"`.
+    #[serde(default)]
+    pub ollama: Option<OllamaConfig>,
+
+    /// How often to scrape the runtime's own load metrics, in seconds.
+    ///
+    /// Only vLLM (`/metrics`) and llama.cpp (`/metrics` or `/slots`) endpoints
+    /// are scraped; the runtime is detected automatically. Defaults to 10
+    /// seconds; `0` disables scraping for this endpoint.
+    #[serde(default)]
+    pub load_scrape_interval_secs: Option<u64>,
+
+    /// Optional deep health probe sending a tiny completion to one model.
+    ///
+    /// The regular health check only confirms the HTTP server is up; a probe
+    /// also catches a crashed or unloadable model. Disabled when omitted.
+    #[serde(default)]
+    pub probe: Option<ProbeConfig>,
+
+    /// Request policy applied to every model served by this endpoint.
+    ///
+    /// A `policy` on a matching `[[models]]` entry overrides individual
+    /// fields of this one.
+    #[serde(default)]
+    pub policy: Option<RequestPolicyConfig>,
+
+    /// Time windows in which the endpoint serves the network.
+    ///
+    /// Outside its windows the endpoint is left out of routing and
+    /// capability reporting. Always available when omitted.
+    #[serde(default)]
+    pub schedule: Option<ScheduleConfig>,
+}
+
+/// An operator-defined model alias (`[[models]]`).
+///
+/// Upstream models whose ID matches one of `match` are presented to the
+/// network as `id`, so that e.g. `llama3.1:8b-instruct-q4_K_M` on Ollama and
+/// `meta-llama/Llama-3.1-8B-Instruct` on vLLM become the same logical model.
+/// Requests are still forwarded with the upstream's own ID. The optional
+/// fields override what discovery reported.
+///
+/// ```toml
+/// [[models]]
+/// id = "llama-3.1-8b-instruct"
+/// match = ["llama3.1:8b-instruct*", "meta-llama/Llama-3.1-8B-Instruct"]
+/// context_length = 32768
+/// tags = ["chat"]
+/// capabilities = ["tools", "json_mode"]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelConfig {
+    /// Canonical model ID presented to the network.
+    pub id: String,
+
+    /// Glob patterns (`*` wildcard) matched against upstream model IDs.
+    ///
+    /// When empty, the entry only matches a model whose upstream ID is `id`
+    /// itself, which is useful for overriding metadata without aliasing.
+    #[serde(default, rename = "match")]
+    pub patterns: Vec<String>,
+
+    /// Endpoint names this entry applies to. Applies to all when unset.
+    #[serde(default)]
+    pub endpoints: Option<Vec<String>>,
+
+    /// Override for the context length in tokens.
+    #[serde(default)]
+    pub context_length: Option<u64>,
+
+    /// Override for the maximum number of output tokens.
+    #[serde(default)]
+    pub max_output_tokens: Option<u64>,
+
+    /// Tags to report for the model, replacing any discovered ones.
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+
+    /// Request features the model supports (`tools`, `vision`,
+    /// `json_mode`), replacing what discovery detected. Requests needing a
+    /// feature outside this list are rejected by the proxy.
+    #[serde(default)]
+    pub capabilities: Option<Vec<ModelCapability>>,
+
+    /// Request policy for this model, overriding the endpoint's policy
+    /// field by field.
+    #[serde(default)]
+    pub policy: Option<RequestPolicyConfig>,
+}
+
+impl ModelConfig {
+    /// Whether this entry applies to models on `endpoint`.
+    pub fn applies_to(&self, endpoint: &str) -> bool {
+        self.endpoints
+            .as_ref()
+            .is_none_or(|names| names.iter().any(|n| n == endpoint))
+    }
+}
+
+/// Concurrency limit for the models of an endpoint matching a pattern.
+///
+/// ```toml
+/// model_limits = [
+///     { match = "llama3.1:70b*", max_concurrent = 1 },
+///     { match = "*:7b*", max_concurrent = 8 },
+/// ]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelLimitConfig {
+    /// Glob pattern (`*` wildcard) matched against upstream model IDs.
+    #[serde(rename = "match")]
+    pub pattern: String,
+
+    /// Maximum number of concurrent requests per matching model.
+    pub max_concurrent: usize,
+}
+
+/// Limits and defaults applied to chat completion requests before they are
+/// forwarded upstream.
+///
+/// Requests from the network can ask for arbitrarily long generations; a
+/// policy bounds how long a single request may occupy the GPU.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RequestPolicyConfig {
+    /// Upper bound for `max_tokens` / `max_completion_tokens`. Requests
+    /// without a limit get this one.
+    #[serde(default)]
+    pub max_tokens: Option<u64>,
+
+    /// Upper bound for `n` (number of choices).
+    #[serde(default)]
+    pub max_n: Option<u64>,
+
+    /// Reject requests exceeding `max_tokens` or `max_n` instead of lowering
+    /// the values to the limit.
+    #[serde(default)]
+    pub reject_over_limit: Option<bool>,
+
+    /// Request parameters that are rejected outright (e.g. `logprobs`).
+    /// Parameters set to `null`, and `stream: false`, are not rejected.
+    #[serde(default)]
+    pub forbidden_params: Option<Vec<String>>,
+
+    /// `temperature` to use when the request does not set one.
+    #[serde(default)]
+    pub default_temperature: Option<f64>,
+}
+
+impl RequestPolicyConfig {
+    /// Combine with a more specific policy whose set fields take precedence.
+    pub fn merged_with(&self, specific: &RequestPolicyConfig) -> RequestPolicyConfig {
+        RequestPolicyConfig {
+            max_tokens: specific.max_tokens.or(self.max_tokens),
+            max_n: specific.max_n.or(self.max_n),
+            reject_over_limit: specific.reject_over_limit.or(self.reject_over_limit),
+            forbidden_params: specific
+                .forbidden_params
+                .clone()
+                .or_else(|| self.forbidden_params.clone()),
+            default_temperature: specific.default_temperature.or(self.default_temperature),
+        }
+    }
+
+    fn validate(&self, path: &str, report: &mut ValidationReport) {
+        if self.max_tokens == Some(0) || self.max_n == Some(0) {
+            report.error(path, "max_tokens and max_n must be positive");
+        }
+        if self
+            .default_temperature
+            .is_some_and(|t| !(0.0..=2.0).contains(&t))
+        {
+            report.error(
+                format!("{}.default_temperature", path),
+                "must be between 0 and 2",
+            );
+        }
+    }
+}
+
+/// Availability windows of an endpoint.
+///
+/// ```toml
+/// [endpoints.schedule]
+/// timezone = "local"
+/// drain_secs = 600
+/// windows = [
+///     { days = ["mon", "tue", "wed", "thu", "fri"], start = "22:00", end = "07:00" },
+///     { days = ["sat", "sun"] },
+/// ]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScheduleConfig {
+    /// Timezone the windows are expressed in: `"UTC"` (the default),
+    /// `"local"` for the host's timezone, or a fixed offset such as
+    /// `"+02:00"`.
+    #[serde(default)]
+    pub timezone: Option<String>,
+
+    /// Seconds before a window closes during which the endpoint drains: it
+    /// stops taking new requests so in-flight ones finish in time. Defaults
+    /// to 300.
+    #[serde(default)]
+    pub drain_secs: Option<u64>,
+
+    /// Windows in which the endpoint is available; it is available if any
+    /// window is open.
+    #[serde(default)]
+    pub windows: Vec<ScheduleWindowConfig>,
+}
+
+/// Default `drain_secs` for endpoint schedules.
+pub const DEFAULT_SCHEDULE_DRAIN_SECS: u64 = 300;
+
+impl ScheduleConfig {
+    /// Parse `timezone`.
+    pub fn parsed_timezone(&self) -> std::result::Result<ScheduleTimezone, String> {
+        let Some(tz) = self.timezone.as_deref().map(str::trim) else {
+            return Ok(ScheduleTimezone::Utc);
+        };
+        if tz.eq_ignore_ascii_case("utc") || tz.eq_ignore_ascii_case("z") {
+            return Ok(ScheduleTimezone::Utc);
+        }
+        if tz.eq_ignore_ascii_case("local") {
+            return Ok(ScheduleTimezone::Local);
+        }
+
+        let invalid = || {
+            format!(
+                "invalid timezone '{}' (expected \"UTC\", \"local\" or an offset like \"+02:00\")",
+                tz
+            )
+        };
+        let (sign, rest) = match tz.as_bytes().first() {
+            Some(b'+') => (1, &tz[1..]),
+            Some(b'-') => (-1, &tz[1..]),
+            _ => return Err(invalid()),
+        };
+        let minutes = parse_hh_mm(rest)
+            .filter(|m| *m <= 14 * 60)
+            .ok_or_else(invalid)?;
+        Ok(ScheduleTimezone::Offset(sign * minutes as i32 * 60))
+    }
+
+    fn validate(&self, path: &str, report: &mut ValidationReport) {
+        if let Err(reason) = self.parsed_timezone() {
+            report.error(format!("{}.timezone", path), reason);
+        }
+        if self.windows.is_empty() {
+            report.error(
+                format!("{}.windows", path),
+                "at least one window is required",
+            );
+        }
+        for (i, window) in self.windows.iter().enumerate() {
+            let window_path = format!("{}.windows[{}]", path, i);
+            if let Err(reason) = window.parsed_days() {
+                report.error(format!("{}.days", window_path), reason);
+            }
+            match window.parsed_times() {
+                Err(reason) => report.error(window_path, reason),
+                Ok((start, end)) if start == end => report.error(
+                    window_path,
+                    format!(
+                        "start and end must differ (both {:02}:{:02})",
+                        start / 60,
+                        start % 60
+                    ),
+                ),
+                Ok(_) => {}
+            }
+        }
+    }
+}
+
+/// A recurring availability window.
+///
+/// A window whose `end` is earlier than its `start` runs past midnight into
+/// the following day, e.g. `start = "22:00", end = "07:00"` on `fri` lasts
+/// from Friday 22:00 until Saturday 07:00.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScheduleWindowConfig {
+    /// Days the window starts on (`mon` … `sun`, or full names). Every day
+    /// when omitted.
+    #[serde(default)]
+    pub days: Option<Vec<String>>,
+
+    /// Opening time as `HH:MM`. Defaults to `00:00`.
+    #[serde(default)]
+    pub start: Option<String>,
+
+    /// Closing time as `HH:MM`; `24:00` is the end of the day, which is
+    /// also the default.
+    #[serde(default)]
+    pub end: Option<String>,
+}
+
+impl ScheduleWindowConfig {
+    /// Days the window starts on, as days since Monday (`0` = Monday).
+    pub fn parsed_days(&self) -> std::result::Result<Vec<u32>, String> {
+        const DAYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+        const FULL: [&str; 7] = [
+            "monday",
+            "tuesday",
+            "wednesday",
+            "thursday",
+            "friday",
+            "saturday",
+            "sunday",
+        ];
+
+        let Some(days) = &self.days else {
+            return Ok((0..7).collect());
+        };
+        if days.is_empty() {
+            return Err("window days must not be empty (omit them for every day)".to_string());
+        }
+        days.iter()
+            .map(|day| {
+                let day_lower = day.trim().to_ascii_lowercase();
+                DAYS.iter()
+                    .position(|d| *d == day_lower)
+                    .or_else(|| FULL.iter().position(|d| *d == day_lower))
+                    .map(|i| i as u32)
+                    .ok_or_else(|| format!("unknown day '{}'", day))
+            })
+            .collect()
+    }
+
+    /// Opening and closing time in minutes after midnight.
+    pub fn parsed_times(&self) -> std::result::Result<(u32, u32), String> {
+        let parse = |value: &Option<String>, default: u32| match value {
+            None => Ok(default),
+            Some(value) => parse_hh_mm(value.trim())
+                .filter(|m| *m <= 24 * 60)
+                .ok_or_else(|| format!("invalid time '{}' (expected HH:MM)", value)),
+        };
+        let start = parse(&self.start, 0)?;
+        if start == 24 * 60 {
+            return Err("window start must be before 24:00".to_string());
+        }
+        Ok((start, parse(&self.end, 24 * 60)?))
+    }
+}
+
+/// Timezone of an endpoint schedule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleTimezone {
+    Utc,
+
+    /// The host's timezone, including daylight saving changes.
+    Local,
+
+    /// A fixed offset east of UTC, in seconds.
+    Offset(i32),
+}
+
+/// Parse `HH:MM` into minutes after midnight.
+fn parse_hh_mm(value: &str) -> Option<u32> {
+    let (hours, minutes) = value.split_once(':')?;
+    if hours.is_empty() || hours.len() > 2 || minutes.len() != 2 {
+        return None;
+    }
+    let hours: u32 = hours.parse().ok()?;
+    let minutes: u32 = minutes.parse().ok()?;
+    (minutes < 60).then_some(hours * 60 + minutes)
+}
+
+/// Synthetic inference probe settings for an endpoint.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProbeConfig {
+    /// Model ID (as reported by the endpoint) to send the probe to.
+    pub model: String,
+
+    /// Seconds between probes. Defaults to 300.
+    #[serde(default = "default_probe_interval_secs")]
+    pub interval_secs: u64,
+
+    /// Seconds to wait for the probe completion. Defaults to 30.
+    #[serde(default = "default_probe_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+/// API flavour spoken by an endpoint.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EndpointKind {
+    /// Generic OpenAI-compatible server (`/v1/models`, `/v1/chat/completions`).
+    #[default]
+    OpenAi,
+
+    /// Ollama, discovered via its native `/api/tags` and `/api/show` routes so
+    /// that model size, quantization and context length are available.
+    Ollama,
+}
+
+/// Options for endpoints with `kind = "ollama"`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OllamaConfig {
+    /// Translate chat completions to Ollama's native `/api/chat` instead of
+    /// forwarding them to the OpenAI shim at `/v1/chat/completions`.
+    #[serde(default)]
+    pub native_chat: bool,
+
+    /// `keep_alive` value sent with native chat requests (e.g. "10m", "-1").
+    ///
+    /// Controls how long Ollama keeps the model loaded after a request. When
+    /// unset, Ollama's own default applies.
+    #[serde(default)]
+    pub keep_alive: Option<String>,
 }
 
 /// Load configuration from a specific file path.
 ///
-/// This function parses TOML into [`LabmanConfig`] and maps errors into
-/// [`LabmanError::Config`] / [`LabmanError::InvalidConfig`] as appropriate.
+/// This parses the TOML file, merges any drop-ins from the `conf.d`
+/// directory next to it (see [`load_layered`]) into [`LabmanConfig`], and
+/// maps errors into [`LabmanError::Config`] / [`LabmanError::InvalidConfig`]
+/// as appropriate.
 pub fn load_from_path<P: AsRef<Path>>(path: P) -> Result<LabmanConfig> {
-    let path_ref = path.as_ref();
-    let contents = fs::read_to_string(path_ref).map_err(|err| {
-        LabmanError::config(format!(
-            "failed to read config file '{}': {}",
-            path_ref.display(),
-            err
-        ))
-    })?;
-
-    let cfg: LabmanConfig = toml::from_str(&contents).map_err(|err| {
-        LabmanError::invalid_config(
-            path_ref.display().to_string(),
-            format!("failed to parse config: {}", err),
-        )
-    })?;
-
-    Ok(cfg)
+    load_layered(path).map(|loaded| loaded.config)
 }
 
-/// Attempt to load configuration using the default search strategy.
+/// Path of the configuration file `load_default` would use, if any.
 ///
 /// Current strategy (in order):
 /// 1. `/etc/labman/labman.toml`
 /// 2. `./labman.toml` (in the current working directory)
-pub fn load_default() -> Result<LabmanConfig> {
+pub fn default_config_path() -> Option<PathBuf> {
     let candidates = [
         PathBuf::from("/etc/labman/labman.toml"),
         std::env::current_dir()
@@ -408,15 +850,19 @@ pub fn load_default() -> Result<LabmanConfig> {
             .join("labman.toml"),
     ];
 
-    for candidate in &candidates {
-        if candidate.exists() {
-            return load_from_path(candidate);
-        }
-    }
+    candidates.into_iter().find(|candidate| candidate.exists())
+}
 
-    Err(LabmanError::config(
-        "no configuration file found; provide a path explicitly or create /etc/labman/labman.toml or ./labman.toml".to_string(),
-    ))
+/// Attempt to load configuration using the default search strategy (see
+/// [`default_config_path`]), including drop-ins such as
+/// `/etc/labman/conf.d/*.toml`.
+pub fn load_default() -> Result<LabmanConfig> {
+    match default_config_path() {
+        Some(path) => load_from_path(path),
+        None => Err(LabmanError::config(
+            "no configuration file found; provide a path explicitly or create /etc/labman/labman.toml or ./labman.toml".to_string(),
+        )),
+    }
 }
 
 fn default_interface_name() -> String {
@@ -431,6 +877,42 @@ fn default_metrics_port() -> u16 {
     9090
 }
 
+fn default_log_file_max_size_mb() -> u64 {
+    10
+}
+
+fn default_log_file_max_files() -> usize {
+    5
+}
+
+fn default_heartbeat_interval_secs() -> u64 {
+    30
+}
+
+fn default_portman_listen_addr() -> String {
+    "127.0.0.1".to_string()
+}
+
+fn default_portman_listen_port() -> u16 {
+    9100
+}
+
+fn default_health_interval_secs() -> u64 {
+    30
+}
+
+fn default_health_timeout_secs() -> u64 {
+    10
+}
+
+fn default_probe_interval_secs() -> u64 {
+    300
+}
+
+fn default_probe_timeout_secs() -> u64 {
+    30
+}
+
 /// Compute a short, non-reversible fingerprint for a sensitive token.
 ///
 /// This is intentionally lossy and only used for deriving a provisional,
@@ -459,6 +941,40 @@ mod tests {
     use std::io::Write;
     use std::path::PathBuf;
 
+    /// A structurally valid configuration with no endpoints.
+    pub(crate) fn test_config() -> LabmanConfig {
+        LabmanConfig {
+            control_plane: ControlPlaneConfig {
+                base_url: "https://control.example.com/api/v1".to_string(),
+                node_token: "token".into(),
+                node_token_file: None,
+                region: None,
+                description: None,
+                heartbeat_interval_secs: 30,
+                state_file: None,
+                slug_scheme: Default::default(),
+            },
+            wireguard: WireGuardConfig {
+                interface_name: "labman0".to_string(),
+                address: None,
+                private_key_path: None,
+                public_key_path: None,
+                peer_endpoint: None,
+                allowed_ips: Vec::new(),
+                rosenpass: None,
+            },
+            proxy: ProxyConfig {
+                listen_port: 8080,
+                listen_addr: None,
+            },
+            portman: Default::default(),
+            telemetry: None,
+            health: Default::default(),
+            endpoints: Vec::new(),
+            models: Vec::new(),
+        }
+    }
+
     #[test]
     fn test_load_from_path_minimal() {
         // Create a temporary file path in the current directory without relying on
@@ -498,7 +1014,7 @@ base_url = "http://127.0.0.1:11434/v1"
             cfg.control_plane.base_url,
             "https://control.example.com/api/v1"
         );
-        assert_eq!(cfg.control_plane.node_token, "test-token");
+        assert_eq!(cfg.control_plane.node_token.expose(), "test-token");
         assert_eq!(cfg.wireguard.interface_name, "labman0");
         assert_eq!(cfg.proxy.listen_port, 8080);
         assert_eq!(cfg.endpoints.len(), 1);
@@ -529,9 +1045,13 @@ base_url = "http://127.0.0.1:11434/v1"
         let cfg = LabmanConfig {
             control_plane: ControlPlaneConfig {
                 base_url: "https://control.example.com/api/v1".to_string(),
-                node_token: "token-123".to_string(),
+                node_token: "token-123".into(),
+                node_token_file: None,
                 region: Some("edge-eu-west".to_string()),
                 description: Some("Edge node".to_string()),
+                heartbeat_interval_secs: 30,
+                state_file: None,
+                slug_scheme: Default::default(),
             },
             wireguard: WireGuardConfig {
                 interface_name: "labman0".to_string(),
@@ -546,8 +1066,11 @@ base_url = "http://127.0.0.1:11434/v1"
                 listen_port: 8080,
                 listen_addr: None,
             },
+            portman: Default::default(),
             telemetry: None,
+            health: Default::default(),
             endpoints: Vec::new(),
+            models: Vec::new(),
         };
 
         let caps = labman_core::NodeCapabilities::new(Vec::new(), 0);
@@ -565,9 +1088,13 @@ base_url = "http://127.0.0.1:11434/v1"
         let cfg = LabmanConfig {
             control_plane: ControlPlaneConfig {
                 base_url: "".to_string(),
-                node_token: "token".to_string(),
+                node_token: "token".into(),
+                node_token_file: None,
                 region: None,
                 description: None,
+                heartbeat_interval_secs: 30,
+                state_file: None,
+                slug_scheme: Default::default(),
             },
             wireguard: WireGuardConfig {
                 interface_name: "labman0".to_string(),
@@ -582,22 +1109,239 @@ base_url = "http://127.0.0.1:11434/v1"
                 listen_port: 8080,
                 listen_addr: None,
             },
+            portman: Default::default(),
             telemetry: None,
+            health: Default::default(),
             endpoints: Vec::new(),
+            models: Vec::new(),
         };
 
         let res = cfg.validate();
         assert!(res.is_err());
     }
 
+    #[test]
+    fn test_validate_accepts_unix_socket_endpoints() {
+        let mut cfg = test_config();
+        cfg.endpoints = vec![EndpointConfig {
+            name: "ollama-sock".to_string(),
+            base_url: "unix:///run/ollama.sock:/v1".to_string(),
+            max_concurrent: None,
+            models_include: None,
+            models_exclude: None,
+            tenant: None,
+            ..Default::default()
+        }];
+        assert!(cfg.validate().is_ok());
+
+        cfg.endpoints[0].base_url = "unix://relative.sock:/v1".to_string();
+        assert!(cfg.validate().is_err());
+    }
+
+    #[test]
+    fn test_probe_config_defaults_and_validation() {
+        let ep: EndpointConfig = toml::from_str(
+            r#"
+name = "vllm"
+base_url = "http://127.0.0.1:8000/v1"
+probe = { model = "qwen2.5-7b" }
+"#,
+        )
+        .expect("parse endpoint");
+        let probe = ep.probe.as_ref().expect("probe configured");
+        assert_eq!(probe.interval_secs, 300);
+        assert_eq!(probe.timeout_secs, 30);
+
+        let mut cfg = test_config();
+        cfg.endpoints = vec![ep];
+        assert!(cfg.validate().is_ok());
+
+        cfg.endpoints[0].probe.as_mut().unwrap().interval_secs = 0;
+        assert!(cfg.validate().is_err());
+    }
+
+    #[test]
+    fn test_slug_scheme_parses_and_validates() {
+        let cp: ControlPlaneConfig = toml::from_str(
+            r#"
+base_url = "https://control.example.com/api/v1"
+node_token = "token"
+slug_scheme = { version = "v1", endpoint_key = "name", long_digest = true }
+"#,
+        )
+        .expect("parse control plane");
+        assert_eq!(cp.slug_scheme.version, labman_core::SlugVersion::V1);
+        assert_eq!(
+            cp.slug_scheme.endpoint_key,
+            labman_core::SlugEndpointKey::Name
+        );
+        assert!(cp.slug_scheme.long_digest);
+
+        let mut cfg = test_config();
+        assert_eq!(cfg.control_plane.slug_scheme, SlugScheme::default());
+        cfg.control_plane.slug_scheme.long_digest = true;
+        assert!(cfg.validate().is_err());
+        cfg.control_plane.slug_scheme.version = labman_core::SlugVersion::V1;
+        assert!(cfg.validate().is_ok());
+    }
+
+    #[test]
+    fn test_model_aliases_parse_and_validate() {
+        let mut cfg = test_config();
+        let extra: LabmanConfig = toml::from_str(
+            r#"
+[control_plane]
+base_url = "https://control.example.com/api/v1"
+node_token = "token"
+
+[wireguard]
+
+[proxy]
+listen_port = 8080
+
+[[models]]
+id = "llama-3.1-8b-instruct"
+match = ["llama3.1:8b-instruct*", "meta-llama/Llama-3.1-8B-Instruct"]
+context_length = 32768
+tags = ["chat"]
+
+[[models]]
+id = "qwen2.5-7b"
+endpoints = ["vllm"]
+"#,
+        )
+        .expect("parse models");
+        cfg.models = extra.models;
+
+        assert_eq!(cfg.models.len(), 2);
+        assert_eq!(cfg.models[0].patterns.len(), 2);
+        assert_eq!(cfg.models[0].context_length, Some(32768));
+        assert!(cfg.models[0].applies_to("anything"));
+        assert!(cfg.models[1].patterns.is_empty());
+        assert!(cfg.models[1].applies_to("vllm"));
+        assert!(!cfg.models[1].applies_to("ollama"));
+
+        // `vllm` is not a configured endpoint.
+        assert!(cfg.validate().is_err());
+        cfg.models[1].endpoints = None;
+        assert!(cfg.validate().is_ok());
+    }
+
+    #[test]
+    fn test_request_policy_merges_and_validates() {
+        let ep: EndpointConfig = toml::from_str(
+            r#"
+name = "vllm"
+base_url = "http://127.0.0.1:8000/v1"
+policy = { max_tokens = 2048, max_n = 1, forbidden_params = ["logprobs"] }
+"#,
+        )
+        .expect("parse endpoint");
+        let endpoint_policy = ep.policy.clone().expect("policy");
+
+        let model_policy = RequestPolicyConfig {
+            max_tokens: Some(512),
+            default_temperature: Some(0.2),
+            ..Default::default()
+        };
+        let merged = endpoint_policy.merged_with(&model_policy);
+        assert_eq!(merged.max_tokens, Some(512));
+        assert_eq!(merged.max_n, Some(1));
+        assert_eq!(merged.forbidden_params, Some(vec!["logprobs".to_string()]));
+        assert_eq!(merged.default_temperature, Some(0.2));
+
+        let mut cfg = test_config();
+        cfg.endpoints = vec![ep];
+        assert!(cfg.validate().is_ok());
+        cfg.endpoints[0]
+            .policy
+            .as_mut()
+            .unwrap()
+            .default_temperature = Some(3.0);
+        assert!(cfg.validate().is_err());
+    }
+
+    #[test]
+    fn test_endpoint_schedule_parses_and_validates() {
+        let ep: EndpointConfig = toml::from_str(
+            r#"
+name = "gaming-pc"
+base_url = "http://10.0.0.5:11434/v1"
+
+[schedule]
+timezone = "+02:00"
+windows = [
+    { days = ["Mon", "friday"], start = "22:00", end = "07:00" },
+    { days = ["sat", "sun"] },
+]
+"#,
+        )
+        .expect("parse endpoint");
+        let schedule = ep.schedule.clone().expect("schedule");
+
+        assert_eq!(
+            schedule.parsed_timezone(),
+            Ok(ScheduleTimezone::Offset(7200))
+        );
+        assert_eq!(schedule.windows[0].parsed_days(), Ok(vec![0, 4]));
+        assert_eq!(schedule.windows[0].parsed_times(), Ok((22 * 60, 7 * 60)));
+        assert_eq!(schedule.windows[1].parsed_times(), Ok((0, 24 * 60)));
+
+        let mut cfg = test_config();
+        cfg.endpoints = vec![ep];
+        assert!(cfg.validate().is_ok());
+
+        let invalid = [
+            ("timezone", "Europe/Berlin"),
+            ("day", "someday"),
+            ("time", "25:00"),
+        ];
+        for (field, value) in invalid {
+            let mut broken = cfg.clone();
+            let schedule = broken.endpoints[0].schedule.as_mut().unwrap();
+            match field {
+                "timezone" => schedule.timezone = Some(value.to_string()),
+                "day" => schedule.windows[0].days = Some(vec![value.to_string()]),
+                _ => schedule.windows[0].start = Some(value.to_string()),
+            }
+            assert!(broken.validate().is_err(), "{} '{}' accepted", field, value);
+        }
+    }
+
+    #[test]
+    fn test_endpoint_kind_parses_ollama_options() {
+        let cfg: EndpointConfig = toml::from_str(
+            r#"
+name = "ollama"
+base_url = "http://127.0.0.1:11434/v1"
+kind = "ollama"
+ollama = { native_chat = true, keep_alive = "10m" }
+"#,
+        )
+        .expect("parse endpoint");
+
+        assert_eq!(cfg.kind, EndpointKind::Ollama);
+        let ollama = cfg.ollama.expect("ollama options");
+        assert!(ollama.native_chat);
+        assert_eq!(ollama.keep_alive.as_deref(), Some("10m"));
+
+        let default_kind: EndpointConfig =
+            toml::from_str("name = \"x\"\nbase_url = \"http://h/v1\"").expect("parse");
+        assert_eq!(default_kind.kind, EndpointKind::OpenAi);
+    }
+
     #[test]
     fn test_validate_rejects_duplicate_endpoint_names() {
         let cfg = LabmanConfig {
             control_plane: ControlPlaneConfig {
                 base_url: "https://control.example.com/api/v1".to_string(),
-                node_token: "token".to_string(),
+                node_token: "token".into(),
+                node_token_file: None,
                 region: None,
                 description: None,
+                heartbeat_interval_secs: 30,
+                state_file: None,
+                slug_scheme: Default::default(),
             },
             wireguard: WireGuardConfig {
                 interface_name: "labman0".to_string(),
@@ -612,7 +1356,9 @@ base_url = "http://127.0.0.1:11434/v1"
                 listen_port: 8080,
                 listen_addr: None,
             },
+            portman: Default::default(),
             telemetry: None,
+            health: Default::default(),
             endpoints: vec![
                 EndpointConfig {
                     name: "dup".to_string(),
@@ -620,6 +1366,8 @@ base_url = "http://127.0.0.1:11434/v1"
                     max_concurrent: None,
                     models_include: None,
                     models_exclude: None,
+                    tenant: None,
+                    ..Default::default()
                 },
                 EndpointConfig {
                     name: "dup".to_string(),
@@ -627,8 +1375,11 @@ base_url = "http://127.0.0.1:11434/v1"
                     max_concurrent: None,
                     models_include: None,
                     models_exclude: None,
+                    tenant: None,
+                    ..Default::default()
                 },
             ],
+            models: Vec::new(),
         };
 
         let res = cfg.validate();
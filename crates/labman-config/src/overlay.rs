@@ -0,0 +1,367 @@
+//! Settings supplied by the control plane.
+//!
+//! A few settings describe this node's place in the control plane's network
+//! rather than the operator's machine: the WireGuard address assigned at
+//! registration (`RegistrationResponse::wireguard_address`), the control
+//! plane's WireGuard endpoint and allowed IPs, and the heartbeat interval.
+//! The control plane may supply these as a [`ControlPlaneOverlay`], which
+//! labmand keeps in a state file (`control_plane.state_file`, by default
+//! [`DEFAULT_STATE_FILE`]) so that they survive restarts, and lays over the
+//! operator configuration after loading it.
+//!
+//! The operator always has precedence: a setting present in `labman.toml`
+//! or one of its drop-ins is locked, and a control-plane value for it is
+//! ignored and reported as a note. No other settings can be supplied, and
+//! allowed IPs that would route a private network into the tunnel are
+//! rejected (see "No LAN Exposure Over WireGuard" in architecture.md).
+//!
+//! Applied values are recorded in [`ConfigSources`](crate::ConfigSources)
+//! with the state file as their source;
+//! [`ConfigSources::from_control_plane`](crate::ConfigSources::from_control_plane)
+//! tells them apart from operator values.
+
+use std::fs;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+
+use labman_core::{LabmanError, RegistrationResponse, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::validation::{parse_cidr, private_range_overlap};
+use crate::{ControlPlaneConfig, LabmanConfig, LoadedConfig, ValidationReport};
+
+/// Default location of the control-plane state file.
+pub const DEFAULT_STATE_FILE: &str = "/var/lib/labman/control-plane.toml";
+
+const STATE_FILE_HEADER: &str =
+    "# Settings supplied by the control plane. Written by labmand; do not edit.\n\n";
+
+/// Settings the control plane may supply. Unset fields leave the operator
+/// configuration alone.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ControlPlaneOverlay {
+    /// WireGuard address assigned to this node (`wireguard.address`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wireguard_address: Option<String>,
+
+    /// The control plane's WireGuard endpoint, `host:port`
+    /// (`wireguard.peer_endpoint`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peer_endpoint: Option<String>,
+
+    /// Allowed IPs for the control-plane peer (`wireguard.allowed_ips`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_ips: Option<Vec<String>>,
+
+    /// Seconds between heartbeats (`control_plane.heartbeat_interval_secs`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heartbeat_interval_secs: Option<u64>,
+}
+
+impl ControlPlaneOverlay {
+    /// The settings carried by a registration response.
+    pub fn from_registration(response: &RegistrationResponse) -> Self {
+        let address = response.wireguard_address.trim();
+        Self {
+            wireguard_address: (!address.is_empty()).then(|| address.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Take over every setting `newer` supplies, keeping the others.
+    pub fn update(&mut self, newer: ControlPlaneOverlay) {
+        let ControlPlaneOverlay {
+            wireguard_address,
+            peer_endpoint,
+            allowed_ips,
+            heartbeat_interval_secs,
+        } = newer;
+        self.wireguard_address = wireguard_address.or(self.wireguard_address.take());
+        self.peer_endpoint = peer_endpoint.or(self.peer_endpoint.take());
+        self.allowed_ips = allowed_ips.or(self.allowed_ips.take());
+        self.heartbeat_interval_secs = heartbeat_interval_secs.or(self.heartbeat_interval_secs);
+    }
+
+    /// Read the state file at `path`. A missing file is an empty overlay.
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => {
+                return Err(LabmanError::config(format!(
+                    "failed to read control-plane state file '{}': {}",
+                    path.display(),
+                    err
+                )))
+            }
+        };
+        toml::from_str(&contents).map_err(|err| {
+            LabmanError::invalid_config(
+                path.display().to_string(),
+                format!("failed to parse control-plane state: {}", err),
+            )
+        })
+    }
+
+    /// Write the state file at `path`, replacing it atomically.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let contents = toml::to_string(self).map_err(|err| {
+            LabmanError::config(format!("failed to serialize control-plane state: {}", err))
+        })?;
+        let write_err = |err: std::io::Error| {
+            LabmanError::config(format!(
+                "failed to write control-plane state file '{}': {}",
+                path.display(),
+                err
+            ))
+        };
+
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(write_err)?;
+        }
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, format!("{}{}", STATE_FILE_HEADER, contents)).map_err(write_err)?;
+        fs::rename(&tmp, path).map_err(write_err)
+    }
+}
+
+impl ControlPlaneConfig {
+    /// Path of the control-plane state file.
+    pub fn state_file_path(&self) -> PathBuf {
+        PathBuf::from(self.state_file.as_deref().unwrap_or(DEFAULT_STATE_FILE))
+    }
+}
+
+impl LoadedConfig {
+    /// Lay the settings kept in `control_plane.state_file` over the
+    /// configuration (see [`apply_overlay`](Self::apply_overlay)).
+    ///
+    /// An unreadable state file is reported as a warning and ignored.
+    pub fn apply_control_plane_state(&mut self) {
+        let path = self.config.control_plane.state_file_path();
+        match ControlPlaneOverlay::load(&path) {
+            Ok(overlay) => self.apply_overlay(&overlay, &path),
+            Err(err) => {
+                let mut report = ValidationReport::default();
+                report.warning(
+                    "control_plane.state_file",
+                    format!("{}; control-plane settings ignored", err),
+                );
+                self.issues.extend(report.issues);
+            }
+        }
+    }
+
+    /// Apply the control-plane settings in `overlay`, read from
+    /// `state_file`, to every setting the operator has not set.
+    ///
+    /// Locked and rejected values are added to `issues` as notes and
+    /// warnings.
+    pub fn apply_overlay(&mut self, overlay: &ControlPlaneOverlay, state_file: &Path) {
+        let mut report = ValidationReport::default();
+
+        if let Some(address) = &overlay.wireguard_address {
+            self.overlay_field(
+                "wireguard.address",
+                format!("'{}'", address),
+                parse_cidr(address).map(|_| ()),
+                |cfg| cfg.wireguard.address = Some(address.clone()),
+                state_file,
+                &mut report,
+            );
+        }
+
+        if let Some(endpoint) = &overlay.peer_endpoint {
+            self.overlay_field(
+                "wireguard.peer_endpoint",
+                format!("'{}'", endpoint),
+                check_peer_endpoint(endpoint),
+                |cfg| cfg.wireguard.peer_endpoint = Some(endpoint.clone()),
+                state_file,
+                &mut report,
+            );
+        }
+
+        if let Some(allowed_ips) = &overlay.allowed_ips {
+            self.overlay_field(
+                "wireguard.allowed_ips",
+                format!("[{}]", allowed_ips.join(", ")),
+                allowed_ips.iter().try_for_each(|e| check_allowed_ip(e)),
+                |cfg| cfg.wireguard.allowed_ips = allowed_ips.clone(),
+                state_file,
+                &mut report,
+            );
+        }
+
+        if let Some(secs) = overlay.heartbeat_interval_secs {
+            self.overlay_field(
+                "control_plane.heartbeat_interval_secs",
+                secs.to_string(),
+                if secs == 0 {
+                    Err("must be positive".to_string())
+                } else {
+                    Ok(())
+                },
+                |cfg| cfg.control_plane.heartbeat_interval_secs = secs,
+                state_file,
+                &mut report,
+            );
+        }
+
+        self.issues.extend(report.issues);
+    }
+
+    fn overlay_field(
+        &mut self,
+        key: &str,
+        value: String,
+        check: std::result::Result<(), String>,
+        apply: impl FnOnce(&mut LabmanConfig),
+        state_file: &Path,
+        report: &mut ValidationReport,
+    ) {
+        if let Some(file) = self.sources.get(key) {
+            report.info(
+                key,
+                format!(
+                    "control plane supplied {}; keeping the value set in {}",
+                    value,
+                    file.display()
+                ),
+            );
+            return;
+        }
+        if let Err(reason) = check {
+            report.warning(
+                key,
+                format!("rejected control-plane value {}: {}", value, reason),
+            );
+            return;
+        }
+
+        apply(&mut self.config);
+        self.sources.supply(key, state_file);
+    }
+}
+
+fn check_peer_endpoint(endpoint: &str) -> std::result::Result<(), String> {
+    match endpoint.trim().rsplit_once(':') {
+        Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok() => Ok(()),
+        _ => Err("must be of the form host:port".to_string()),
+    }
+}
+
+/// Allowed IPs from the control plane must not route the operator's LAN
+/// into the tunnel.
+fn check_allowed_ip(entry: &str) -> std::result::Result<(), String> {
+    match parse_cidr(entry)? {
+        (_, 0) => Err(format!("'{}' routes all traffic through the tunnel", entry)),
+        (IpAddr::V4(addr), prefix) if prefix < 32 => {
+            match private_range_overlap(u32::from(addr), prefix) {
+                Some(range) => Err(format!("'{}' overlaps the private range {}", entry, range)),
+                None => Ok(()),
+            }
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{load_layered, Severity};
+
+    #[test]
+    fn overlay_fills_unset_fields_and_respects_operator_values() {
+        let dir =
+            std::env::temp_dir().join(format!("labman-config-overlay-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let base = dir.join("labman.toml");
+        let state_file = dir.join("state").join("control-plane.toml");
+        fs::write(
+            &base,
+            format!(
+                r#"
+[control_plane]
+base_url = "https://control.example.com/api/v1"
+node_token = "token"
+state_file = "{}"
+
+[wireguard]
+address = "10.90.0.9/32"
+
+[proxy]
+listen_port = 8080
+"#,
+                state_file.display()
+            ),
+        )
+        .unwrap();
+
+        // Without a state file nothing changes.
+        let mut loaded = load_layered(&base).expect("load config");
+        let before = loaded.config.clone();
+        loaded.apply_control_plane_state();
+        assert_eq!(loaded.config, before);
+        assert!(loaded.issues.is_empty());
+
+        let mut overlay = ControlPlaneOverlay::from_registration(&RegistrationResponse {
+            success: true,
+            node_id: "node-1".to_string(),
+            message: None,
+            wireguard_address: "10.90.0.2/32".to_string(),
+        });
+        overlay.update(ControlPlaneOverlay {
+            peer_endpoint: Some("control.example.com:51820".to_string()),
+            allowed_ips: Some(vec![
+                "10.90.0.1/32".to_string(),
+                "192.168.0.0/16".to_string(),
+            ]),
+            heartbeat_interval_secs: Some(15),
+            ..Default::default()
+        });
+        assert_eq!(overlay.wireguard_address.as_deref(), Some("10.90.0.2/32"));
+        overlay.save(&state_file).expect("save state");
+        assert_eq!(ControlPlaneOverlay::load(&state_file).unwrap(), overlay);
+
+        let mut loaded = load_layered(&base).expect("load config");
+        loaded.apply_control_plane_state();
+        let cfg = &loaded.config;
+
+        // Set by the operator: locked.
+        assert_eq!(cfg.wireguard.address.as_deref(), Some("10.90.0.9/32"));
+        // Unset by the operator: supplied, with provenance.
+        assert_eq!(
+            cfg.wireguard.peer_endpoint.as_deref(),
+            Some("control.example.com:51820")
+        );
+        assert_eq!(cfg.control_plane.heartbeat_interval_secs, 15);
+        assert!(loaded
+            .sources
+            .from_control_plane("control_plane.heartbeat_interval_secs"));
+        assert_eq!(
+            loaded.sources.get("wireguard.peer_endpoint"),
+            Some(state_file.as_path())
+        );
+        assert!(!loaded.sources.from_control_plane("wireguard.address"));
+        // A LAN range would be routed into the tunnel: rejected.
+        assert!(cfg.wireguard.allowed_ips.is_empty());
+
+        let found: Vec<_> = loaded
+            .issues
+            .iter()
+            .map(|i| (i.severity, i.path.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (Severity::Info, "wireguard.address"),
+                (Severity::Warning, "wireguard.allowed_ips"),
+            ]
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
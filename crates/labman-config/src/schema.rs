@@ -0,0 +1,846 @@
+//! Machine-readable description of the configuration file.
+//!
+//! [`CONFIG_SCHEMA`] lists every section and key `LabmanConfig` accepts,
+//! with its type, a short description and its default. It drives:
+//!
+//! - [`json_schema`], a JSON Schema editors can validate `labman.toml`
+//!   against (e.g. with taplo or Even Better TOML);
+//! - [`reference_toml`], a fully commented reference configuration;
+//! - the unknown-key warnings of the validation report.
+//!
+//! `labmand --print-schema` and `labmand --print-reference-config` print the
+//! two documents. The tests check the key names against the serde
+//! definitions, so the schema cannot drift from the types.
+
+use serde_json::{json, Map, Value as Json};
+
+/// Type of a configuration value.
+#[derive(Debug, Clone, Copy)]
+pub enum SchemaType {
+    String,
+    Integer,
+    Float,
+    Bool,
+    /// An array of strings.
+    Strings,
+    /// One of the listed strings.
+    Enum(&'static [&'static str]),
+    /// An array of the listed strings.
+    Enums(&'static [&'static str]),
+    /// A table (`[section]`).
+    Table(&'static [SchemaField]),
+    /// An array of tables (`[[section]]`).
+    Tables(&'static [SchemaField]),
+}
+
+/// A key of the configuration file.
+#[derive(Debug, Clone, Copy)]
+pub struct SchemaField {
+    pub name: &'static str,
+    pub ty: SchemaType,
+    pub doc: &'static str,
+    pub required: bool,
+    /// Default value as a TOML literal.
+    pub default: Option<&'static str>,
+    /// Example value as a TOML literal, for keys without a default.
+    pub example: Option<&'static str>,
+}
+
+impl SchemaField {
+    const fn new(name: &'static str, ty: SchemaType, doc: &'static str) -> Self {
+        SchemaField {
+            name,
+            ty,
+            doc,
+            required: false,
+            default: None,
+            example: None,
+        }
+    }
+
+    const fn required(self) -> Self {
+        SchemaField {
+            required: true,
+            ..self
+        }
+    }
+
+    const fn default(self, value: &'static str) -> Self {
+        SchemaField {
+            default: Some(value),
+            ..self
+        }
+    }
+
+    const fn example(self, value: &'static str) -> Self {
+        SchemaField {
+            example: Some(value),
+            ..self
+        }
+    }
+}
+
+// Note that this shadows `String` with `SchemaType::String` in this module.
+use SchemaType::*;
+
+const fn field(name: &'static str, ty: SchemaType, doc: &'static str) -> SchemaField {
+    SchemaField::new(name, ty, doc)
+}
+
+const POLICY: &[SchemaField] = &[
+    field(
+        "max_tokens",
+        Integer,
+        "Upper bound for max_tokens / max_completion_tokens; requests without a limit get this one.",
+    )
+    .example("4096"),
+    field("max_n", Integer, "Upper bound for n (number of choices).").example("1"),
+    field(
+        "reject_over_limit",
+        Bool,
+        "Reject requests exceeding max_tokens or max_n instead of lowering them to the limit.",
+    )
+    .default("false"),
+    field(
+        "forbidden_params",
+        Strings,
+        "Request parameters that are rejected outright.",
+    )
+    .example(r#"["logprobs"]"#),
+    field(
+        "default_temperature",
+        Float,
+        "Temperature to use when the request does not set one (0 to 2).",
+    )
+    .example("0.7"),
+];
+
+const CONTROL_PLANE: &[SchemaField] = &[
+    field("base_url", String, "Base URL of the control-plane API.")
+        .required()
+        .example(r#""https://control.example.com/api/v1""#),
+    field(
+        "node_token",
+        String,
+        "Node authentication token. Prefer node_token_file or a systemd credential named node_token over writing it here.",
+    )
+    .example(r#""change-me""#),
+    field(
+        "node_token_file",
+        String,
+        "File to read the node token from (not accessible by other users). Relative paths are resolved against $CREDENTIALS_DIRECTORY if set, otherwise against this file's directory.",
+    )
+    .example(r#""/etc/labman/node_token""#),
+    field(
+        "region",
+        String,
+        "Region identifier (datacenter, cloud region, campus, etc.).",
+    )
+    .example(r#""eu-west""#),
+    field("description", String, "Human-readable description of this node.")
+        .example(r#""Rack 3, 2x RTX 4090""#),
+    field(
+        "heartbeat_interval_secs",
+        Integer,
+        "Seconds between heartbeats to the control plane. The control plane may supply this unless it is set here.",
+    )
+    .default("30"),
+    field(
+        "state_file",
+        String,
+        "File in which settings supplied by the control plane are kept.",
+    )
+    .default(r#""/var/lib/labman/control-plane.toml""#),
+    field(
+        "slug_scheme",
+        Table(&[
+            field(
+                "version",
+                Enum(&["v0", "v1"]),
+                "Slug scheme version; v1 slugs are prefixed with \"v1-\".",
+            )
+            .default(r#""v0""#),
+            field(
+                "endpoint_key",
+                Enum(&["base_url", "name"]),
+                "What identifies the endpoint in a v1 slug; the name stays stable when the address changes.",
+            )
+            .default(r#""base_url""#),
+            field(
+                "long_digest",
+                Bool,
+                "Use a 16 instead of 8 byte digest for v1 slugs.",
+            )
+            .default("false"),
+        ]),
+        "How model slugs are derived. The scheme is advertised in the node capabilities.",
+    ),
+];
+
+const WIREGUARD: &[SchemaField] = &[
+    field("interface_name", String, "WireGuard interface name.").default(r#""labman0""#),
+    field(
+        "address",
+        String,
+        "Local WireGuard address in CIDR notation.",
+    )
+    .example(r#""10.90.1.2/32""#),
+    field(
+        "private_key_path",
+        String,
+        "Path to the node's WireGuard private key (not accessible by other users).",
+    )
+    .example(r#""/etc/labman/wg-private.key""#),
+    field(
+        "public_key_path",
+        String,
+        "Path to the node's WireGuard public key.",
+    )
+    .example(r#""/etc/labman/wg-public.key""#),
+    field(
+        "peer_endpoint",
+        String,
+        "Control-plane WireGuard endpoint (host:port).",
+    )
+    .example(r#""control.example.com:51820""#),
+    field(
+        "allowed_ips",
+        Strings,
+        "Allowed IPs for the control-plane peer; keep this to the control plane's address or a narrow range.",
+    )
+    .default("[]"),
+    field(
+        "rosenpass",
+        Table(&[
+            field(
+                "private_key_path",
+                String,
+                "Path to this node's Rosenpass private key (not accessible by other users).",
+            )
+            .example(r#""/etc/labman/rosenpass-private.key""#),
+            field(
+                "public_key_path",
+                String,
+                "Path to this node's Rosenpass public key.",
+            )
+            .example(r#""/etc/labman/rosenpass-public.key""#),
+            field(
+                "peer_public_key_path",
+                String,
+                "Path to the control plane's Rosenpass public key.",
+            )
+            .example(r#""/etc/labman/rosenpass-peer-public.key""#),
+        ]),
+        "Post-quantum key exchange with Rosenpass.",
+    ),
+];
+
+const PROXY: &[SchemaField] = &[
+    field(
+        "listen_port",
+        Integer,
+        "Port of the OpenAI-compatible proxy.",
+    )
+    .default("8080"),
+    field("listen_addr", String, "IP address the proxy listens on.").default(r#""127.0.0.1""#),
+];
+
+const PORTMAN: &[SchemaField] = &[
+    field(
+        "listen_addr",
+        String,
+        "IP address of the Portman WebSocket listener. Must be a loopback, private or link-local address.",
+    )
+    .default(r#""127.0.0.1""#),
+    field(
+        "listen_port",
+        Integer,
+        "Port of the Portman WebSocket listener.",
+    )
+    .default("9100"),
+];
+
+const TELEMETRY: &[SchemaField] = &[
+    field(
+        "log_level",
+        String,
+        "Log level or filter (trace, debug, info, warn, error); --log-level takes precedence.",
+    )
+    .example(r#""info""#),
+    field(
+        "log_format",
+        Enum(&["text", "json"]),
+        "Log format for stdout and file output; json writes one object per line.",
+    )
+    .default(r#""text""#),
+    field(
+        "log_output",
+        Enum(&["stdout", "file", "journald"]),
+        "Where logs go. journald records carry the event fields as journal fields.",
+    )
+    .default(r#""stdout""#),
+    field("log_file", String, "Log file for log_output = \"file\".")
+        .example(r#""/var/log/labman/labmand.log""#),
+    field(
+        "log_file_max_size_mb",
+        Integer,
+        "Size in MiB at which the log file is rotated.",
+    )
+    .default("10"),
+    field(
+        "log_file_max_files",
+        Integer,
+        "Rotated log files to keep.",
+    )
+    .default("5"),
+    field(
+        "disable_metrics",
+        Bool,
+        "Disable the Prometheus metrics endpoint. The control plane then has less visibility into this node's contribution.",
+    )
+    .default("false"),
+    field("metrics_port", Integer, "Port of the metrics endpoint.").default("9090"),
+    field(
+        "metrics_addr",
+        String,
+        "IP address of the HTTP server serving the metrics endpoint; --bind-addr takes precedence.",
+    )
+    .default(r#""0.0.0.0""#),
+];
+
+const HEALTH: &[SchemaField] = &[
+    field(
+        "interval_secs",
+        Integer,
+        "Seconds between endpoint health checks.",
+    )
+    .default("30"),
+    field(
+        "discovery_interval_secs",
+        Integer,
+        "Seconds between model discovery passes; runs after a health check.",
+    )
+    .default("30"),
+    field(
+        "timeout_secs",
+        Integer,
+        "Seconds to wait for a health check or model list response.",
+    )
+    .default("10"),
+];
+
+const ENDPOINT: &[SchemaField] = &[
+    field(
+        "name",
+        String,
+        "Logical name of the endpoint, unique in the configuration.",
+    )
+    .required()
+    .example(r#""gpu-1""#),
+    field(
+        "base_url",
+        String,
+        "Base URL of the OpenAI-compatible API, or unix://<socket>:<path> for runtimes listening on a Unix socket. Normalised on load (\"/v1\" is appended if missing).",
+    )
+    .required()
+    .example(r#""http://127.0.0.1:8000/v1""#),
+    field(
+        "kind",
+        Enum(&["openai", "ollama"]),
+        "API flavour used to talk to the endpoint.",
+    )
+    .default(r#""openai""#),
+    field(
+        "max_concurrent",
+        Integer,
+        "Concurrency limit for the endpoint. Unlimited when unset.",
+    )
+    .example("8"),
+    field(
+        "models_include",
+        Strings,
+        "Glob patterns of models to expose. All models when unset.",
+    )
+    .example(r#"["llama*", "mistral*"]"#),
+    field(
+        "models_exclude",
+        Strings,
+        "Glob patterns of models to hide, applied after models_include.",
+    )
+    .example(r#"["*embed*"]"#),
+    field(
+        "tenant",
+        String,
+        "Tenant the endpoint belongs to, for usage attribution by the control plane. The operator's default tenant when unset.",
+    )
+    .example(r#""acme""#),
+    field(
+        "load_scrape_interval_secs",
+        Integer,
+        "How often to scrape vLLM / llama.cpp load metrics, in seconds; 0 disables scraping.",
+    )
+    .default("10"),
+    field(
+        "model_limits",
+        Tables(&[
+            field(
+                "match",
+                String,
+                "Glob pattern matched against upstream model IDs; the first matching entry applies.",
+            )
+            .required()
+            .example(r#""llama3.1:70b*""#),
+            field(
+                "max_concurrent",
+                Integer,
+                "Maximum number of concurrent requests per matching model.",
+            )
+            .required()
+            .example("1"),
+        ]),
+        "Per-model concurrency limits, applied in addition to max_concurrent.",
+    ),
+    field(
+        "ollama",
+        Table(&[
+            field(
+                "native_chat",
+                Bool,
+                "Translate chat completions to Ollama's native /api/chat.",
+            )
+            .default("false"),
+            field(
+                "keep_alive",
+                String,
+                "keep_alive sent with native chat requests (e.g. \"10m\", \"-1\").",
+            )
+            .example(r#""10m""#),
+        ]),
+        "Native Ollama API options; only used when kind = \"ollama\".",
+    ),
+    field(
+        "probe",
+        Table(&[
+            field("model", String, "Model to send the probe completion to.")
+                .required()
+                .example(r#""llama3.1:8b""#),
+            field("interval_secs", Integer, "Seconds between probes.").default("300"),
+            field("timeout_secs", Integer, "Probe timeout in seconds.").default("30"),
+        ]),
+        "Deep health probe sending a tiny completion to one model. Disabled when omitted.",
+    ),
+    field(
+        "policy",
+        Table(POLICY),
+        "Request policy for every model of the endpoint; a [[models]] policy overrides it field by field.",
+    ),
+    field(
+        "schedule",
+        Table(&[
+            field(
+                "timezone",
+                String,
+                "Timezone of the windows: \"UTC\", \"local\" or an offset such as \"+02:00\".",
+            )
+            .default(r#""UTC""#),
+            field(
+                "drain_secs",
+                Integer,
+                "Seconds before a window closes during which no new requests are taken.",
+            )
+            .default("300"),
+            field(
+                "windows",
+                Tables(&[
+                    field(
+                        "days",
+                        Strings,
+                        "Days the window starts on (mon ... sun). Every day when unset.",
+                    )
+                    .example(r#"["mon", "tue", "wed", "thu", "fri"]"#),
+                    field("start", String, "Opening time (HH:MM).").default(r#""00:00""#),
+                    field(
+                        "end",
+                        String,
+                        "Closing time (HH:MM); earlier than start to run past midnight.",
+                    )
+                    .default(r#""24:00""#),
+                ]),
+                "Windows in which the endpoint is available.",
+            ),
+        ]),
+        "Availability windows. Always available when omitted.",
+    ),
+];
+
+const MODEL: &[SchemaField] = &[
+    field("id", String, "Canonical model ID presented to the network.")
+        .required()
+        .example(r#""llama-3.1-8b-instruct""#),
+    field(
+        "match",
+        Strings,
+        "Glob patterns matched against upstream model IDs. Only the model named id itself when empty.",
+    )
+    .default("[]"),
+    field(
+        "endpoints",
+        Strings,
+        "Endpoint names the entry applies to. All endpoints when unset.",
+    )
+    .example(r#"["gpu-1"]"#),
+    field(
+        "context_length",
+        Integer,
+        "Override for the context length in tokens.",
+    )
+    .example("32768"),
+    field(
+        "max_output_tokens",
+        Integer,
+        "Override for the maximum number of output tokens.",
+    )
+    .example("8192"),
+    field("tags", Strings, "Tags to report, replacing discovered ones.")
+        .example(r#"["chat"]"#),
+    field(
+        "capabilities",
+        Enums(&["tools", "vision", "json_mode"]),
+        "Request features the model supports, replacing what discovery detected.",
+    )
+    .example(r#"["tools", "json_mode"]"#),
+    field(
+        "policy",
+        Table(POLICY),
+        "Request policy for the model, overriding the endpoint's field by field.",
+    ),
+];
+
+/// Top-level sections of the configuration file.
+pub const CONFIG_SCHEMA: &[SchemaField] = &[
+    field(
+        "control_plane",
+        Table(CONTROL_PLANE),
+        "Control-plane connection.",
+    )
+    .required(),
+    field(
+        "wireguard",
+        Table(WIREGUARD),
+        "WireGuard / Rosenpass tunnel to the control plane.",
+    )
+    .required(),
+    field("proxy", Table(PROXY), "The local OpenAI-compatible proxy.").required(),
+    field(
+        "portman",
+        Table(PORTMAN),
+        "The local Portman WebSocket listener.",
+    ),
+    field("telemetry", Table(TELEMETRY), "Logging and metrics."),
+    field(
+        "health",
+        Table(HEALTH),
+        "Endpoint health checks and model discovery.",
+    ),
+    field(
+        "endpoints",
+        Tables(ENDPOINT),
+        "Local LLM endpoints served by this node.",
+    ),
+    field(
+        "models",
+        Tables(MODEL),
+        "Model aliases and metadata overrides.",
+    ),
+];
+
+/// JSON Schema (draft 2020-12) of the configuration file.
+pub fn json_schema() -> Json {
+    let mut schema = object_schema(CONFIG_SCHEMA);
+    let root = schema.as_object_mut().expect("object schema");
+    root.insert(
+        "$schema".to_string(),
+        json!("https://json-schema.org/draft/2020-12/schema"),
+    );
+    root.insert("title".to_string(), json!("labman configuration"));
+    schema
+}
+
+fn object_schema(fields: &[SchemaField]) -> Json {
+    let properties: Map<std::string::String, Json> = fields
+        .iter()
+        .map(|f| (f.name.to_string(), field_schema(f)))
+        .collect();
+    let required: Vec<&str> = fields
+        .iter()
+        .filter(|f| f.required)
+        .map(|f| f.name)
+        .collect();
+
+    let mut schema = json!({
+        "type": "object",
+        "properties": properties,
+        "additionalProperties": false,
+    });
+    if !required.is_empty() {
+        schema["required"] = json!(required);
+    }
+    schema
+}
+
+fn field_schema(field: &SchemaField) -> Json {
+    let mut schema = match field.ty {
+        String => json!({ "type": "string" }),
+        Integer => json!({ "type": "integer", "minimum": 0 }),
+        Float => json!({ "type": "number" }),
+        Bool => json!({ "type": "boolean" }),
+        Strings => json!({ "type": "array", "items": { "type": "string" } }),
+        Enum(values) => json!({ "enum": values }),
+        Enums(values) => json!({ "type": "array", "items": { "enum": values } }),
+        Table(fields) => object_schema(fields),
+        Tables(fields) => json!({ "type": "array", "items": object_schema(fields) }),
+    };
+    schema["description"] = json!(field.doc);
+    if let Some(default) = field.default {
+        schema["default"] = toml_literal_to_json(default);
+    }
+    if let Some(example) = field.example {
+        schema["examples"] = json!([toml_literal_to_json(example)]);
+    }
+    schema
+}
+
+fn toml_literal_to_json(literal: &str) -> Json {
+    let table: toml::Table =
+        toml::from_str(&format!("v = {}", literal)).expect("schema literals are valid TOML");
+    serde_json::to_value(&table["v"]).expect("TOML values convert to JSON")
+}
+
+/// A reference configuration listing every key, commented out, with its
+/// description and default (or an example where there is no default).
+pub fn reference_toml() -> std::string::String {
+    render(true)
+}
+
+fn render(commented: bool) -> std::string::String {
+    let mut out = std::string::String::new();
+    if commented {
+        out.push_str(
+            "# labman reference configuration.\n\
+             #\n\
+             # Every setting, commented out. Values shown are the defaults; for\n\
+             # settings without a default an example is shown instead.\n\
+             # Generated by `labmand --print-reference-config`.\n",
+        );
+    }
+    render_fields(&mut out, CONFIG_SCHEMA, "", commented);
+    out
+}
+
+fn render_fields(
+    out: &mut std::string::String,
+    fields: &[SchemaField],
+    prefix: &str,
+    commented: bool,
+) {
+    let hash = if commented { "# " } else { "" };
+
+    for field in fields.iter().filter(|f| !is_table(f)) {
+        out.push('\n');
+        push_doc(out, field.doc);
+        match (field.required, field.default) {
+            (true, _) => out.push_str("# Required.\n"),
+            (false, None) => out.push_str("# Unset by default.\n"),
+            (false, Some(_)) => {}
+        }
+        let value = field.default.or(field.example).unwrap_or("\"\"");
+        out.push_str(&format!("{}{} = {}\n", hash, field.name, value));
+    }
+
+    for field in fields.iter().filter(|f| is_table(f)) {
+        let path = if prefix.is_empty() {
+            field.name.to_string()
+        } else {
+            format!("{}.{}", prefix, field.name)
+        };
+        out.push('\n');
+        push_doc(out, field.doc);
+        match field.ty {
+            Table(nested) => {
+                out.push_str(&format!("{}[{}]\n", hash, path));
+                render_fields(out, nested, &path, commented);
+            }
+            Tables(nested) => {
+                out.push_str(&format!("{}[[{}]]\n", hash, path));
+                render_fields(out, nested, &path, commented);
+            }
+            _ => unreachable!("filtered to tables"),
+        }
+    }
+}
+
+fn is_table(field: &SchemaField) -> bool {
+    matches!(field.ty, Table(_) | Tables(_))
+}
+
+/// Write `doc` as comment lines of at most 78 characters.
+fn push_doc(out: &mut std::string::String, doc: &str) {
+    let mut line = std::string::String::from("#");
+    for word in doc.split_whitespace() {
+        if line.len() + 1 + word.len() > 78 && line.len() > 1 {
+            out.push_str(&line);
+            out.push('\n');
+            line = std::string::String::from("#");
+        }
+        line.push(' ');
+        line.push_str(word);
+    }
+    out.push_str(&line);
+    out.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::*;
+    use serde::de::{self, Deserialize, Visitor};
+
+    /// Field names serde accepts for `T`, captured from `deserialize_struct`.
+    fn serde_fields<'de, T: Deserialize<'de>>() -> Vec<&'static str> {
+        struct Capture(Vec<&'static str>);
+
+        #[derive(Debug)]
+        struct Captured;
+        impl std::fmt::Display for Captured {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "captured")
+            }
+        }
+        impl std::error::Error for Captured {}
+        impl de::Error for Captured {
+            fn custom<M: std::fmt::Display>(_: M) -> Self {
+                Captured
+            }
+        }
+
+        impl<'de> de::Deserializer<'de> for &mut Capture {
+            type Error = Captured;
+
+            fn deserialize_any<V: Visitor<'de>>(
+                self,
+                _: V,
+            ) -> std::result::Result<V::Value, Captured> {
+                Err(Captured)
+            }
+
+            fn deserialize_struct<V: Visitor<'de>>(
+                self,
+                _: &'static str,
+                fields: &'static [&'static str],
+                _: V,
+            ) -> std::result::Result<V::Value, Captured> {
+                self.0 = fields.to_vec();
+                Err(Captured)
+            }
+
+            serde::forward_to_deserialize_any! {
+                bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+                bytes byte_buf option unit unit_struct newtype_struct seq tuple
+                tuple_struct map enum identifier ignored_any
+            }
+        }
+
+        let mut capture = Capture(Vec::new());
+        let _ = T::deserialize(&mut capture);
+        let mut fields = capture.0;
+        fields.sort();
+        fields
+    }
+
+    fn schema_fields(path: &str) -> Vec<&'static str> {
+        let mut fields = CONFIG_SCHEMA;
+        for name in path.split('.').filter(|n| !n.is_empty()) {
+            let field = fields
+                .iter()
+                .find(|f| f.name == name)
+                .unwrap_or_else(|| panic!("no schema field {}", name));
+            fields = match field.ty {
+                Table(nested) | Tables(nested) => nested,
+                _ => panic!("{} is not a table", name),
+            };
+        }
+        let mut names: Vec<_> = fields.iter().map(|f| f.name).collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn schema_matches_config_types() {
+        let cases = [
+            ("", serde_fields::<LabmanConfig>()),
+            ("control_plane", serde_fields::<ControlPlaneConfig>()),
+            (
+                "control_plane.slug_scheme",
+                serde_fields::<labman_core::SlugScheme>(),
+            ),
+            ("wireguard", serde_fields::<WireGuardConfig>()),
+            ("wireguard.rosenpass", serde_fields::<RosenpassConfig>()),
+            ("proxy", serde_fields::<ProxyConfig>()),
+            ("portman", serde_fields::<PortmanConfig>()),
+            ("telemetry", serde_fields::<TelemetryConfig>()),
+            ("health", serde_fields::<HealthConfig>()),
+            ("endpoints", serde_fields::<EndpointConfig>()),
+            ("endpoints.model_limits", serde_fields::<ModelLimitConfig>()),
+            ("endpoints.ollama", serde_fields::<OllamaConfig>()),
+            ("endpoints.probe", serde_fields::<ProbeConfig>()),
+            ("endpoints.policy", serde_fields::<RequestPolicyConfig>()),
+            ("endpoints.schedule", serde_fields::<ScheduleConfig>()),
+            (
+                "endpoints.schedule.windows",
+                serde_fields::<ScheduleWindowConfig>(),
+            ),
+            ("models", serde_fields::<ModelConfig>()),
+            ("models.policy", serde_fields::<RequestPolicyConfig>()),
+        ];
+        for (path, fields) in cases {
+            assert!(!fields.is_empty(), "no serde fields captured for {}", path);
+            assert_eq!(schema_fields(path), fields, "schema of '{}'", path);
+        }
+    }
+
+    #[test]
+    fn reference_config_parses_with_every_key_set() {
+        let toml = render(false);
+        let cfg: LabmanConfig = toml::from_str(&toml).expect("reference config parses");
+        assert_eq!(cfg.endpoints.len(), 1);
+        assert!(cfg.endpoints[0].schedule.is_some());
+        assert_eq!(
+            crate::validation::unknown_keys(&toml::from_str(&toml).unwrap()),
+            Vec::new()
+        );
+
+        // The commented version parses too (as an empty document).
+        let commented: toml::Table = toml::from_str(&reference_toml()).unwrap();
+        assert!(commented.is_empty());
+    }
+
+    #[test]
+    fn json_schema_describes_nested_sections() {
+        let schema = json_schema();
+        let endpoint = &schema["properties"]["endpoints"]["items"];
+        assert_eq!(endpoint["required"], json!(["name", "base_url"]));
+        assert_eq!(endpoint["additionalProperties"], json!(false));
+        assert_eq!(
+            endpoint["properties"]["kind"]["enum"],
+            json!(["openai", "ollama"])
+        );
+        assert_eq!(
+            endpoint["properties"]["schedule"]["properties"]["drain_secs"]["default"],
+            json!(300)
+        );
+        assert_eq!(
+            schema["properties"]["proxy"]["properties"]["listen_port"]["default"],
+            json!(8080)
+        );
+    }
+}
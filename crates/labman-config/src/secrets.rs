@@ -0,0 +1,264 @@
+//! Secret values and secret file references.
+//!
+//! The node token does not have to be written into `labman.toml`, where it
+//! would end up in backups and configuration management repositories. It
+//! can instead be read from:
+//!
+//! - a file named by `control_plane.node_token_file`, or
+//! - a systemd credential called `node_token` (`LoadCredential=` /
+//!   `SetCredentialEncrypted=`), found in `$CREDENTIALS_DIRECTORY`, when
+//!   neither `node_token` nor `node_token_file` is set.
+//!
+//! Relative `node_token_file` paths are resolved against
+//! `$CREDENTIALS_DIRECTORY` when it is set, and against the directory of
+//! the base configuration file otherwise.
+//!
+//! Secret files, including the WireGuard and Rosenpass private keys, must
+//! not be accessible by other users (no "other" permission bits).
+//!
+//! Secret values are wrapped in [`Secret`], whose `Debug` output and
+//! serialized form are redacted, so printing, debug-logging or dumping a
+//! `LabmanConfig` never reveals them.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use labman_core::{LabmanError, Result};
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::LabmanConfig;
+
+/// Environment variable systemd sets to the service's credentials directory.
+pub const CREDENTIALS_DIRECTORY_ENV: &str = "CREDENTIALS_DIRECTORY";
+
+/// Name of the systemd credential holding the node token.
+pub const NODE_TOKEN_CREDENTIAL: &str = "node_token";
+
+/// What a non-empty [`Secret`] serializes to.
+pub const REDACTED: &str = "<redacted>";
+
+/// A secret string whose `Debug` output and serialized form are redacted.
+#[derive(Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(transparent)]
+pub struct Secret(String);
+
+impl Secret {
+    /// Wrap a secret value.
+    pub fn new(value: impl Into<String>) -> Self {
+        Secret(value.into())
+    }
+
+    /// The secret value itself. Do not log it.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    /// Whether the secret is empty (or only whitespace).
+    pub fn is_empty(&self) -> bool {
+        self.0.trim().is_empty()
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            write!(f, "Secret(<empty>)")
+        } else {
+            write!(f, "Secret(<redacted>)")
+        }
+    }
+}
+
+impl Serialize for Secret {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        if self.0.is_empty() {
+            serializer.serialize_str("")
+        } else {
+            serializer.serialize_str(REDACTED)
+        }
+    }
+}
+
+impl From<&str> for Secret {
+    fn from(value: &str) -> Self {
+        Secret::new(value)
+    }
+}
+
+impl From<String> for Secret {
+    fn from(value: String) -> Self {
+        Secret(value)
+    }
+}
+
+impl LabmanConfig {
+    /// Read secrets referenced by file and check secret file permissions.
+    ///
+    /// `config_dir` is the directory of the base configuration file, used
+    /// to resolve relative paths when `$CREDENTIALS_DIRECTORY` is not set.
+    /// Called by the loaders; configurations built in code only need it if
+    /// they use file references.
+    pub fn resolve_secrets(&mut self, config_dir: Option<&Path>) -> Result<()> {
+        let credentials_dir = std::env::var_os(CREDENTIALS_DIRECTORY_ENV).map(PathBuf::from);
+        let cp = &mut self.control_plane;
+
+        match (&cp.node_token_file, cp.node_token.is_empty()) {
+            (Some(_), false) => {
+                return Err(LabmanError::invalid_config(
+                    "control_plane.node_token_file",
+                    "set either control_plane.node_token or control_plane.node_token_file, not both",
+                ));
+            }
+            (Some(file), true) => {
+                let path = resolve_path(file, credentials_dir.as_deref(), config_dir);
+                cp.node_token = read_secret_file(&path, "control_plane.node_token_file")?;
+            }
+            (None, true) => {
+                if let Some(dir) = &credentials_dir {
+                    let path = dir.join(NODE_TOKEN_CREDENTIAL);
+                    if path.is_file() {
+                        cp.node_token = read_secret_file(&path, "control_plane.node_token")?;
+                    }
+                }
+            }
+            (None, false) => {}
+        }
+
+        let key_files = [
+            (
+                "wireguard.private_key_path",
+                self.wireguard.private_key_path.as_deref(),
+            ),
+            (
+                "wireguard.rosenpass.private_key_path",
+                self.wireguard
+                    .rosenpass
+                    .as_ref()
+                    .and_then(|r| r.private_key_path.as_deref()),
+            ),
+        ];
+        for (field, path) in key_files {
+            // Missing key files are reported by the components using them.
+            if let Some(path) = path.map(Path::new).filter(|p| p.exists()) {
+                check_permissions(path, field)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn resolve_path(file: &str, credentials_dir: Option<&Path>, config_dir: Option<&Path>) -> PathBuf {
+    let path = Path::new(file);
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+    match credentials_dir.or(config_dir) {
+        Some(dir) => dir.join(path),
+        None => path.to_path_buf(),
+    }
+}
+
+/// Read a secret from `path`, without its trailing newline.
+fn read_secret_file(path: &Path, field: &str) -> Result<Secret> {
+    check_permissions(path, field)?;
+    let contents = std::fs::read_to_string(path).map_err(|err| {
+        LabmanError::invalid_config(
+            field.to_string(),
+            format!("failed to read secret file '{}': {}", path.display(), err),
+        )
+    })?;
+
+    let secret = Secret::new(contents.trim_end_matches(['\n', '\r']));
+    if secret.is_empty() {
+        return Err(LabmanError::invalid_config(
+            field.to_string(),
+            format!("secret file '{}' is empty", path.display()),
+        ));
+    }
+    Ok(secret)
+}
+
+/// Refuse secret files that other users can read or modify.
+#[cfg(unix)]
+fn check_permissions(path: &Path, field: &str) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let metadata = std::fs::metadata(path).map_err(|err| {
+        LabmanError::invalid_config(
+            field.to_string(),
+            format!("cannot access secret file '{}': {}", path.display(), err),
+        )
+    })?;
+    let mode = metadata.permissions().mode();
+    if mode & 0o007 != 0 {
+        return Err(LabmanError::invalid_config(
+            field.to_string(),
+            format!(
+                "secret file '{}' is accessible by other users (mode {:o}); restrict it with chmod o-rwx",
+                path.display(),
+                mode & 0o777
+            ),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn check_permissions(_path: &Path, _field: &str) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    fn write_secret(path: &Path, contents: &str, mode: u32) {
+        std::fs::write(path, contents).unwrap();
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode)).unwrap();
+    }
+
+    #[test]
+    fn secrets_are_redacted_in_debug_output() {
+        let mut cfg = crate::tests::test_config();
+        cfg.control_plane.node_token = "super-secret-token".into();
+
+        let debug = format!("{:?}", cfg);
+        assert!(!debug.contains("super-secret-token"));
+        assert!(debug.contains("Secret(<redacted>)"));
+        assert_eq!(cfg.control_plane.node_token.expose(), "super-secret-token");
+    }
+
+    #[test]
+    fn node_token_file_is_read_and_permission_checked() {
+        let dir =
+            std::env::temp_dir().join(format!("labman-config-secrets-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        write_secret(&dir.join("token"), "file-token\n", 0o600);
+
+        let mut cfg = crate::tests::test_config();
+        cfg.control_plane.node_token = Secret::default();
+        cfg.control_plane.node_token_file = Some("token".to_string());
+        cfg.resolve_secrets(Some(&dir)).expect("resolve secrets");
+        assert_eq!(cfg.control_plane.node_token.expose(), "file-token");
+
+        // Setting both is ambiguous.
+        assert!(cfg.resolve_secrets(Some(&dir)).is_err());
+
+        // World-readable token files are refused.
+        write_secret(&dir.join("token"), "file-token\n", 0o644);
+        cfg.control_plane.node_token = Secret::default();
+        let err = cfg.resolve_secrets(Some(&dir)).unwrap_err();
+        assert!(err.to_string().contains("other users"), "{}", err);
+
+        // So are world-readable private keys.
+        let mut cfg = crate::tests::test_config();
+        write_secret(&dir.join("wg.key"), "key", 0o604);
+        cfg.wireguard.private_key_path = Some(dir.join("wg.key").display().to_string());
+        assert!(cfg.resolve_secrets(Some(&dir)).is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,852 @@
+//! Configuration validation reports.
+//!
+//! [`LabmanConfig::validation_report`] checks the whole configuration and
+//! collects every problem instead of stopping at the first one. Each issue
+//! carries the TOML path it refers to, e.g. `endpoints[gpu-1].base_url`
+//! (entries of `[[endpoints]]` and `[[models]]` are addressed by name or id,
+//! like in [`ConfigSources`](crate::ConfigSources)), and errors are told
+//! apart from warnings about settings that are valid but probably not what
+//! the operator meant.
+//!
+//! Keys serde does not know about are ignored when deserializing, so a typo
+//! such as `model_include` silently does nothing. The loaders therefore also
+//! compare the raw TOML against the [schema](crate::schema) and report
+//! unknown keys as warnings, with a "did you mean" suggestion when a known
+//! key is close.
+
+use std::fmt;
+use std::net::IpAddr;
+use std::path::Path;
+
+use labman_core::{LabmanError, Result};
+use toml::{Table, Value};
+
+use crate::schema::{SchemaField, SchemaType, CONFIG_SCHEMA};
+use crate::{normalize_base_url, EndpointConfig, LabmanConfig, ModelConfig};
+
+/// How serious a validation issue is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The configuration cannot be used.
+    Error,
+    /// The configuration works but is probably not what was intended.
+    Warning,
+    /// Nothing to fix, e.g. a value that was rewritten to its canonical form.
+    Info,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Info => write!(f, "note"),
+        }
+    }
+}
+
+/// A single problem found in a configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+
+    /// TOML path of the offending value, e.g. `endpoints[gpu-1].base_url`.
+    pub path: String,
+
+    pub message: String,
+
+    /// A likely intended value or key, shown as "did you mean ...".
+    pub suggestion: Option<String>,
+}
+
+impl ValidationIssue {
+    /// The message including the suggestion, without severity and path.
+    pub fn detail(&self) -> String {
+        match &self.suggestion {
+            Some(suggestion) => format!("{} (did you mean '{}'?)", self.message, suggestion),
+            None => self.message.clone(),
+        }
+    }
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}: {}", self.severity, self.path, self.detail())
+    }
+}
+
+/// All problems found in a configuration, errors first and notes last.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    /// Whether nothing was found.
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Whether the configuration is unusable.
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.with_severity(Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.with_severity(Severity::Warning)
+    }
+
+    pub fn notes(&self) -> impl Iterator<Item = &ValidationIssue> {
+        self.with_severity(Severity::Info)
+    }
+
+    /// The first error as a [`LabmanError::InvalidConfig`], if any.
+    pub fn into_result(self) -> Result<()> {
+        match self.errors().next() {
+            Some(issue) => Err(LabmanError::invalid_config(
+                issue.path.clone(),
+                issue.detail(),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Add issues (e.g. found while loading) and keep errors first.
+    pub fn extend(&mut self, issues: impl IntoIterator<Item = ValidationIssue>) {
+        self.issues.extend(issues);
+        self.sort();
+    }
+
+    /// Order issues by severity, errors first. The sort is stable, so
+    /// issues of the same severity keep the order they were found in.
+    fn sort(&mut self) {
+        self.issues.sort_by_key(|issue| issue.severity);
+    }
+
+    pub(crate) fn error(&mut self, path: impl Into<String>, message: impl Into<String>) {
+        self.push(Severity::Error, path.into(), message.into(), None);
+    }
+
+    pub(crate) fn warning(&mut self, path: impl Into<String>, message: impl Into<String>) {
+        self.push(Severity::Warning, path.into(), message.into(), None);
+    }
+
+    pub(crate) fn info(&mut self, path: impl Into<String>, message: impl Into<String>) {
+        self.push(Severity::Info, path.into(), message.into(), None);
+    }
+
+    fn push(
+        &mut self,
+        severity: Severity,
+        path: String,
+        message: String,
+        suggestion: Option<String>,
+    ) {
+        self.issues.push(ValidationIssue {
+            severity,
+            path,
+            message,
+            suggestion,
+        });
+    }
+
+    fn with_severity(&self, severity: Severity) -> impl Iterator<Item = &ValidationIssue> {
+        self.issues.iter().filter(move |i| i.severity == severity)
+    }
+}
+
+impl LabmanConfig {
+    /// Perform basic structural validation of the configuration.
+    ///
+    /// This does not attempt to contact any external systems; it only checks
+    /// for obviously invalid or inconsistent values. More advanced validation
+    /// (e.g., control-plane reachability) belongs in higher-level crates.
+    ///
+    /// Returns the first error of [`validation_report`](Self::validation_report).
+    pub fn validate(&self) -> Result<()> {
+        self.validation_report().into_result()
+    }
+
+    /// Check the whole configuration and collect every error and warning.
+    pub fn validation_report(&self) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        self.check_control_plane(&mut report);
+        self.check_endpoints(&mut report);
+        self.check_models(&mut report);
+        self.check_wireguard(&mut report);
+        self.check_listeners(&mut report);
+        self.check_health(&mut report);
+        self.check_telemetry(&mut report);
+        report.sort();
+        report
+    }
+
+    fn check_control_plane(&self, report: &mut ValidationReport) {
+        let cp = &self.control_plane;
+
+        let url = cp.base_url.trim();
+        if url.is_empty() {
+            report.error("control_plane.base_url", "must not be empty");
+        } else if !(url.starts_with("http://") || url.starts_with("https://")) {
+            // Very lightweight check: URL should look like http(s)://...
+            report.error(
+                "control_plane.base_url",
+                "must start with http:// or https://",
+            );
+        }
+
+        if cp.node_token.is_empty() {
+            report.error(
+                "control_plane.node_token",
+                "must not be empty (set node_token, node_token_file or a node_token systemd credential)",
+            );
+        }
+
+        if let Err(reason) = cp.slug_scheme.validate() {
+            report.error("control_plane.slug_scheme", reason);
+        }
+    }
+
+    fn check_endpoints(&self, report: &mut ValidationReport) {
+        let mut seen = std::collections::HashSet::new();
+        for (index, ep) in self.endpoints.iter().enumerate() {
+            let path = endpoint_path(ep, index);
+
+            if ep.name.trim().is_empty() {
+                report.error(format!("{}.name", path), "endpoint name must not be empty");
+            } else if !seen.insert(ep.name.as_str()) {
+                report.error(
+                    format!("{}.name", path),
+                    format!("duplicate endpoint name '{}'", ep.name),
+                );
+            }
+
+            if let Err(reason) = normalize_base_url(&ep.base_url) {
+                report.error(format!("{}.base_url", path), reason);
+            }
+
+            if let Some(policy) = &ep.policy {
+                policy.validate(&format!("{}.policy", path), report);
+            }
+
+            for (i, limit) in ep.model_limits.iter().enumerate() {
+                if limit.pattern.trim().is_empty() || limit.max_concurrent == 0 {
+                    report.error(
+                        format!("{}.model_limits[{}]", path, i),
+                        "model limits need a pattern and a positive max_concurrent",
+                    );
+                }
+            }
+
+            if let Some(schedule) = &ep.schedule {
+                schedule.validate(&format!("{}.schedule", path), report);
+            }
+
+            if let Some(probe) = &ep.probe {
+                if probe.model.trim().is_empty() {
+                    report.error(format!("{}.probe.model", path), "must not be empty");
+                }
+                if probe.interval_secs == 0 || probe.timeout_secs == 0 {
+                    report.error(
+                        format!("{}.probe", path),
+                        "interval_secs and timeout_secs must be positive",
+                    );
+                }
+            }
+
+            if ep.models_include.as_ref().is_some_and(Vec::is_empty) {
+                report.warning(
+                    format!("{}.models_include", path),
+                    "an empty include list matches no models; remove it to serve all models",
+                );
+            }
+        }
+
+        if self.endpoints.is_empty() {
+            report.warning("endpoints", "no endpoints are configured");
+        }
+    }
+
+    fn check_models(&self, report: &mut ValidationReport) {
+        let endpoint_names: Vec<&str> = self.endpoints.iter().map(|e| e.name.as_str()).collect();
+
+        for (index, model) in self.models.iter().enumerate() {
+            let path = model_path(model, index);
+
+            if model.id.trim().is_empty() {
+                report.error(format!("{}.id", path), "model id must not be empty");
+            }
+
+            if model.patterns.iter().any(|p| p.trim().is_empty()) {
+                report.error(
+                    format!("{}.match", path),
+                    "match patterns must not be empty",
+                );
+            }
+
+            if let Some(policy) = &model.policy {
+                policy.validate(&format!("{}.policy", path), report);
+            }
+
+            for name in model.endpoints.iter().flatten() {
+                if !endpoint_names.contains(&name.as_str()) {
+                    report.push(
+                        Severity::Error,
+                        format!("{}.endpoints", path),
+                        format!("unknown endpoint '{}'", name),
+                        closest(name, endpoint_names.iter().copied()),
+                    );
+                }
+            }
+        }
+    }
+
+    fn check_wireguard(&self, report: &mut ValidationReport) {
+        // Stronger invariants (e.g., interface existence) are left to the
+        // wireguard layer.
+        let wg = &self.wireguard;
+        if wg.interface_name.trim().is_empty() {
+            report.error("wireguard.interface_name", "must not be empty");
+        }
+
+        for (i, entry) in wg.allowed_ips.iter().enumerate() {
+            let path = format!("wireguard.allowed_ips[{}]", i);
+            match parse_cidr(entry) {
+                Err(reason) => report.error(path, reason),
+                Ok((_, 0)) => report.warning(
+                    path,
+                    format!(
+                        "'{}' routes all traffic through the tunnel; allow only the control plane's address",
+                        entry.trim()
+                    ),
+                ),
+                Ok((IpAddr::V4(addr), prefix)) if prefix < 32 => {
+                    if let Some(range) = private_range_overlap(u32::from(addr), prefix) {
+                        report.warning(
+                            path,
+                            format!(
+                                "'{}' overlaps the private range {}; LAN hosts in it would be routed into the tunnel",
+                                entry.trim(),
+                                range
+                            ),
+                        );
+                    }
+                }
+                Ok(_) => {}
+            }
+        }
+
+        let rosenpass = wg.rosenpass.as_ref();
+        let key_files = [
+            ("wireguard.private_key_path", wg.private_key_path.as_deref()),
+            ("wireguard.public_key_path", wg.public_key_path.as_deref()),
+            (
+                "wireguard.rosenpass.private_key_path",
+                rosenpass.and_then(|r| r.private_key_path.as_deref()),
+            ),
+            (
+                "wireguard.rosenpass.public_key_path",
+                rosenpass.and_then(|r| r.public_key_path.as_deref()),
+            ),
+            (
+                "wireguard.rosenpass.peer_public_key_path",
+                rosenpass.and_then(|r| r.peer_public_key_path.as_deref()),
+            ),
+        ];
+        for (path, file) in key_files {
+            if let Some(file) = file.filter(|f| !Path::new(f).exists()) {
+                report.warning(path, format!("key file '{}' does not exist", file));
+            }
+        }
+    }
+
+    fn check_listeners(&self, report: &mut ValidationReport) {
+        let proxy_addr = self.proxy.listen_addr.as_deref();
+        let metrics_addr = self
+            .telemetry
+            .as_ref()
+            .and_then(|t| t.metrics_addr.as_deref());
+        for (path, addr) in [
+            ("proxy.listen_addr", proxy_addr),
+            ("telemetry.metrics_addr", metrics_addr),
+        ] {
+            if let Some(addr) = addr {
+                if let Err(reason) = parse_listen_addr(addr) {
+                    report.error(path, reason);
+                }
+            }
+        }
+
+        // Portman talks to labman from the operator's machine or LAN and must
+        // never be reachable from the internet or through the tunnel.
+        let path = "portman.listen_addr";
+        let addr = match parse_listen_addr(&self.portman.listen_addr) {
+            Ok(addr) => addr,
+            Err(reason) => return report.error(path, reason),
+        };
+        if addr.is_unspecified() {
+            report.error(
+                path,
+                format!(
+                    "'{}' listens on every interface, including the WireGuard tunnel; use a loopback or LAN address",
+                    addr
+                ),
+            );
+        } else if !is_local_addr(addr) {
+            report.error(
+                path,
+                format!(
+                    "'{}' is not a loopback, private or link-local address; the Portman socket must stay on this machine or the LAN",
+                    addr
+                ),
+            );
+        } else if let Some(wg_addr) = self.wireguard.address.as_deref() {
+            if parse_cidr(wg_addr).is_ok_and(|(wg, _)| wg == addr) {
+                report.error(
+                    path,
+                    format!(
+                        "'{}' is the WireGuard address; the Portman socket must not be reachable through the tunnel",
+                        addr
+                    ),
+                );
+            }
+        }
+    }
+
+    fn check_telemetry(&self, report: &mut ValidationReport) {
+        let Some(telemetry) = &self.telemetry else {
+            return;
+        };
+
+        for (path, value, allowed) in [
+            (
+                "telemetry.log_format",
+                telemetry.log_format.as_deref(),
+                &["text", "json"][..],
+            ),
+            (
+                "telemetry.log_output",
+                telemetry.log_output.as_deref(),
+                &["stdout", "file", "journald"][..],
+            ),
+        ] {
+            if let Some(value) = value.filter(|v| !allowed.contains(v)) {
+                report.push(
+                    Severity::Error,
+                    path.to_string(),
+                    format!(
+                        "unknown value '{}' (expected {})",
+                        value,
+                        allowed.join(", ")
+                    ),
+                    closest(value, allowed.iter().copied()),
+                );
+            }
+        }
+
+        let to_file = telemetry.log_output.as_deref() == Some("file");
+        match telemetry.log_file.as_deref().map(str::trim) {
+            None | Some("") if to_file => {
+                report.error("telemetry.log_file", "required when log_output is \"file\"")
+            }
+            Some(_) if !to_file => report.warning(
+                "telemetry.log_file",
+                "ignored unless log_output is \"file\"",
+            ),
+            _ => {}
+        }
+        if to_file && (telemetry.log_file_max_size_mb == 0 || telemetry.log_file_max_files == 0) {
+            report.error(
+                "telemetry",
+                "log_file_max_size_mb and log_file_max_files must be positive",
+            );
+        }
+    }
+
+    fn check_health(&self, report: &mut ValidationReport) {
+        let health = &self.health;
+        for (path, secs) in [
+            ("health.interval_secs", health.interval_secs),
+            (
+                "health.discovery_interval_secs",
+                health.discovery_interval_secs,
+            ),
+            ("health.timeout_secs", health.timeout_secs),
+        ] {
+            if secs == 0 {
+                report.error(path, "must be positive");
+            }
+        }
+
+        if health.interval_secs > 0 && health.timeout_secs > health.interval_secs {
+            report.warning(
+                "health.timeout_secs",
+                format!(
+                    "exceeds health.interval_secs ({}s); slow endpoints delay the next check",
+                    health.interval_secs
+                ),
+            );
+        }
+    }
+}
+
+pub(crate) fn endpoint_path(ep: &EndpointConfig, index: usize) -> String {
+    entry_path("endpoints", &ep.name, index)
+}
+
+fn model_path(model: &ModelConfig, index: usize) -> String {
+    entry_path("models", &model.id, index)
+}
+
+fn entry_path(section: &str, key: &str, index: usize) -> String {
+    if key.trim().is_empty() {
+        format!("{}[{}]", section, index)
+    } else {
+        format!("{}[{}]", section, key)
+    }
+}
+
+/// Parse an address with an optional prefix length (`10.0.0.1/32`).
+pub(crate) fn parse_cidr(entry: &str) -> std::result::Result<(IpAddr, u8), String> {
+    let entry = entry.trim();
+    if entry.is_empty() {
+        return Err("must not be empty".to_string());
+    }
+    let invalid = || format!("'{}' is not a valid address or CIDR range", entry);
+
+    let (addr, prefix) = match entry.split_once('/') {
+        Some((addr, prefix)) => (addr, Some(prefix)),
+        None => (entry, None),
+    };
+    let addr: IpAddr = addr.parse().map_err(|_| invalid())?;
+    let max = if addr.is_ipv4() { 32 } else { 128 };
+    let prefix = match prefix {
+        Some(p) => p
+            .parse::<u8>()
+            .ok()
+            .filter(|p| *p <= max)
+            .ok_or_else(invalid)?,
+        None => max,
+    };
+    Ok((addr, prefix))
+}
+
+/// Parse a listener address, which is an IP address without a port.
+fn parse_listen_addr(addr: &str) -> std::result::Result<IpAddr, String> {
+    addr.trim().parse().map_err(|_| {
+        format!(
+            "'{}' is not a valid IP address (the port is configured separately)",
+            addr.trim()
+        )
+    })
+}
+
+/// Whether `addr` is a loopback, private (RFC 1918, IPv6 unique local) or
+/// link-local address.
+fn is_local_addr(addr: IpAddr) -> bool {
+    match addr {
+        IpAddr::V4(v4) => v4.is_loopback() || v4.is_private() || v4.is_link_local(),
+        IpAddr::V6(v6) => v6.is_loopback() || v6.is_unique_local() || v6.is_unicast_link_local(),
+    }
+}
+
+/// The RFC 1918 range an IPv4 network overlaps, if any.
+pub(crate) fn private_range_overlap(addr: u32, prefix: u8) -> Option<&'static str> {
+    const PRIVATE: [(u32, u8, &str); 3] = [
+        (0x0a00_0000, 8, "10.0.0.0/8"),
+        (0xac10_0000, 12, "172.16.0.0/12"),
+        (0xc0a8_0000, 16, "192.168.0.0/16"),
+    ];
+    let mask = |bits: u8| u32::MAX.checked_shl(32 - bits as u32).unwrap_or(0);
+
+    PRIVATE
+        .iter()
+        .find(|(net, bits, _)| {
+            let m = mask(prefix.min(*bits));
+            addr & m == net & m
+        })
+        .map(|(_, _, name)| *name)
+}
+
+/// The candidate closest to `input`, if any is close enough to be a typo.
+fn closest<'a>(input: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    candidates
+        .into_iter()
+        .map(|c| (strsim::jaro_winkler(input, c), c))
+        .filter(|(score, _)| *score > 0.8)
+        .max_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, c)| c.to_string())
+}
+
+/// Warnings for keys in a raw configuration table that no setting uses.
+pub(crate) fn unknown_keys(table: &Table) -> Vec<ValidationIssue> {
+    let mut report = ValidationReport::default();
+    check_keys(table, CONFIG_SCHEMA, "", &mut report);
+    report.issues
+}
+
+fn check_keys(
+    table: &Table,
+    known: &'static [SchemaField],
+    prefix: &str,
+    report: &mut ValidationReport,
+) {
+    for (key, value) in table {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+
+        let Some(field) = known.iter().find(|f| f.name == key) else {
+            report.push(
+                Severity::Warning,
+                path,
+                "unknown key, ignored".to_string(),
+                closest(key, known.iter().map(|f| f.name)),
+            );
+            continue;
+        };
+
+        // Type mismatches are reported by the deserializer.
+        match (field.ty, value) {
+            (SchemaType::Table(nested), Value::Table(table)) => {
+                check_keys(table, nested, &path, report)
+            }
+            (SchemaType::Tables(nested), Value::Array(items)) => {
+                let id_key = match key.as_str() {
+                    "endpoints" if prefix.is_empty() => Some("name"),
+                    "models" if prefix.is_empty() => Some("id"),
+                    _ => None,
+                };
+                for (index, item) in items.iter().enumerate() {
+                    let Value::Table(item) = item else { continue };
+                    let id = id_key
+                        .and_then(|k| item.get(k))
+                        .and_then(Value::as_str)
+                        .unwrap_or_default();
+                    check_keys(item, nested, &entry_path(&path, id, index), report);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(toml: &str) -> (LabmanConfig, Vec<ValidationIssue>) {
+        let base = r#"
+[control_plane]
+base_url = "https://control.example.com/api/v1"
+node_token = "token"
+
+[wireguard]
+
+[proxy]
+listen_port = 8080
+"#;
+        let toml = format!("{}{}", base, toml);
+        let table: Table = toml::from_str(&toml).expect("parse table");
+        let cfg = toml::from_str(&toml).expect("parse config");
+        (cfg, unknown_keys(&table))
+    }
+
+    fn find<'a>(report: &'a ValidationReport, path: &str) -> &'a ValidationIssue {
+        report
+            .issues
+            .iter()
+            .find(|i| i.path == path)
+            .unwrap_or_else(|| panic!("no issue for {} in {:#?}", path, report.issues))
+    }
+
+    #[test]
+    fn report_collects_every_problem_with_paths() {
+        let (mut cfg, _) = config(
+            r#"
+[[endpoints]]
+name = "gpu-1"
+base_url = "ftp://127.0.0.1:8000"
+models_include = []
+
+[[endpoints]]
+name = "gpu-1"
+base_url = "http://127.0.0.1:8001"
+
+[[models]]
+id = "llama"
+endpoints = ["gpu1"]
+"#,
+        );
+        cfg.wireguard.allowed_ips = [
+            "10.90.0.1/32",
+            "192.168.0.0/16",
+            "0.0.0.0/0",
+            "10.0.0.300/8",
+        ]
+        .map(String::from)
+        .to_vec();
+        cfg.wireguard.private_key_path = Some("/nonexistent/labman/wg.key".to_string());
+
+        let report = cfg.validation_report();
+        assert!(report.has_errors());
+
+        let errors: Vec<_> = report.errors().map(|i| i.path.as_str()).collect();
+        assert_eq!(
+            errors,
+            vec![
+                "endpoints[gpu-1].base_url",
+                "endpoints[gpu-1].name",
+                "models[llama].endpoints",
+                "wireguard.allowed_ips[3]",
+            ]
+        );
+        assert_eq!(
+            find(&report, "models[llama].endpoints")
+                .suggestion
+                .as_deref(),
+            Some("gpu-1")
+        );
+
+        let warnings: Vec<_> = report.warnings().map(|i| i.path.as_str()).collect();
+        assert_eq!(
+            warnings,
+            vec![
+                "endpoints[gpu-1].models_include",
+                "wireguard.allowed_ips[1]",
+                "wireguard.allowed_ips[2]",
+                "wireguard.private_key_path",
+            ]
+        );
+        assert!(find(&report, "wireguard.allowed_ips[1]")
+            .message
+            .contains("192.168.0.0/16"));
+
+        // `validate` still fails on the first error.
+        let err = cfg.validate().unwrap_err().to_string();
+        assert!(err.contains("endpoints[gpu-1].base_url"), "{}", err);
+    }
+
+    #[test]
+    fn portman_listener_must_stay_local() {
+        let errors = |addr: &str| {
+            let mut cfg = crate::tests::test_config();
+            cfg.wireguard.address = Some("10.90.0.2/32".to_string());
+            cfg.portman.listen_addr = addr.to_string();
+            cfg.validation_report()
+                .errors()
+                .filter(|i| i.path == "portman.listen_addr")
+                .count()
+        };
+
+        for local in ["127.0.0.1", "192.168.1.10", "169.254.0.5", "::1", "fd00::1"] {
+            assert_eq!(errors(local), 0, "{}", local);
+        }
+        for exposed in [
+            "0.0.0.0",
+            "::",
+            "203.0.113.7",
+            "10.90.0.2",
+            "localhost:9100",
+        ] {
+            assert_eq!(errors(exposed), 1, "{}", exposed);
+        }
+
+        let mut cfg = crate::tests::test_config();
+        cfg.health.timeout_secs = 0;
+        cfg.proxy.listen_addr = Some("127.0.0.1:8080".to_string());
+        let paths: Vec<_> = cfg
+            .validation_report()
+            .errors()
+            .map(|i| i.path.clone())
+            .collect();
+        assert_eq!(paths, vec!["proxy.listen_addr", "health.timeout_secs"]);
+    }
+
+    #[test]
+    fn telemetry_output_settings_are_checked() {
+        let mut cfg = crate::tests::test_config();
+        cfg.telemetry = Some(
+            toml::from_str(
+                r#"
+log_format = "jsn"
+log_output = "file"
+"#,
+            )
+            .unwrap(),
+        );
+        let report = cfg.validation_report();
+        let found: Vec<_> = report
+            .errors()
+            .map(|i| (i.path.as_str(), i.suggestion.as_deref()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("telemetry.log_format", Some("json")),
+                ("telemetry.log_file", None),
+            ]
+        );
+
+        let telemetry = cfg.telemetry.as_mut().unwrap();
+        telemetry.log_format = Some("json".to_string());
+        telemetry.log_output = Some("journald".to_string());
+        telemetry.log_file = Some("/var/log/labman/labmand.log".to_string());
+        let report = cfg.validation_report();
+        assert_eq!(report.errors().count(), 0);
+        assert!(report.warnings().any(|i| i.path == "telemetry.log_file"));
+    }
+
+    #[test]
+    fn unknown_keys_are_reported_with_suggestions() {
+        let (_, issues) = config(
+            r#"
+[telemetry]
+log_levl = "debug"
+
+[[endpoints]]
+name = "gpu-1"
+base_url = "http://127.0.0.1:8000/v1"
+model_include = ["llama*"]
+
+[endpoints.schedule]
+windows = [{ days = ["mon"], strat = "08:00", end = "18:00" }]
+
+[[models]]
+id = "llama"
+frobnicate = true
+"#,
+        );
+
+        let found: Vec<_> = issues
+            .iter()
+            .map(|i| (i.path.as_str(), i.suggestion.as_deref()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("endpoints[gpu-1].model_include", Some("models_include")),
+                ("endpoints[gpu-1].schedule.windows[0].strat", Some("start")),
+                ("models[llama].frobnicate", None),
+                ("telemetry.log_levl", Some("log_level")),
+            ]
+        );
+        assert!(issues.iter().all(|i| i.severity == Severity::Warning));
+    }
+
+    #[test]
+    fn example_config_has_no_unknown_keys() {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../../bin/labman-daemon/example.toml"
+        );
+        let table: Table = toml::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        assert_eq!(unknown_keys(&table), Vec::new());
+    }
+}
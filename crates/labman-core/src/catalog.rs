@@ -0,0 +1,133 @@
+//! Slug catalog types.
+//!
+//! A node resolves opaque model slugs to `(tenant, endpoint, model_id)`
+//! triples (see [`crate::slug`]). The catalog publishes that mapping,
+//! together with health and capacity hints, so the control plane can
+//! schedule by slug without recomputing hashes itself.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::endpoint::EndpointLoad;
+use crate::slug::SlugScheme;
+
+/// All model slugs a node currently knows about.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SlugCatalog {
+    /// Scheme the slugs were derived with
+    pub slug_scheme: SlugScheme,
+
+    /// When the catalog was assembled
+    pub generated_at: DateTime<Utc>,
+
+    /// One entry per slug, ordered by endpoint name and model id
+    pub entries: Vec<SlugCatalogEntry>,
+}
+
+impl SlugCatalog {
+    /// Create an empty catalog for the given scheme
+    pub fn new(slug_scheme: SlugScheme) -> Self {
+        Self {
+            slug_scheme,
+            generated_at: Utc::now(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Look up the entry for a slug
+    pub fn get(&self, slug: &str) -> Option<&SlugCatalogEntry> {
+        self.entries.iter().find(|e| e.slug == slug)
+    }
+}
+
+/// A single routable (or temporarily unroutable) slug.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SlugCatalogEntry {
+    /// Opaque slug to send as the OpenAI `model` field
+    pub slug: String,
+
+    /// Tenant the model belongs to (`None` for the operator's default tenant)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
+
+    /// Configured endpoint name
+    pub endpoint: String,
+
+    /// Concrete model identifier on the endpoint
+    pub model_id: String,
+
+    /// Whether requests for this slug are currently accepted
+    pub health: SlugHealth,
+
+    /// Hints for capacity-aware scheduling
+    #[serde(default)]
+    pub capacity: CapacityHints,
+}
+
+/// Routing health of a slug.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SlugHealth {
+    /// Requests are routed to the endpoint
+    Available,
+
+    /// The endpoint or model is down; requests get `503` until it recovers
+    Unavailable,
+}
+
+/// Capacity information about the endpoint and model behind a slug.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CapacityHints {
+    /// Configured concurrency limit of the endpoint
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_concurrent: Option<usize>,
+
+    /// Requests currently in flight through labman on the endpoint
+    #[serde(default)]
+    pub active_requests: usize,
+
+    /// Configured concurrency limit of this model on the endpoint
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_max_concurrent: Option<usize>,
+
+    /// Runtime-reported load of the endpoint, if scraped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub load: Option<EndpointLoad>,
+
+    /// Model context window in tokens
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context_length: Option<u64>,
+
+    /// Maximum number of output tokens per request
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catalog_round_trips_through_json() {
+        let mut catalog = SlugCatalog::new(SlugScheme::default());
+        catalog.entries.push(SlugCatalogEntry {
+            slug: "abc".to_string(),
+            tenant: None,
+            endpoint: "gpu-1".to_string(),
+            model_id: "llama3".to_string(),
+            health: SlugHealth::Unavailable,
+            capacity: CapacityHints {
+                max_concurrent: Some(4),
+                ..Default::default()
+            },
+        });
+
+        let json = serde_json::to_value(&catalog).unwrap();
+        assert_eq!(json["entries"][0]["health"], "unavailable");
+        assert!(json["entries"][0].get("tenant").is_none());
+
+        let back: SlugCatalog = serde_json::from_value(json).unwrap();
+        assert_eq!(back, catalog);
+        assert_eq!(back.get("abc").unwrap().capacity.max_concurrent, Some(4));
+    }
+}
@@ -7,6 +7,50 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+/// Scheme prefix for endpoints reached over a Unix domain socket.
+///
+/// Such base URLs take the form `unix://<socket path>:<http path>`, e.g.
+/// `unix:///run/ollama.sock:/v1`. The HTTP path part may be omitted, in which
+/// case requests are issued relative to `/`.
+pub const UNIX_SOCKET_SCHEME: &str = "unix://";
+
+/// Host used in the HTTP request line when talking to a Unix socket upstream.
+///
+/// The socket path determines where the connection goes; the host is only
+/// used for the `Host` header, which most runtimes ignore.
+const UNIX_SOCKET_HTTP_HOST: &str = "localhost";
+
+/// Split a `unix://` base URL into its socket path and HTTP path prefix.
+///
+/// Returns `None` if `base_url` does not use the `unix://` scheme or the
+/// socket path is empty.
+///
+/// ```rust
+/// use labman_core::endpoint::parse_unix_base_url;
+///
+/// assert_eq!(
+///     parse_unix_base_url("unix:///run/ollama.sock:/v1"),
+///     Some(("/run/ollama.sock", "/v1"))
+/// );
+/// assert_eq!(parse_unix_base_url("http://127.0.0.1:11434/v1"), None);
+/// ```
+pub fn parse_unix_base_url(base_url: &str) -> Option<(&str, &str)> {
+    let rest = base_url.trim().strip_prefix(UNIX_SOCKET_SCHEME)?;
+
+    // The HTTP path, if present, is introduced by the last ":/" so that
+    // socket paths without a path suffix are still accepted.
+    let (socket_path, http_path) = match rest.rfind(":/") {
+        Some(idx) => (&rest[..idx], &rest[idx + 1..]),
+        None => (rest, ""),
+    };
+
+    if socket_path.is_empty() {
+        return None;
+    }
+
+    Some((socket_path, http_path))
+}
+
 /// An LLM inference endpoint.
 ///
 /// Represents a single OpenAI-compatible API endpoint (Ollama, vLLM, llama.cpp, etc.)
@@ -33,6 +77,14 @@ pub struct Endpoint {
 
     /// Number of consecutive health check failures
     pub consecutive_failures: u32,
+
+    /// Inference runtime detected behind this endpoint, if fingerprinted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub runtime: Option<RuntimeKind>,
+
+    /// Most recent load reported by the runtime itself, if scraped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub load: Option<EndpointLoad>,
 }
 
 impl Endpoint {
@@ -46,6 +98,8 @@ impl Endpoint {
             last_checked: None,
             last_success: None,
             consecutive_failures: 0,
+            runtime: None,
+            load: None,
         }
     }
 
@@ -81,6 +135,28 @@ impl Endpoint {
     pub fn model_count(&self) -> usize {
         self.models.len()
     }
+
+    /// Socket path for endpoints reached over a Unix domain socket, if any.
+    pub fn unix_socket_path(&self) -> Option<&str> {
+        parse_unix_base_url(&self.base_url).map(|(socket, _)| socket)
+    }
+
+    /// Base URL to use when issuing HTTP requests to this endpoint.
+    ///
+    /// For TCP endpoints this is `base_url` without a trailing slash. For Unix
+    /// socket endpoints the socket path is dropped and the HTTP path is
+    /// rooted at a placeholder host, e.g. `http://localhost/v1`; the HTTP
+    /// client is expected to be bound to the socket separately.
+    pub fn http_base_url(&self) -> String {
+        match parse_unix_base_url(&self.base_url) {
+            Some((_, path)) => format!(
+                "http://{}{}",
+                UNIX_SOCKET_HTTP_HOST,
+                path.trim_end_matches('/')
+            ),
+            None => self.base_url.trim().trim_end_matches('/').to_string(),
+        }
+    }
 }
 
 /// Health status of an endpoint.
@@ -110,6 +186,85 @@ impl fmt::Display for EndpointHealth {
     }
 }
 
+/// Inference runtime serving an endpoint.
+///
+/// Detected during model discovery from characteristic routes, headers and
+/// `owned_by` values. `Unknown` means the endpoint was probed but did not
+/// match any known runtime; it is still treated as generic OpenAI.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum RuntimeKind {
+    /// Ollama
+    Ollama,
+
+    /// vLLM
+    Vllm,
+
+    /// llama.cpp `llama-server`
+    LlamaCpp,
+
+    /// mistral.rs
+    MistralRs,
+
+    /// Hugging Face Text Generation Inference
+    Tgi,
+
+    /// Probed, but not recognised
+    Unknown,
+}
+
+impl fmt::Display for RuntimeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ollama => write!(f, "ollama"),
+            Self::Vllm => write!(f, "vllm"),
+            Self::LlamaCpp => write!(f, "llama_cpp"),
+            Self::MistralRs => write!(f, "mistral_rs"),
+            Self::Tgi => write!(f, "tgi"),
+            Self::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// Load on an endpoint as reported by its runtime.
+///
+/// Unlike labman's own request accounting, this includes traffic from other
+/// clients sharing the same server. Fields are `None` when the runtime does
+/// not expose them.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EndpointLoad {
+    /// Requests currently being processed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub running_requests: Option<u64>,
+
+    /// Requests queued waiting for a free slot
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub waiting_requests: Option<u64>,
+
+    /// KV-cache utilisation in the range `0.0..=1.0`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kv_cache_usage: Option<f64>,
+
+    /// Total number of parallel slots (llama.cpp)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_slots: Option<u64>,
+
+    /// When these values were scraped
+    pub scraped_at: DateTime<Utc>,
+}
+
+impl EndpointLoad {
+    /// Relative load score used to rank endpoints; lower is less loaded.
+    ///
+    /// Queued requests weigh double since they indicate saturation; the
+    /// KV-cache ratio breaks ties between otherwise equal endpoints.
+    pub fn score(&self) -> f64 {
+        let running = self.running_requests.unwrap_or(0) as f64;
+        let waiting = self.waiting_requests.unwrap_or(0) as f64;
+        running + 2.0 * waiting + self.kv_cache_usage.unwrap_or(0.0)
+    }
+}
+
 /// Descriptor for a model available on an endpoint.
 ///
 /// This represents a model as returned by the OpenAI /v1/models API.
@@ -118,6 +273,11 @@ pub struct ModelDescriptor {
     /// Model identifier (e.g., "llama3.2:3b", "gpt-4")
     pub id: String,
 
+    /// Identifier the upstream runtime uses, when an operator alias presents
+    /// the model under a different `id`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upstream_id: Option<String>,
+
     /// Unix timestamp when this model was created (optional)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub created: Option<i64>,
@@ -126,6 +286,39 @@ pub struct ModelDescriptor {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub owned_by: Option<String>,
 
+    /// Model family as reported by the runtime (e.g. "llama", "qwen2")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family: Option<String>,
+
+    /// Human-readable parameter count (e.g. "8.0B")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parameter_size: Option<String>,
+
+    /// Quantization level of the loaded weights (e.g. "Q4_K_M")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantization: Option<String>,
+
+    /// Maximum context length in tokens
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_length: Option<u64>,
+
+    /// Maximum number of tokens a single completion may generate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u64>,
+
+    /// Input modalities accepted by the model (e.g. "text", "image")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub modalities: Vec<String>,
+
+    /// Free-form tags assigned by the operator (e.g. "chat", "code")
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    /// Request features the model supports, from discovery or operator
+    /// overrides; `None` when unknown
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<Vec<ModelCapability>>,
+
     /// Additional metadata about the model (optional)
     #[serde(flatten)]
     pub metadata: serde_json::Value,
@@ -136,12 +329,40 @@ impl ModelDescriptor {
     pub fn new<S: Into<String>>(id: S) -> Self {
         Self {
             id: id.into(),
+            upstream_id: None,
             created: None,
             owned_by: None,
+            family: None,
+            parameter_size: None,
+            quantization: None,
+            context_length: None,
+            max_output_tokens: None,
+            modalities: Vec::new(),
+            tags: Vec::new(),
+            capabilities: None,
             metadata: serde_json::Value::Null,
         }
     }
 
+    /// Identifier to send to the upstream runtime for this model
+    pub fn upstream_model_id(&self) -> &str {
+        self.upstream_id.as_deref().unwrap_or(&self.id)
+    }
+
+    /// Whether the model supports `capability`, or `None` if unknown.
+    ///
+    /// Vision support is also derived from `modalities` when no explicit
+    /// capability list is known.
+    pub fn supports(&self, capability: ModelCapability) -> Option<bool> {
+        if let Some(capabilities) = &self.capabilities {
+            return Some(capabilities.contains(&capability));
+        }
+        if capability == ModelCapability::Vision && !self.modalities.is_empty() {
+            return Some(self.modalities.iter().any(|m| m == "image"));
+        }
+        None
+    }
+
     /// Create a model descriptor with full information
     pub fn with_details<S: Into<String>>(
         id: S,
@@ -150,13 +371,47 @@ impl ModelDescriptor {
     ) -> Self {
         Self {
             id: id.into(),
+            upstream_id: None,
             created,
             owned_by,
+            family: None,
+            parameter_size: None,
+            quantization: None,
+            context_length: None,
+            max_output_tokens: None,
+            modalities: Vec::new(),
+            tags: Vec::new(),
+            capabilities: None,
             metadata: serde_json::Value::Null,
         }
     }
 }
 
+/// A request feature that not every model supports.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelCapability {
+    /// Function / tool calling (`tools`)
+    Tools,
+
+    /// Image inputs in message content
+    Vision,
+
+    /// Structured output (`response_format` of type `json_object` or
+    /// `json_schema`)
+    JsonMode,
+}
+
+impl fmt::Display for ModelCapability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tools => write!(f, "tools"),
+            Self::Vision => write!(f, "vision"),
+            Self::JsonMode => write!(f, "json_mode"),
+        }
+    }
+}
+
 /// OpenAI-compatible model list response.
 ///
 /// This is the expected format from GET /v1/models endpoints.
@@ -236,6 +491,28 @@ mod tests {
         assert_eq!(endpoint.model_count(), 2);
     }
 
+    #[test]
+    fn test_unix_socket_base_url() {
+        assert_eq!(
+            parse_unix_base_url("unix:///run/ollama.sock:/v1"),
+            Some(("/run/ollama.sock", "/v1"))
+        );
+        assert_eq!(
+            parse_unix_base_url("unix:///run/llama.sock"),
+            Some(("/run/llama.sock", ""))
+        );
+        assert_eq!(parse_unix_base_url("unix://:/v1"), None);
+        assert_eq!(parse_unix_base_url("http://localhost:8000/v1"), None);
+
+        let endpoint = Endpoint::new("sock", "unix:///run/ollama.sock:/v1/");
+        assert_eq!(endpoint.unix_socket_path(), Some("/run/ollama.sock"));
+        assert_eq!(endpoint.http_base_url(), "http://localhost/v1");
+
+        let tcp = Endpoint::new("tcp", "http://localhost:8000/v1/");
+        assert_eq!(tcp.unix_socket_path(), None);
+        assert_eq!(tcp.http_base_url(), "http://localhost:8000/v1");
+    }
+
     #[test]
     fn test_endpoint_health_display() {
         let healthy = EndpointHealth::Healthy;
@@ -260,6 +537,80 @@ mod tests {
         assert_eq!(deserialized.id, "test-model");
     }
 
+    #[test]
+    fn test_model_descriptor_optional_details() {
+        let mut model = ModelDescriptor::new("llama3.1:8b");
+        let json = serde_json::to_string(&model).unwrap();
+        assert!(!json.contains("context_length"));
+
+        model.parameter_size = Some("8.0B".to_string());
+        model.quantization = Some("Q4_K_M".to_string());
+        model.context_length = Some(131072);
+
+        let json = serde_json::to_string(&model).unwrap();
+        let deserialized: ModelDescriptor = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.parameter_size.as_deref(), Some("8.0B"));
+        assert_eq!(deserialized.quantization.as_deref(), Some("Q4_K_M"));
+        assert_eq!(deserialized.context_length, Some(131072));
+        assert!(!json.contains("modalities"));
+
+        model.max_output_tokens = Some(4096);
+        model.modalities = vec!["text".to_string(), "image".to_string()];
+        let json = serde_json::to_string(&model).unwrap();
+        let deserialized: ModelDescriptor = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.max_output_tokens, Some(4096));
+        assert_eq!(deserialized.modalities, vec!["text", "image"]);
+    }
+
+    #[test]
+    fn test_model_capabilities() {
+        let mut model = ModelDescriptor::new("llava");
+        assert_eq!(model.supports(ModelCapability::Vision), None);
+
+        model.modalities = vec!["text".to_string(), "image".to_string()];
+        assert_eq!(model.supports(ModelCapability::Vision), Some(true));
+        assert_eq!(model.supports(ModelCapability::Tools), None);
+
+        model.capabilities = Some(vec![ModelCapability::JsonMode]);
+        assert_eq!(model.supports(ModelCapability::Vision), Some(false));
+        assert_eq!(model.supports(ModelCapability::JsonMode), Some(true));
+
+        let json = serde_json::to_string(&model).unwrap();
+        assert!(json.contains("\"capabilities\":[\"json_mode\"]"));
+    }
+
+    #[test]
+    fn test_endpoint_load_score() {
+        let idle = EndpointLoad {
+            running_requests: Some(0),
+            waiting_requests: Some(0),
+            kv_cache_usage: Some(0.1),
+            total_slots: None,
+            scraped_at: Utc::now(),
+        };
+        let queued = EndpointLoad {
+            running_requests: Some(1),
+            waiting_requests: Some(2),
+            kv_cache_usage: None,
+            ..idle.clone()
+        };
+        assert!(idle.score() < queued.score());
+        assert_eq!(queued.score(), 5.0);
+    }
+
+    #[test]
+    fn test_runtime_kind_serialization() {
+        let mut endpoint = Endpoint::new("test", "http://localhost:8000/v1");
+        assert!(!serde_json::to_string(&endpoint)
+            .unwrap()
+            .contains("runtime"));
+
+        endpoint.runtime = Some(RuntimeKind::LlamaCpp);
+        let json = serde_json::to_string(&endpoint).unwrap();
+        assert!(json.contains("\"runtime\":\"llama_cpp\""));
+        assert_eq!(RuntimeKind::LlamaCpp.to_string(), "llama_cpp");
+    }
+
     #[test]
     fn test_model_list_response() {
         let models = vec![
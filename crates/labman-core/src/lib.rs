@@ -5,6 +5,7 @@
 //! - **Error types**: Comprehensive error handling with [`LabmanError`] and [`Result`]
 //! - **Endpoint types**: Representation of LLM endpoints, health tracking, and model discovery
 //! - **Node types**: Node identity, capabilities, and status reporting for control plane communication
+//! - **Catalog types**: The slug catalog published to the control plane
 //! - **Helpers**: Shared utilities such as model slug encoding for control‑plane routing.
 //!
 //! # Overview
@@ -53,18 +54,24 @@
 //! }
 //! ```
 
+pub mod catalog;
 pub mod endpoint;
 pub mod error;
 pub mod node;
 pub mod slug;
 
 // Re-export commonly used types for convenience
-pub use endpoint::{Endpoint, EndpointHealth, ModelDescriptor, ModelListResponse};
+pub use catalog::{CapacityHints, SlugCatalog, SlugCatalogEntry, SlugHealth};
+pub use endpoint::{
+    Endpoint, EndpointHealth, EndpointLoad, ModelCapability, ModelDescriptor, ModelListResponse,
+    RuntimeKind,
+};
 pub use error::{LabmanError, Result};
 pub use node::{
     HeartbeatRequest, HeartbeatResponse, NodeCapabilities, NodeInfo, NodeState, NodeStatus,
     RegistrationRequest, RegistrationResponse,
 };
+pub use slug::{SlugEndpointKey, SlugScheme, SlugVersion};
 
 /// Prelude module for convenient imports.
 ///
@@ -75,7 +82,7 @@ pub use node::{
 /// use labman_core::prelude::*;
 /// ```
 pub mod prelude {
-    pub use crate::endpoint::{Endpoint, EndpointHealth, ModelDescriptor};
+    pub use crate::endpoint::{Endpoint, EndpointHealth, ModelDescriptor, RuntimeKind};
     pub use crate::error::{LabmanError, Result};
     pub use crate::node::{NodeCapabilities, NodeInfo, NodeState, NodeStatus};
 }
@@ -8,7 +8,8 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-use crate::endpoint::ModelDescriptor;
+use crate::endpoint::{EndpointLoad, ModelDescriptor};
+use crate::slug::SlugScheme;
 
 /// Node identity and capabilities.
 ///
@@ -97,6 +98,14 @@ pub struct NodeCapabilities {
     #[serde(default = "default_true")]
     pub supports_completions: bool,
 
+    /// Runtime-reported load per endpoint name, for endpoints that expose it
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub endpoint_load: HashMap<String, EndpointLoad>,
+
+    /// Scheme used to derive the model slugs this node accepts
+    #[serde(default)]
+    pub slug_scheme: SlugScheme,
+
     /// Additional metadata
     #[serde(flatten)]
     pub metadata: HashMap<String, serde_json::Value>,
@@ -116,16 +125,30 @@ impl NodeCapabilities {
             supports_streaming: true,
             supports_chat: true,
             supports_completions: true,
+            endpoint_load: HashMap::new(),
+            slug_scheme: SlugScheme::default(),
             metadata: HashMap::new(),
         }
     }
 
+    /// Set the slug scheme
+    pub fn with_slug_scheme(mut self, scheme: SlugScheme) -> Self {
+        self.slug_scheme = scheme;
+        self
+    }
+
     /// Set maximum concurrent requests
     pub fn with_max_concurrent(mut self, max: usize) -> Self {
         self.max_concurrent_requests = Some(max);
         self
     }
 
+    /// Record the runtime-reported load of an endpoint
+    pub fn with_endpoint_load<S: Into<String>>(mut self, endpoint: S, load: EndpointLoad) -> Self {
+        self.endpoint_load.insert(endpoint.into(), load);
+        self
+    }
+
     /// Add custom metadata
     pub fn with_metadata<S: Into<String>>(mut self, key: S, value: serde_json::Value) -> Self {
         self.metadata.insert(key.into(), value);
@@ -35,9 +35,147 @@
 //! Note: this is not intended as a security primitive. It is a convenient
 //! identifier for scheduling and accounting logic in a distributed, partially
 //! trustless network.
+//!
+//! # Versioned schemes
+//!
+//! The scheme above is version 0 (`SlugVersion::V0`) and remains the default
+//! so existing control planes keep working. It has two weaknesses: a 64-bit
+//! digest, and an endpoint slug derived from the `base_url`, so moving an
+//! endpoint to a new IP changes every slug it serves.
+//!
+//! Version 1 (`SlugVersion::V1`) slugs carry a `v1-` prefix, which cannot
+//! occur in a v0 slug (base62 has no `-`), so both sides can tell which
+//! scheme produced a slug. They are computed as:
+//!
+//! ```text
+//! slug_input = "labman-slug-v1" + "\n" + tenant + "\n" + key_kind + "\n" + endpoint_key + "\n" + model_id
+//! slug_hash  = SHA-256(slug_input)
+//! slug_bytes = first 8 (or 16, with `long_digest`) bytes of slug_hash
+//! slug       = "v1-" + base62(slug_bytes)
+//! ```
+//!
+//! where `key_kind` is `url` (and `endpoint_key` the endpoint slug) or `name`
+//! (and `endpoint_key` the configured endpoint name). The node advertises
+//! the [`SlugScheme`] it uses in its capabilities so the control plane can
+//! reproduce its slugs.
 
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
+/// Version of the slug encoding scheme.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlugVersion {
+    /// Unprefixed 8-byte digest of `tenant`, endpoint slug and model id.
+    #[default]
+    V0,
+
+    /// `v1-` prefixed digest with a configurable endpoint key and length.
+    V1,
+}
+
+impl SlugVersion {
+    /// Determine which scheme version produced `slug`.
+    pub fn of(slug: &str) -> SlugVersion {
+        if slug.starts_with(V1_PREFIX) {
+            SlugVersion::V1
+        } else {
+            SlugVersion::V0
+        }
+    }
+}
+
+/// What identifies the endpoint in a slug.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlugEndpointKey {
+    /// The schema-stripped `base_url` (changes when the endpoint moves).
+    #[default]
+    BaseUrl,
+
+    /// The configured endpoint name (stable across address changes).
+    Name,
+}
+
+/// A complete slug scheme: version plus its parameters.
+///
+/// The default is the legacy v0 scheme implemented by [`encode_model_slug`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SlugScheme {
+    /// Scheme version.
+    #[serde(default)]
+    pub version: SlugVersion,
+
+    /// What identifies the endpoint (v1 only).
+    #[serde(default)]
+    pub endpoint_key: SlugEndpointKey,
+
+    /// Use a 16-byte instead of an 8-byte digest (v1 only).
+    #[serde(default)]
+    pub long_digest: bool,
+}
+
+const V1_PREFIX: &str = "v1-";
+
+impl SlugScheme {
+    /// Check that the parameters are supported by the selected version.
+    ///
+    /// Returns a human-readable reason on failure.
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        if self.version == SlugVersion::V0 {
+            if self.endpoint_key != SlugEndpointKey::BaseUrl {
+                return Err("slug scheme v0 only supports endpoint_key = \"base_url\"".to_string());
+            }
+            if self.long_digest {
+                return Err("slug scheme v0 does not support long_digest".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Encode a model slug under this scheme.
+    ///
+    /// `endpoint_name` and `endpoint_slug` both describe the endpoint; which
+    /// one is used depends on `endpoint_key` (v0 always uses the slug).
+    pub fn encode(
+        &self,
+        tenant: &str,
+        endpoint_name: &str,
+        endpoint_slug: &str,
+        model_id: &str,
+    ) -> String {
+        match self.version {
+            SlugVersion::V0 => encode_model_slug(tenant, endpoint_slug, model_id),
+            SlugVersion::V1 => {
+                let (key_kind, key) = match self.endpoint_key {
+                    SlugEndpointKey::BaseUrl => ("url", endpoint_slug),
+                    SlugEndpointKey::Name => ("name", endpoint_name),
+                };
+
+                let mut hasher = Sha256::new();
+                for part in ["labman-slug-v1", tenant, key_kind, key] {
+                    hasher.update(part.as_bytes());
+                    hasher.update(b"\n");
+                }
+                hasher.update(model_id.as_bytes());
+                let digest = hasher.finalize();
+
+                let value = if self.long_digest {
+                    let mut buf = [0u8; 16];
+                    buf.copy_from_slice(&digest[..16]);
+                    u128::from_be_bytes(buf)
+                } else {
+                    let mut buf = [0u8; 8];
+                    buf.copy_from_slice(&digest[..8]);
+                    u64::from_be_bytes(buf) as u128
+                };
+
+                format!("{}{}", V1_PREFIX, base62_encode(value))
+            }
+        }
+    }
+}
+
 /// Encode a `(tenant, endpoint_slug, model_id)` triple into an opaque model
 /// slug suitable for use as the OpenAI `model` field.
 ///
@@ -75,17 +213,17 @@ pub fn encode_model_slug(tenant: &str, endpoint_slug: &str, model_id: &str) -> S
     // Interpret the prefix as a big-endian u64.
     let mut buf = [0u8; 8];
     buf.copy_from_slice(prefix);
-    let mut value = u64::from_be_bytes(buf);
+    let value = u64::from_be_bytes(buf);
 
     // Base62-encode the u64 to get a compact, URL-safe slug.
-    base62_encode_u64(value)
+    base62_encode(value as u128)
 }
 
-/// Base62-encode a u64 value.
+/// Base62-encode an unsigned value.
 ///
-/// This is sufficient for the 8-byte prefix of the SHA-256 hash used above and
-/// keeps the slug short and URL-safe.
-fn base62_encode_u64(mut value: u64) -> String {
+/// This covers both the 8- and 16-byte prefixes of the SHA-256 hashes used
+/// above and keeps slugs short and URL-safe.
+fn base62_encode(mut value: u128) -> String {
     const ALPHABET: &[u8; 62] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
 
     if value == 0 {
@@ -134,4 +272,59 @@ mod tests {
         assert!(s.len() <= 11);
         assert!(!s.is_empty());
     }
+
+    #[test]
+    fn default_scheme_matches_legacy_encoding() {
+        let scheme = SlugScheme::default();
+        assert_eq!(
+            scheme.encode("t", "gpu-1", "10.6.0.213:11434/v1", "m"),
+            encode_model_slug("t", "10.6.0.213:11434/v1", "m")
+        );
+        assert_eq!(
+            SlugVersion::of(&encode_model_slug("t", "e", "m")),
+            SlugVersion::V0
+        );
+    }
+
+    #[test]
+    fn v1_slugs_are_prefixed_and_can_key_on_name() {
+        let by_name = SlugScheme {
+            version: SlugVersion::V1,
+            endpoint_key: SlugEndpointKey::Name,
+            long_digest: false,
+        };
+        let a = by_name.encode("", "gpu-1", "10.6.0.213:11434/v1", "m");
+        let b = by_name.encode("", "gpu-1", "10.6.0.99:11434/v1", "m");
+        assert_eq!(a, b);
+        assert!(a.starts_with("v1-"));
+        assert_eq!(SlugVersion::of(&a), SlugVersion::V1);
+
+        let by_url = SlugScheme {
+            endpoint_key: SlugEndpointKey::BaseUrl,
+            ..by_name
+        };
+        assert_ne!(
+            by_url.encode("", "gpu-1", "10.6.0.213:11434/v1", "m"),
+            by_url.encode("", "gpu-1", "10.6.0.99:11434/v1", "m")
+        );
+
+        let long = SlugScheme {
+            long_digest: true,
+            ..by_name
+        };
+        let long_slug = long.encode("", "gpu-1", "10.6.0.213:11434/v1", "m");
+        assert!(long_slug.len() > a.len());
+        // 16 bytes in base-62 => at most 22 chars, plus the prefix.
+        assert!(long_slug.len() <= 3 + 22);
+    }
+
+    #[test]
+    fn v0_rejects_v1_only_parameters() {
+        assert!(SlugScheme::default().validate().is_ok());
+        let invalid = SlugScheme {
+            endpoint_key: SlugEndpointKey::Name,
+            ..SlugScheme::default()
+        };
+        assert!(invalid.validate().is_err());
+    }
 }
@@ -0,0 +1,127 @@
+//! Concurrency admission for proxied requests.
+//!
+//! Each endpoint may cap its in-flight requests (`max_concurrent`), and
+//! individual models on it may be capped further (`model_limits`), e.g. one
+//! concurrent request on a 70B model but eight on a 7B model sharing the
+//! same Ollama instance.
+//!
+//! The proxy asks the registry for a [`ConcurrencyPermit`] before forwarding
+//! a request and holds it until the response (including a streamed body) is
+//! finished. Counters are atomics shared between the registry and the
+//! permits, so releasing a permit does not need the registry lock.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use thiserror::Error;
+
+/// Why a request was not admitted.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AdmissionError {
+    #[error("endpoint '{endpoint}' is at its concurrency limit of {limit}")]
+    EndpointAtCapacity { endpoint: String, limit: usize },
+
+    #[error("model '{model}' on endpoint '{endpoint}' is at its concurrency limit of {limit}")]
+    ModelAtCapacity {
+        endpoint: String,
+        model: String,
+        limit: usize,
+    },
+
+    #[error("unknown endpoint '{0}'")]
+    UnknownEndpoint(String),
+}
+
+impl AdmissionError {
+    /// Stable error kind used for metrics labels.
+    pub fn metric_kind(&self) -> &'static str {
+        match self {
+            AdmissionError::EndpointAtCapacity { .. } => "endpoint_concurrency_limited",
+            AdmissionError::ModelAtCapacity { .. } => "model_concurrency_limited",
+            AdmissionError::UnknownEndpoint(_) => "admission_unknown_endpoint",
+        }
+    }
+}
+
+/// A slot for one in-flight request; released on drop.
+#[derive(Debug)]
+pub struct ConcurrencyPermit {
+    endpoint: Arc<AtomicUsize>,
+    model: Arc<AtomicUsize>,
+}
+
+impl ConcurrencyPermit {
+    /// Take a slot on both counters, or none if either is at its limit.
+    pub(crate) fn acquire(
+        endpoint: (&Arc<AtomicUsize>, Option<usize>),
+        model: (&Arc<AtomicUsize>, Option<usize>),
+    ) -> Result<ConcurrencyPermit, Exhausted> {
+        if !try_increment(endpoint.0, endpoint.1) {
+            return Err(Exhausted::Endpoint);
+        }
+        if !try_increment(model.0, model.1) {
+            endpoint.0.fetch_sub(1, Ordering::SeqCst);
+            return Err(Exhausted::Model);
+        }
+
+        Ok(ConcurrencyPermit {
+            endpoint: Arc::clone(endpoint.0),
+            model: Arc::clone(model.0),
+        })
+    }
+}
+
+impl Drop for ConcurrencyPermit {
+    fn drop(&mut self) {
+        self.endpoint.fetch_sub(1, Ordering::SeqCst);
+        self.model.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Which counter refused a permit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Exhausted {
+    Endpoint,
+    Model,
+}
+
+fn try_increment(counter: &AtomicUsize, limit: Option<usize>) -> bool {
+    counter
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| match limit {
+            Some(limit) if n >= limit => None,
+            _ => Some(n + 1),
+        })
+        .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn permits_respect_both_limits_and_release_on_drop() {
+        let endpoint = Arc::new(AtomicUsize::new(0));
+        let big = Arc::new(AtomicUsize::new(0));
+        let small = Arc::new(AtomicUsize::new(0));
+
+        let first = ConcurrencyPermit::acquire((&endpoint, Some(2)), (&big, Some(1))).unwrap();
+        assert_eq!(
+            ConcurrencyPermit::acquire((&endpoint, Some(2)), (&big, Some(1))).unwrap_err(),
+            Exhausted::Model
+        );
+        // A refused model slot does not leak an endpoint slot.
+        assert_eq!(endpoint.load(Ordering::SeqCst), 1);
+
+        let second = ConcurrencyPermit::acquire((&endpoint, Some(2)), (&small, None)).unwrap();
+        assert_eq!(
+            ConcurrencyPermit::acquire((&endpoint, Some(2)), (&small, None)).unwrap_err(),
+            Exhausted::Endpoint
+        );
+
+        drop(first);
+        drop(second);
+        assert_eq!(endpoint.load(Ordering::SeqCst), 0);
+        assert_eq!(big.load(Ordering::SeqCst), 0);
+        assert!(ConcurrencyPermit::acquire((&endpoint, Some(2)), (&big, Some(1))).is_ok());
+    }
+}
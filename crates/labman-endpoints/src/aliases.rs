@@ -0,0 +1,146 @@
+//! Operator-defined model aliases and metadata overrides.
+//!
+//! `[[models]]` entries in the configuration map upstream model IDs (by glob
+//! pattern) to a canonical ID. Discovery applies them after filtering and
+//! enrichment, so include/exclude patterns and runtime queries still see the
+//! upstream's own IDs, while the model index, slugs and node capabilities
+//! use the canonical one. The upstream ID is kept in
+//! `ModelDescriptor::upstream_id` so the proxy can rewrite requests.
+
+use std::collections::HashSet;
+
+use labman_config::ModelConfig;
+use labman_core::ModelDescriptor;
+
+use crate::glob_match;
+
+/// Apply the first matching override to each model.
+///
+/// If several upstream models on the same endpoint end up with the same
+/// canonical ID, only the first is kept and the others are dropped with a
+/// warning; a slug must resolve to exactly one upstream model.
+pub(crate) fn apply(
+    endpoint: &str,
+    overrides: &[ModelConfig],
+    models: Vec<ModelDescriptor>,
+) -> Vec<ModelDescriptor> {
+    if overrides.is_empty() {
+        return models;
+    }
+
+    let mut seen = HashSet::new();
+    let mut result = Vec::with_capacity(models.len());
+
+    for mut model in models {
+        if let Some(config) = overrides.iter().find(|c| matches(c, &model.id)) {
+            if config.id != model.id {
+                model.upstream_id = Some(std::mem::replace(&mut model.id, config.id.clone()));
+            }
+            if let Some(context_length) = config.context_length {
+                model.context_length = Some(context_length);
+            }
+            if let Some(max_output_tokens) = config.max_output_tokens {
+                model.max_output_tokens = Some(max_output_tokens);
+            }
+            if let Some(tags) = &config.tags {
+                model.tags = tags.clone();
+            }
+            if let Some(capabilities) = &config.capabilities {
+                model.capabilities = Some(capabilities.clone());
+            }
+        }
+
+        if !seen.insert(model.id.clone()) {
+            tracing::warn!(
+                "endpoint '{}': model '{}' is also presented as '{}'; ignoring the duplicate",
+                endpoint,
+                model.upstream_model_id(),
+                model.id
+            );
+            continue;
+        }
+        result.push(model);
+    }
+
+    result
+}
+
+fn matches(config: &ModelConfig, upstream_id: &str) -> bool {
+    if config.patterns.is_empty() {
+        return config.id == upstream_id;
+    }
+    config
+        .patterns
+        .iter()
+        .any(|pattern| glob_match(pattern, upstream_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alias(id: &str, patterns: &[&str]) -> ModelConfig {
+        ModelConfig {
+            id: id.to_string(),
+            patterns: patterns.iter().map(|p| p.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn aliases_rename_and_override_metadata() {
+        let mut llama = alias("llama-3.1-8b", &["llama3.1:8b*"]);
+        llama.context_length = Some(32768);
+        llama.tags = Some(vec!["chat".to_string()]);
+        llama.capabilities = Some(vec![labman_core::ModelCapability::Tools]);
+        let mut qwen = alias("qwen", &[]);
+        qwen.max_output_tokens = Some(1024);
+
+        let mut discovered = ModelDescriptor::new("llama3.1:8b-instruct-q4_K_M");
+        discovered.context_length = Some(8192);
+        let models = apply(
+            "ollama",
+            &[llama, qwen],
+            vec![
+                discovered,
+                ModelDescriptor::new("qwen"),
+                ModelDescriptor::new("other"),
+            ],
+        );
+
+        assert_eq!(models[0].id, "llama-3.1-8b");
+        assert_eq!(
+            models[0].upstream_id.as_deref(),
+            Some("llama3.1:8b-instruct-q4_K_M")
+        );
+        assert_eq!(models[0].upstream_model_id(), "llama3.1:8b-instruct-q4_K_M");
+        assert_eq!(models[0].context_length, Some(32768));
+        assert_eq!(models[0].tags, vec!["chat"]);
+        assert_eq!(
+            models[0].capabilities,
+            Some(vec![labman_core::ModelCapability::Tools])
+        );
+
+        // An entry without patterns only overrides metadata.
+        assert_eq!(models[1].id, "qwen");
+        assert_eq!(models[1].upstream_id, None);
+        assert_eq!(models[1].max_output_tokens, Some(1024));
+
+        assert_eq!(models[2].id, "other");
+    }
+
+    #[test]
+    fn duplicate_canonical_ids_keep_the_first_model() {
+        let models = apply(
+            "ollama",
+            &[alias("llama", &["llama*"])],
+            vec![
+                ModelDescriptor::new("llama3.1:8b-q4"),
+                ModelDescriptor::new("llama3.1:8b-q8"),
+            ],
+        );
+
+        assert_eq!(models.len(), 1);
+        assert_eq!(models[0].upstream_model_id(), "llama3.1:8b-q4");
+    }
+}
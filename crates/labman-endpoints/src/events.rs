@@ -0,0 +1,88 @@
+//! Inventory change events.
+//!
+//! The registry diffs its routable inventory whenever the model indices are
+//! rebuilt and whenever endpoint health flips, and publishes the differences
+//! on a `tokio::sync::broadcast` channel. Components that care about
+//! inventory changes (heartbeats, observers) call
+//! [`EndpointRegistry::subscribe`](crate::EndpointRegistry::subscribe)
+//! instead of polling the registry.
+//!
+//! Broadcast receivers that fall behind lose the oldest events and see
+//! `RecvError::Lagged`; they should then resynchronise from a full snapshot
+//! such as `EndpointRegistry::to_node_capabilities`.
+
+use serde::Serialize;
+
+use crate::ScheduleState;
+
+/// Number of events buffered per subscriber before the oldest are dropped.
+pub(crate) const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// A change in what this node can serve.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum InventoryEvent {
+    /// An endpoint passed its health check after being down (or unchecked).
+    EndpointUp { endpoint: String },
+
+    /// A previously healthy endpoint failed its health check.
+    EndpointDown { endpoint: String, reason: String },
+
+    /// An endpoint entered a new phase of its availability schedule.
+    EndpointScheduleChanged {
+        endpoint: String,
+        state: ScheduleState,
+    },
+
+    /// A model became routable via `slug`.
+    ModelAdded {
+        endpoint: String,
+        model_id: String,
+        slug: String,
+    },
+
+    /// A model stopped being routable, because it disappeared from
+    /// discovery, its endpoint went down or the model itself is unhealthy.
+    ModelRemoved {
+        endpoint: String,
+        model_id: String,
+        slug: String,
+    },
+}
+
+impl InventoryEvent {
+    /// Stable event kind used for logging and metrics labels.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            InventoryEvent::EndpointUp { .. } => "endpoint_up",
+            InventoryEvent::EndpointDown { .. } => "endpoint_down",
+            InventoryEvent::EndpointScheduleChanged { .. } => "endpoint_schedule_changed",
+            InventoryEvent::ModelAdded { .. } => "model_added",
+            InventoryEvent::ModelRemoved { .. } => "model_removed",
+        }
+    }
+}
+
+impl std::fmt::Display for InventoryEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InventoryEvent::EndpointUp { endpoint } => write!(f, "endpoint '{}' up", endpoint),
+            InventoryEvent::EndpointDown { endpoint, reason } => {
+                write!(f, "endpoint '{}' down: {}", endpoint, reason)
+            }
+            InventoryEvent::EndpointScheduleChanged { endpoint, state } => {
+                write!(f, "endpoint '{}' schedule {}", endpoint, state)
+            }
+            InventoryEvent::ModelAdded {
+                endpoint, model_id, ..
+            } => write!(f, "model '{}' added on endpoint '{}'", model_id, endpoint),
+            InventoryEvent::ModelRemoved {
+                endpoint, model_id, ..
+            } => write!(
+                f,
+                "model '{}' removed from endpoint '{}'",
+                model_id, endpoint
+            ),
+        }
+    }
+}
@@ -0,0 +1,334 @@
+//! Runtime fingerprinting and model metadata enrichment.
+//!
+//! Every upstream speaks (some dialect of) the OpenAI API, but the runtime
+//! behind it determines which extra information is available. During model
+//! discovery labman identifies the runtime once per endpoint and then queries
+//! its native routes for details `/v1/models` doesn't carry.
+//!
+//! Detection uses, in order:
+//!
+//! 1. The `Server` response header of the `/v1/models` request.
+//! 2. `owned_by` values in the model list (`vllm`, `llamacpp`, `tgi`, ...).
+//! 3. Characteristic routes relative to the server root:
+//!    - Ollama: `GET /api/version`
+//!    - TGI: `GET /info` (carries `router` / `max_total_tokens`)
+//!    - llama.cpp: `GET /props` (carries `default_generation_settings`)
+//!    - vLLM: `GET /version`
+//!
+//! Enrichment is best effort: failures are logged at debug level and leave
+//! the descriptors as discovered.
+
+use labman_core::{ModelCapability, ModelDescriptor, RuntimeKind};
+use serde_json::Value;
+
+use crate::ollama;
+
+/// Server header substrings that identify a runtime.
+const SERVER_HEADER_HINTS: &[(&str, RuntimeKind)] = &[
+    ("mistralrs", RuntimeKind::MistralRs),
+    ("mistral.rs", RuntimeKind::MistralRs),
+    ("llama.cpp", RuntimeKind::LlamaCpp),
+    ("text-generation-inference", RuntimeKind::Tgi),
+    ("vllm", RuntimeKind::Vllm),
+    ("ollama", RuntimeKind::Ollama),
+];
+
+/// `owned_by` values reported by each runtime's `/v1/models`.
+///
+/// Only values specific to one runtime are listed: generic ones such as
+/// `local` (mistral.rs, among others) would mislabel other servers.
+const OWNED_BY_HINTS: &[(&str, RuntimeKind)] = &[
+    ("vllm", RuntimeKind::Vllm),
+    ("llamacpp", RuntimeKind::LlamaCpp),
+    ("tgi", RuntimeKind::Tgi),
+    ("text-generation-inference", RuntimeKind::Tgi),
+    ("mistral.rs", RuntimeKind::MistralRs),
+    ("library", RuntimeKind::Ollama),
+];
+
+/// Identify a runtime from the `/v1/models` response alone, without issuing
+/// further requests.
+pub(crate) fn runtime_from_hints(
+    server_header: Option<&str>,
+    models: &[ModelDescriptor],
+) -> Option<RuntimeKind> {
+    if let Some(server) = server_header {
+        let server = server.to_ascii_lowercase();
+        if let Some((_, kind)) = SERVER_HEADER_HINTS
+            .iter()
+            .find(|(hint, _)| server.contains(hint))
+        {
+            return Some(*kind);
+        }
+    }
+
+    models.iter().find_map(|m| {
+        let owned_by = m.owned_by.as_deref()?.to_ascii_lowercase();
+        OWNED_BY_HINTS
+            .iter()
+            .find(|(hint, _)| owned_by == *hint)
+            .map(|(_, kind)| *kind)
+    })
+}
+
+/// Detect the runtime behind an endpoint.
+///
+/// Cheap hints from the `/v1/models` response are checked first; only if they
+/// are inconclusive are the characteristic routes probed. Returns
+/// `RuntimeKind::Unknown` if nothing matches.
+pub(crate) async fn detect_runtime(
+    client: &reqwest::Client,
+    http_base_url: &str,
+    server_header: Option<&str>,
+    models: &[ModelDescriptor],
+) -> RuntimeKind {
+    if let Some(kind) = runtime_from_hints(server_header, models) {
+        return kind;
+    }
+
+    // The same `/v1` stripping applies to every runtime's native routes.
+    let root = ollama::native_root(http_base_url);
+
+    if get_json(client, &format!("{}/api/version", root))
+        .await
+        .is_some_and(|v| v.get("version").is_some())
+    {
+        return RuntimeKind::Ollama;
+    }
+    if get_json(client, &format!("{}/info", root))
+        .await
+        .is_some_and(|v| v.get("router").is_some() || v.get("max_total_tokens").is_some())
+    {
+        return RuntimeKind::Tgi;
+    }
+    if get_json(client, &format!("{}/props", root))
+        .await
+        .is_some_and(|v| v.get("default_generation_settings").is_some())
+    {
+        return RuntimeKind::LlamaCpp;
+    }
+    if get_json(client, &format!("{}/version", root))
+        .await
+        .is_some_and(|v| v.get("version").is_some())
+    {
+        return RuntimeKind::Vllm;
+    }
+
+    RuntimeKind::Unknown
+}
+
+/// Fill in context length, output limits and modalities from the runtime's
+/// native APIs.
+pub(crate) async fn enrich_models(
+    client: &reqwest::Client,
+    http_base_url: &str,
+    runtime: RuntimeKind,
+    models: &mut [ModelDescriptor],
+) {
+    let root = ollama::native_root(http_base_url);
+
+    match runtime {
+        RuntimeKind::Ollama => ollama::enrich_models(client, &root, models).await,
+        RuntimeKind::LlamaCpp => {
+            if let Some(props) = get_json(client, &format!("{}/props", root)).await {
+                for model in models.iter_mut() {
+                    apply_llama_cpp_props(model, &props);
+                }
+            }
+        }
+        RuntimeKind::Tgi => {
+            if let Some(info) = get_json(client, &format!("{}/info", root)).await {
+                for model in models.iter_mut() {
+                    apply_tgi_info(model, &info);
+                }
+            }
+        }
+        RuntimeKind::Vllm | RuntimeKind::MistralRs | RuntimeKind::Unknown => {}
+    }
+
+    for model in models.iter_mut() {
+        apply_model_list_metadata(model);
+    }
+}
+
+/// Apply fields some runtimes add to their `/v1/models` entries.
+///
+/// vLLM and mistral.rs report `max_model_len`; llama.cpp reports a `meta`
+/// object with the training context size and parameter count.
+fn apply_model_list_metadata(model: &mut ModelDescriptor) {
+    if model.context_length.is_none() {
+        model.context_length = model
+            .metadata
+            .get("max_model_len")
+            .and_then(Value::as_u64)
+            .or_else(|| model.metadata["meta"]["n_ctx_train"].as_u64());
+    }
+
+    if model.parameter_size.is_none() {
+        if let Some(n_params) = model.metadata["meta"]["n_params"].as_u64() {
+            model.parameter_size = Some(format!("{:.1}B", n_params as f64 / 1e9));
+        }
+    }
+}
+
+/// Apply llama.cpp `GET /props` information to a model.
+///
+/// `llama-server` serves a single model, so the props apply to every
+/// descriptor the endpoint reports.
+fn apply_llama_cpp_props(model: &mut ModelDescriptor, props: &Value) {
+    let settings = &props["default_generation_settings"];
+
+    if let Some(n_ctx) = settings["n_ctx"].as_u64().filter(|n| *n > 0) {
+        model.context_length = Some(n_ctx);
+    }
+
+    // `n_predict` is -1 when generation is only bounded by the context.
+    let n_predict = settings["params"]["n_predict"]
+        .as_i64()
+        .or_else(|| settings["n_predict"].as_i64());
+    if let Some(n) = n_predict.filter(|n| *n > 0) {
+        model.max_output_tokens = Some(n as u64);
+    }
+
+    if let Some(modalities) = props["modalities"].as_object() {
+        let mut list = vec!["text".to_string()];
+        if modalities.get("vision").and_then(Value::as_bool) == Some(true) {
+            list.push("image".to_string());
+        }
+        if modalities.get("audio").and_then(Value::as_bool) == Some(true) {
+            list.push("audio".to_string());
+        }
+        model.modalities = list;
+    }
+
+    // Newer servers report what the chat template supports; JSON mode is
+    // always available through grammar-constrained sampling.
+    if let Some(supports_tools) = props["chat_template_caps"]["supports_tools"].as_bool() {
+        let mut capabilities = vec![ModelCapability::JsonMode];
+        if supports_tools {
+            capabilities.push(ModelCapability::Tools);
+        }
+        if model.modalities.iter().any(|m| m == "image") {
+            capabilities.push(ModelCapability::Vision);
+        }
+        model.capabilities = Some(capabilities);
+    }
+}
+
+/// Apply TGI `GET /info` limits to a model.
+fn apply_tgi_info(model: &mut ModelDescriptor, info: &Value) {
+    let total = info["max_total_tokens"].as_u64();
+    let input = info["max_input_tokens"]
+        .as_u64()
+        .or_else(|| info["max_input_length"].as_u64());
+
+    if let Some(total) = total {
+        model.context_length = Some(total);
+        if let Some(input) = input.filter(|i| *i < total) {
+            model.max_output_tokens = Some(total - input);
+        }
+    }
+}
+
+/// GET a URL and parse the body as JSON, returning `None` on any failure.
+async fn get_json(client: &reqwest::Client, url: &str) -> Option<Value> {
+    let resp = match client.get(url).send().await {
+        Ok(resp) if resp.status().is_success() => resp,
+        Ok(resp) => {
+            tracing::debug!("fingerprint probe {} returned HTTP {}", url, resp.status());
+            return None;
+        }
+        Err(e) => {
+            tracing::debug!("fingerprint probe {} failed: {}", url, e);
+            return None;
+        }
+    };
+
+    resp.json().await.ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn models_from(json: &str) -> Vec<ModelDescriptor> {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn hints_prefer_server_header_then_owned_by() {
+        let models = models_from(r#"[{"id":"m","owned_by":"vllm"}]"#);
+        assert_eq!(
+            runtime_from_hints(Some("mistralrs/0.3"), &models),
+            Some(RuntimeKind::MistralRs)
+        );
+        assert_eq!(
+            runtime_from_hints(Some("uvicorn"), &models),
+            Some(RuntimeKind::Vllm)
+        );
+
+        let models = models_from(r#"[{"id":"m","owned_by":"someone"}]"#);
+        assert_eq!(runtime_from_hints(None, &models), None);
+
+        // Many OpenAI-compatible servers report `local`.
+        let models = models_from(r#"[{"id":"m","owned_by":"local"}]"#);
+        assert_eq!(runtime_from_hints(None, &models), None);
+    }
+
+    #[test]
+    fn model_list_metadata_fills_context_and_size() {
+        let mut models = models_from(
+            r#"[{"id":"a","max_model_len":32768},
+                {"id":"b","meta":{"n_ctx_train":8192,"n_params":8030261248}}]"#,
+        );
+        for model in models.iter_mut() {
+            apply_model_list_metadata(model);
+        }
+
+        assert_eq!(models[0].context_length, Some(32768));
+        assert_eq!(models[1].context_length, Some(8192));
+        assert_eq!(models[1].parameter_size.as_deref(), Some("8.0B"));
+    }
+
+    #[test]
+    fn llama_cpp_props_set_limits_and_modalities() {
+        let props: Value = serde_json::from_str(
+            r#"{"default_generation_settings":{"n_ctx":16384,"params":{"n_predict":2048}},
+                "modalities":{"vision":true,"audio":false}}"#,
+        )
+        .unwrap();
+        let mut model = ModelDescriptor::new("gemma");
+        apply_llama_cpp_props(&mut model, &props);
+
+        assert_eq!(model.context_length, Some(16384));
+        assert_eq!(model.max_output_tokens, Some(2048));
+        assert_eq!(model.modalities, vec!["text", "image"]);
+        assert_eq!(model.capabilities, None);
+
+        let props: Value = serde_json::from_str(
+            r#"{"default_generation_settings":{"n_ctx":4096,"n_predict":-1},
+                "chat_template_caps":{"supports_tools":true}}"#,
+        )
+        .unwrap();
+        let mut model = ModelDescriptor::new("llama");
+        apply_llama_cpp_props(&mut model, &props);
+        assert_eq!(model.context_length, Some(4096));
+        assert_eq!(model.max_output_tokens, None);
+        assert!(model.modalities.is_empty());
+        assert_eq!(
+            model.capabilities,
+            Some(vec![ModelCapability::JsonMode, ModelCapability::Tools])
+        );
+    }
+
+    #[test]
+    fn tgi_info_derives_output_budget() {
+        let info: Value =
+            serde_json::from_str(r#"{"max_input_tokens":3000,"max_total_tokens":4096}"#).unwrap();
+        let mut model = ModelDescriptor::new("tgi-model");
+        apply_tgi_info(&mut model, &info);
+
+        assert_eq!(model.context_length, Some(4096));
+        assert_eq!(model.max_output_tokens, Some(1096));
+    }
+}
@@ -1,14 +1,35 @@
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
-
-use labman_config::{EndpointConfig, LabmanConfig};
-use labman_core::endpoint::Endpoint;
-use labman_core::{LabmanError, ModelDescriptor, ModelListResponse, NodeCapabilities, Result};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use labman_config::{
+    normalize_base_url, EndpointConfig, EndpointKind, LabmanConfig, ModelConfig, ModelLimitConfig,
+    OllamaConfig, ProbeConfig, RequestPolicyConfig,
+};
+use labman_core::endpoint::{Endpoint, UNIX_SOCKET_SCHEME};
+use labman_core::{
+    CapacityHints, LabmanError, ModelDescriptor, ModelListResponse, NodeCapabilities, Result,
+    RuntimeKind, SlugCatalog, SlugCatalogEntry, SlugHealth, SlugScheme,
+};
 use labman_telemetry::MetricsRecorder;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
-use tracing;
+use tokio::sync::{broadcast, Notify};
+
+pub use admission::{AdmissionError, ConcurrencyPermit};
+pub use events::InventoryEvent;
+pub use schedule::{Schedule, ScheduleState};
+
+mod admission;
+mod aliases;
+pub mod events;
+mod fingerprint;
+mod load;
+pub mod ollama;
+mod probe;
+mod schedule;
 
 /// Errors specific to endpoint registry operations.
 #[derive(Debug, Error)]
@@ -18,6 +39,9 @@ pub enum EndpointRegistryError {
 
     #[error("invalid endpoint base_url for '{name}': {reason}")]
     InvalidEndpointUrl { name: String, reason: String },
+
+    #[error("invalid schedule for endpoint '{name}': {reason}")]
+    InvalidSchedule { name: String, reason: String },
 }
 
 impl From<EndpointRegistryError> for LabmanError {
@@ -26,6 +50,34 @@ impl From<EndpointRegistryError> for LabmanError {
     }
 }
 
+/// Reasons a model discovery request against a single endpoint can fail.
+#[derive(Debug, Error)]
+pub(crate) enum DiscoveryError {
+    #[error("request error: {0}")]
+    Request(reqwest::Error),
+
+    #[error("HTTP {0}")]
+    Status(reqwest::StatusCode),
+
+    #[error("JSON parse error: {0}")]
+    Parse(reqwest::Error),
+
+    #[error("no response within {0:?}")]
+    Timeout(Duration),
+}
+
+impl DiscoveryError {
+    /// Stable error kind used when recording discovery failures as metrics.
+    fn metric_kind(&self) -> &'static str {
+        match self {
+            DiscoveryError::Request(_) => "model_discovery_error",
+            DiscoveryError::Status(_) => "model_discovery_http_status",
+            DiscoveryError::Parse(_) => "model_discovery_parse",
+            DiscoveryError::Timeout(_) => "model_discovery_timeout",
+        }
+    }
+}
+
 /// Metadata associated with a configured endpoint, beyond what is stored in
 /// `labman_core::Endpoint`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,13 +85,71 @@ pub struct EndpointMeta {
     /// Maximum number of concurrent requests allowed for this endpoint.
     pub max_concurrent: Option<usize>,
 
+    /// Per-model concurrency limits, matched against upstream model IDs.
+    pub model_limits: Vec<ModelLimitConfig>,
+
     /// Glob patterns for model inclusion.
     pub models_include: Option<Vec<String>>,
 
     /// Glob patterns for model exclusion.
     pub models_exclude: Option<Vec<String>>,
+
+    /// API flavour used for discovery and forwarding.
+    pub kind: EndpointKind,
+
+    /// Native Ollama options, when `kind` is `Ollama`.
+    pub ollama: Option<OllamaConfig>,
+
+    /// How often to scrape runtime-reported load; `None` disables scraping.
+    pub load_scrape_interval: Option<Duration>,
+
+    /// Synthetic inference probe settings, if enabled.
+    pub probe: Option<ProbeConfig>,
+
+    /// `[[models]]` aliases and overrides that apply to this endpoint.
+    pub models: Vec<ModelConfig>,
+
+    /// Request policy for all models on this endpoint.
+    pub policy: Option<RequestPolicyConfig>,
+
+    /// Availability windows; always available when `None`.
+    pub schedule: Option<Schedule>,
+}
+
+impl EndpointMeta {
+    /// Whether chat completions should be translated to Ollama's native
+    /// `/api/chat` rather than forwarded to the OpenAI shim.
+    pub fn ollama_native_chat(&self) -> bool {
+        self.kind == EndpointKind::Ollama && self.ollama.as_ref().is_some_and(|o| o.native_chat)
+    }
+
+    /// Concurrency limit for a model, by its upstream ID.
+    pub fn model_concurrency_limit(&self, upstream_model_id: &str) -> Option<usize> {
+        self.model_limits
+            .iter()
+            .find(|l| glob_match(&l.pattern, upstream_model_id))
+            .map(|l| l.max_concurrent)
+    }
+
+    /// Effective request policy for `model_id` (the canonical ID): the
+    /// endpoint policy with the matching `[[models]]` policy layered on top.
+    pub fn request_policy(&self, model_id: &str) -> Option<RequestPolicyConfig> {
+        let model_policy = self
+            .models
+            .iter()
+            .find(|m| m.id == model_id)
+            .and_then(|m| m.policy.as_ref());
+
+        match (&self.policy, model_policy) {
+            (Some(endpoint), Some(model)) => Some(endpoint.merged_with(model)),
+            (endpoint, model) => endpoint.as_ref().or(model).cloned(),
+        }
+    }
 }
 
+/// Default interval for scraping runtime-reported load.
+pub const DEFAULT_LOAD_SCRAPE_INTERVAL: Duration = Duration::from_secs(10);
+
 /// A registry of configured endpoints on this node.
 ///
 /// This is the central in-process view of all OpenAI-compatible upstreams
@@ -69,9 +179,8 @@ pub struct EndpointRegistry {
     /// requests are routed via the control plane) to concrete tenant/endpoint/
     /// model triples.
     ///
-    /// The control plane is free to define the exact slug format (e.g.
-    /// `base62(SHA-256(tenant + "\n" + endpoint_slug + "\n" + model)[0..8])`),
-    /// but labman must be able to reproduce it in order to build this mapping.
+    /// Slugs are derived with `slug_scheme`, which the control plane
+    /// learns from the node capabilities so both sides agree on the format.
     ///
     /// This index allows the proxy layer to:
     /// - Treat the incoming `model` field as an opaque slug.
@@ -79,6 +188,47 @@ pub struct EndpointRegistry {
     /// - Rewrite the upstream request so that the local endpoint sees the
     ///   original model string it understands.
     hash_index: HashMap<String, HashedModelMapping>,
+
+    /// Scheme used to derive the slugs in `hash_index`.
+    slug_scheme: SlugScheme,
+
+    /// Slug collisions detected by the last index rebuild.
+    slug_collisions: Vec<SlugCollision>,
+
+    /// Slugs that recently left `hash_index` because their endpoint (or the
+    /// model itself) became unavailable, with the time they left.
+    ///
+    /// Keeping these for `SLUG_RETENTION` lets the proxy tell a temporarily
+    /// unavailable model (503) apart from a slug it has never heard of (404)
+    /// instead of reporting every slug of a flapping endpoint as unknown.
+    retained_slugs: HashMap<String, (HashedModelMapping, DateTime<Utc>)>,
+
+    /// Publisher for inventory change events (see the [`events`] module).
+    events: broadcast::Sender<InventoryEvent>,
+
+    /// How long health checks and model list requests may take
+    /// (`[health].timeout_secs`).
+    check_timeout: Duration,
+
+    /// Notified by `apply_config` so periodic tasks re-read their intervals.
+    config_changed: Arc<Notify>,
+}
+
+/// How long slugs of an unavailable endpoint or model are remembered.
+pub const SLUG_RETENTION: Duration = Duration::from_secs(600);
+
+/// Result of resolving an opaque model slug.
+#[derive(Debug, Clone, Copy)]
+pub enum SlugResolution<'a> {
+    /// The slug maps to a model that can currently be served.
+    Available(&'a HashedModelMapping),
+
+    /// The slug is known, but its endpoint is down or the model is
+    /// unhealthy. Requests should fail with a retryable error.
+    Unavailable(&'a HashedModelMapping),
+
+    /// The slug is not known to this node.
+    Unknown,
 }
 
 /// Mapping from an opaque model slug (as seen in the OpenAI `model` field
@@ -99,7 +249,30 @@ pub struct HashedModelMapping {
     pub endpoint_name: String,
 
     /// Concrete model identifier on the endpoint (e.g. "mistral-nemo:12b").
+    ///
+    /// This is the canonical ID when the model has an operator alias.
     pub model_id: String,
+
+    /// Model identifier the upstream expects in requests; differs from
+    /// `model_id` only for aliased models.
+    pub upstream_model_id: String,
+}
+
+/// Two different endpoint/model pairs that map to the same slug.
+///
+/// The pair whose endpoint name sorts first keeps the slug; the other model
+/// is left out of the slug index until the collision is resolved, e.g. by
+/// switching to a longer digest or renaming an endpoint.
+#[derive(Debug, Clone)]
+pub struct SlugCollision {
+    /// The contested slug.
+    pub slug: String,
+
+    /// The mapping that was kept in the index.
+    pub kept: HashedModelMapping,
+
+    /// The mapping that was dropped from the index.
+    pub dropped: HashedModelMapping,
 }
 
 /// A single entry in the registry.
@@ -125,8 +298,11 @@ pub struct EndpointEntry {
     /// operator's default tenant.
     pub tenant: Option<String>,
 
-    /// Current number of active requests (for scheduling, not yet used).
-    active_requests: usize,
+    /// Number of requests currently in flight through the proxy.
+    active_requests: Arc<AtomicUsize>,
+
+    /// In-flight requests per model (by canonical ID), for `model_limits`.
+    model_active_requests: HashMap<String, Arc<AtomicUsize>>,
 
     /// Whether this endpoint is currently considered healthy.
     ///
@@ -134,11 +310,188 @@ pub struct EndpointEntry {
     /// and not yet exposed externally.
     healthy: bool,
 
+    /// Where the endpoint is within its schedule, as of the last
+    /// `update_schedules`. Always `Open` without a schedule.
+    schedule_state: ScheduleState,
+
     /// Models discovered from this endpoint via `/v1/models`.
     ///
     /// This will be populated by model discovery logic and used for routing
     /// decisions and capability reporting.
     discovered_models: Vec<ModelDescriptor>,
+
+    /// HTTP client used for all traffic to this endpoint.
+    ///
+    /// For `unix://` endpoints the client is bound to the socket, so requests
+    /// must be built from `Endpoint::http_base_url` rather than `base_url`.
+    client: reqwest::Client,
+
+    /// When load was last scraped (successfully or not).
+    last_load_scrape: Option<Instant>,
+
+    /// Health of individual models on this endpoint, keyed by model ID.
+    ///
+    /// Only models that have served a request or been probed have an entry;
+    /// models without one are considered healthy. Unavailable models are
+    /// left out of the model indices and capability reporting while the
+    /// endpoint itself stays in rotation for its other models.
+    model_health: HashMap<String, ModelHealth>,
+
+    /// When the probe last ran (successfully or not).
+    last_probe: Option<Instant>,
+
+    /// Latency of the most recent successful probe.
+    probe_latency: Option<Duration>,
+}
+
+/// Consecutive failed requests after which a model is taken out of rotation.
+pub const MODEL_FAILURE_THRESHOLD: u32 = 3;
+
+/// How long an unhealthy model is kept out of rotation before it is offered
+/// traffic again.
+///
+/// Excluded models receive no real requests, so without this they could
+/// only recover through a synthetic probe. Once the cooldown has elapsed the
+/// model is re-included on the next index rebuild; a single further failure
+/// takes it out again for another cooldown.
+pub const MODEL_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// Health of a single model on an endpoint.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModelHealth {
+    /// Failures since the last success.
+    pub consecutive_failures: u32,
+
+    /// Most recent failure reason, kept after recovery for diagnostics.
+    pub last_error: Option<String>,
+
+    /// When the most recent failure happened.
+    pub last_error_at: Option<DateTime<Utc>>,
+
+    /// When the model last successfully served a proxied request.
+    pub last_served: Option<DateTime<Utc>>,
+
+    /// When the model was (most recently) marked unhealthy; `None` while
+    /// healthy.
+    pub unhealthy_since: Option<DateTime<Utc>>,
+}
+
+impl ModelHealth {
+    /// Whether the model is currently healthy.
+    pub fn is_healthy(&self) -> bool {
+        self.unhealthy_since.is_none()
+    }
+
+    /// Whether the model should be offered traffic at `now`.
+    pub fn is_available(&self, now: DateTime<Utc>) -> bool {
+        match self.unhealthy_since {
+            None => true,
+            Some(since) => now
+                .signed_duration_since(since)
+                .to_std()
+                .is_ok_and(|elapsed| elapsed >= MODEL_RETRY_AFTER),
+        }
+    }
+
+    /// Record a success. Returns `true` if the model recovered.
+    fn succeed(&mut self) -> bool {
+        self.consecutive_failures = 0;
+        self.unhealthy_since.take().is_some()
+    }
+
+    /// Record a failure, marking the model unhealthy once `threshold`
+    /// consecutive failures are reached. Returns `true` if the model was
+    /// (re-)marked unhealthy.
+    fn fail(&mut self, error: String, threshold: u32) -> bool {
+        let now = Utc::now();
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        self.last_error = Some(error);
+        self.last_error_at = Some(now);
+
+        if self.consecutive_failures >= threshold {
+            self.unhealthy_since = Some(now);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl EndpointEntry {
+    /// HTTP client to use for requests to this endpoint.
+    ///
+    /// Cloning the returned client is cheap; clones share a connection pool.
+    pub fn http_client(&self) -> &reqwest::Client {
+        &self.client
+    }
+
+    /// Where the endpoint is within its availability schedule.
+    pub fn schedule_state(&self) -> ScheduleState {
+        self.schedule_state
+    }
+
+    /// Whether the endpoint should be offered new requests: it is healthy
+    /// and inside an open schedule window.
+    pub fn is_routable(&self) -> bool {
+        self.healthy && self.schedule_state == ScheduleState::Open
+    }
+
+    /// Descriptor of a discovered model, by its (canonical) ID.
+    pub fn model(&self, model_id: &str) -> Option<&ModelDescriptor> {
+        self.discovered_models.iter().find(|m| m.id == model_id)
+    }
+
+    /// Whether `model_id` is currently considered healthy on this endpoint.
+    pub fn is_model_healthy(&self, model_id: &str) -> bool {
+        self.model_health
+            .get(model_id)
+            .is_none_or(|health| health.is_healthy())
+    }
+
+    /// Whether `model_id` should currently be offered traffic, i.e. it is
+    /// healthy or its retry cooldown has elapsed.
+    pub fn is_model_available(&self, model_id: &str) -> bool {
+        self.model_health
+            .get(model_id)
+            .is_none_or(|health| health.is_available(Utc::now()))
+    }
+
+    /// Tracked health of `model_id`, if it has served a request or been
+    /// probed.
+    pub fn model_health(&self, model_id: &str) -> Option<&ModelHealth> {
+        self.model_health.get(model_id)
+    }
+
+    /// Latency of the most recent successful synthetic probe, if any.
+    pub fn last_probe_latency(&self) -> Option<Duration> {
+        self.probe_latency
+    }
+
+    /// Number of requests currently in flight through the proxy.
+    pub fn active_requests(&self) -> usize {
+        self.active_requests.load(Ordering::SeqCst)
+    }
+
+    /// How many requests this endpoint can serve at once, if bounded.
+    ///
+    /// This is `max_concurrent`, or the sum of the per-model limits when
+    /// every discovered model has one, whichever is lower. `None` means the
+    /// endpoint is unbounded.
+    pub fn concurrency_capacity(&self) -> Option<usize> {
+        let per_model: Option<usize> = if self.discovered_models.is_empty() {
+            None
+        } else {
+            self.discovered_models
+                .iter()
+                .map(|m| self.meta.model_concurrency_limit(m.upstream_model_id()))
+                .try_fold(0usize, |acc, limit| Some(acc.saturating_add(limit?)))
+        };
+
+        match (self.meta.max_concurrent, per_model) {
+            (Some(endpoint), Some(models)) => Some(endpoint.min(models)),
+            (endpoint, models) => endpoint.or(models),
+        }
+    }
 }
 
 impl EndpointRegistry {
@@ -157,23 +510,7 @@ impl EndpointRegistry {
                 );
             }
 
-            let endpoint = Self::build_core_endpoint(ep_cfg)?;
-            let meta = EndpointMeta {
-                max_concurrent: ep_cfg.max_concurrent,
-                models_include: ep_cfg.models_include.clone(),
-                models_exclude: ep_cfg.models_exclude.clone(),
-            };
-
-            let entry = EndpointEntry {
-                endpoint,
-                meta,
-                tenant: ep_cfg.tenant.clone(),
-                active_requests: 0,
-                healthy: false,
-                discovered_models: Vec::new(),
-            };
-
-            endpoints.insert(ep_cfg.name.clone(), entry);
+            endpoints.insert(ep_cfg.name.clone(), Self::build_entry(cfg, ep_cfg)?);
         }
 
         Ok(Self {
@@ -181,9 +518,162 @@ impl EndpointRegistry {
             metrics: None,
             model_index: HashMap::new(),
             hash_index: HashMap::new(),
+            slug_scheme: cfg.control_plane.slug_scheme,
+            slug_collisions: Vec::new(),
+            retained_slugs: HashMap::new(),
+            events: broadcast::channel(events::EVENT_CHANNEL_CAPACITY).0,
+            check_timeout: Duration::from_secs(cfg.health.timeout_secs),
+            config_changed: Arc::new(Notify::new()),
+        })
+    }
+
+    /// Build the registry entry for one configured endpoint, with fresh
+    /// (unchecked) health state.
+    fn build_entry(cfg: &LabmanConfig, ep_cfg: &EndpointConfig) -> Result<EndpointEntry> {
+        let endpoint = Self::build_core_endpoint(ep_cfg)?;
+        let client = Self::build_http_client(&endpoint)?;
+        let meta = Self::build_meta(cfg, ep_cfg)?;
+        let schedule_state = meta
+            .schedule
+            .as_ref()
+            .map_or(ScheduleState::Open, |s| s.state_at(Utc::now()));
+
+        Ok(EndpointEntry {
+            endpoint,
+            meta,
+            tenant: ep_cfg.tenant.clone(),
+            active_requests: Arc::new(AtomicUsize::new(0)),
+            model_active_requests: HashMap::new(),
+            healthy: false,
+            schedule_state,
+            discovered_models: Vec::new(),
+            client,
+            last_load_scrape: None,
+            model_health: HashMap::new(),
+            last_probe: None,
+            probe_latency: None,
+        })
+    }
+
+    fn build_meta(cfg: &LabmanConfig, ep_cfg: &EndpointConfig) -> Result<EndpointMeta> {
+        let schedule = ep_cfg
+            .schedule
+            .as_ref()
+            .map(Schedule::from_config)
+            .transpose()
+            .map_err(|reason| EndpointRegistryError::InvalidSchedule {
+                name: ep_cfg.name.clone(),
+                reason,
+            })?;
+
+        Ok(EndpointMeta {
+            max_concurrent: ep_cfg.max_concurrent,
+            model_limits: ep_cfg.model_limits.clone(),
+            models_include: ep_cfg.models_include.clone(),
+            models_exclude: ep_cfg.models_exclude.clone(),
+            kind: ep_cfg.kind,
+            ollama: ep_cfg.ollama.clone(),
+            load_scrape_interval: match ep_cfg.load_scrape_interval_secs {
+                Some(0) => None,
+                Some(secs) => Some(Duration::from_secs(secs)),
+                None => Some(DEFAULT_LOAD_SCRAPE_INTERVAL),
+            },
+            probe: ep_cfg.probe.clone(),
+            models: cfg
+                .models
+                .iter()
+                .filter(|m| m.applies_to(&ep_cfg.name))
+                .cloned()
+                .collect(),
+            policy: ep_cfg.policy.clone(),
+            schedule,
         })
     }
 
+    /// Apply the endpoints and `[[models]]` of a reloaded configuration in
+    /// place.
+    ///
+    /// Endpoints are matched by name:
+    /// - New endpoints are added and stay unhealthy until the next health
+    ///   check.
+    /// - Removed endpoints are dropped. Requests in flight on them finish
+    ///   normally, as they hold their own client and concurrency permit.
+    /// - Endpoints whose `base_url` or `kind` changed are replaced with fresh
+    ///   state.
+    /// - Any other change (filters, limits, policy, probe, schedule, tenant,
+    ///   aliases) updates the entry in place, keeping its health, discovered
+    ///   models and in-flight request counters.
+    ///
+    /// The model indices are rebuilt right away. Filter and alias changes
+    /// only affect the listed models once discovery has run again. If any
+    /// endpoint is invalid the registry is left unchanged.
+    pub fn apply_config(&mut self, cfg: &LabmanConfig) -> Result<()> {
+        enum Update {
+            Replace(Box<EndpointEntry>),
+            InPlace(Box<EndpointMeta>),
+        }
+
+        // Build everything first so that an invalid endpoint leaves the
+        // registry untouched.
+        let mut updates: HashMap<String, Update> = HashMap::new();
+        for ep_cfg in &cfg.endpoints {
+            if updates.contains_key(&ep_cfg.name) {
+                return Err(
+                    EndpointRegistryError::DuplicateEndpointName(ep_cfg.name.clone()).into(),
+                );
+            }
+
+            let update = match self.endpoints.get(&ep_cfg.name) {
+                Some(entry)
+                    if entry.endpoint.base_url == ep_cfg.base_url.trim()
+                        && entry.meta.kind == ep_cfg.kind =>
+                {
+                    Update::InPlace(Box::new(Self::build_meta(cfg, ep_cfg)?))
+                }
+                _ => Update::Replace(Box::new(Self::build_entry(cfg, ep_cfg)?)),
+            };
+            updates.insert(ep_cfg.name.clone(), update);
+        }
+
+        self.endpoints.retain(|name, _| {
+            let keep = updates.contains_key(name);
+            if !keep {
+                tracing::info!("removing endpoint '{}'", name);
+            }
+            keep
+        });
+
+        for ep_cfg in &cfg.endpoints {
+            match updates.remove(&ep_cfg.name) {
+                Some(Update::Replace(entry)) => {
+                    tracing::info!(
+                        "adding endpoint '{}' -> {}",
+                        ep_cfg.name,
+                        entry.endpoint.base_url
+                    );
+                    self.endpoints.insert(ep_cfg.name.clone(), *entry);
+                }
+                Some(Update::InPlace(meta)) => {
+                    let entry = self
+                        .endpoints
+                        .get_mut(&ep_cfg.name)
+                        .expect("endpoint checked above");
+                    entry.schedule_state = meta
+                        .schedule
+                        .as_ref()
+                        .map_or(ScheduleState::Open, |s| s.state_at(Utc::now()));
+                    entry.meta = *meta;
+                    entry.tenant = ep_cfg.tenant.clone();
+                }
+                None => {}
+            }
+        }
+
+        self.rebuild_model_index();
+        self.config_changed.notify_waiters();
+        Ok(())
+    }
+
     /// Return the number of configured endpoints.
     pub fn len(&self) -> usize {
         self.endpoints.len()
@@ -204,6 +694,25 @@ impl EndpointRegistry {
         self.endpoints.get_mut(name)
     }
 
+    /// Subscribe to inventory change events.
+    ///
+    /// Only events emitted after subscribing are received; callers that need
+    /// the current state should take a snapshot (e.g. via
+    /// `to_node_capabilities`) after subscribing.
+    pub fn subscribe(&self) -> broadcast::Receiver<InventoryEvent> {
+        self.events.subscribe()
+    }
+
+    /// Log, count and publish an inventory event.
+    fn emit(&self, event: InventoryEvent) {
+        tracing::info!("inventory: {}", event);
+        if let Some(metrics) = &self.metrics {
+            metrics.record_inventory_event(event.kind());
+        }
+        // Sending only fails when nobody is subscribed, which is fine.
+        let _ = self.events.send(event);
+    }
+
     /// Whether metrics recording is enabled for this registry.
     pub fn has_metrics(&self) -> bool {
         self.metrics.is_some()
@@ -226,11 +735,16 @@ impl EndpointRegistry {
     /// Build `NodeCapabilities` from the currently discovered models and
     /// endpoint configuration.
     ///
-    /// This flattens all unique model IDs across endpoints and uses simple
-    /// heuristics for capacity:
+    /// This flattens all unique model IDs across endpoints, leaving out models
+    /// that are currently unavailable (see `ModelHealth`) and endpoints outside
+    /// their schedule (see `ScheduleState`), and uses simple heuristics for
+    /// capacity:
     /// - `endpoint_count`: total configured endpoints.
-    /// - `max_concurrent_requests`: sum of per-endpoint `max_concurrent`
-    ///   values, ignoring `None` entries.
+    /// - `max_concurrent_requests`: sum of per-endpoint capacities, ignoring
+    ///   unbounded endpoints (see `EndpointEntry::concurrency_capacity`).
+    ///
+    /// Runtime-reported load of healthy endpoints is included in
+    /// `endpoint_load`.
     pub fn to_node_capabilities(&self) -> NodeCapabilities {
         use std::collections::HashSet;
 
@@ -238,7 +752,13 @@ impl EndpointRegistry {
         let mut models: Vec<ModelDescriptor> = Vec::new();
 
         for entry in self.endpoints.values() {
+            if entry.schedule_state != ScheduleState::Open {
+                continue;
+            }
             for model in &entry.discovered_models {
+                if !entry.is_model_available(&model.id) {
+                    continue;
+                }
                 if unique_models.insert(model.id.clone()) {
                     models.push(model.clone());
                 }
@@ -250,42 +770,53 @@ impl EndpointRegistry {
         let max_concurrent_requests = self
             .endpoints
             .values()
-            .filter_map(|e| e.meta.max_concurrent)
+            .filter(|e| e.schedule_state == ScheduleState::Open)
+            .filter_map(|e| e.concurrency_capacity())
             .reduce(|acc, v| acc.saturating_add(v));
 
-        let mut caps = NodeCapabilities::new(models, endpoint_count);
+        let mut caps =
+            NodeCapabilities::new(models, endpoint_count).with_slug_scheme(self.slug_scheme);
         if let Some(max) = max_concurrent_requests {
             caps = caps.with_max_concurrent(max);
         }
+        for (name, entry) in &self.endpoints {
+            if let (true, Some(load)) = (entry.is_routable(), &entry.endpoint.load) {
+                caps = caps.with_endpoint_load(name.clone(), load.clone());
+            }
+        }
         caps
     }
 
-    /// Convert an `EndpointConfig` into a `labman_core::Endpoint`, performing
-
-    /// Convert an `EndpointConfig` into a `labman_core::Endpoint`, performing
-    /// minimal validation/normalisation on the base URL.
+    /// Convert an `EndpointConfig` into a `labman_core::Endpoint`, with the
+    /// base URL in its canonical form (see `labman_config::normalize_base_url`)
+    /// so that equivalent URLs yield the same model slugs.
     fn build_core_endpoint(cfg: &EndpointConfig) -> Result<Endpoint> {
-        let base_url = cfg.base_url.trim();
-
-        if base_url.is_empty() {
-            return Err(EndpointRegistryError::InvalidEndpointUrl {
+        let base_url = normalize_base_url(&cfg.base_url).map_err(|reason| {
+            EndpointRegistryError::InvalidEndpointUrl {
                 name: cfg.name.clone(),
-                reason: "base_url must not be empty".to_string(),
+                reason: format!("base_url {}", reason),
             }
-            .into());
-        }
+        })?;
+        Ok(Endpoint::new(&cfg.name, &base_url))
+    }
 
-        if !(base_url.starts_with("http://") || base_url.starts_with("https://")) {
-            return Err(EndpointRegistryError::InvalidEndpointUrl {
-                name: cfg.name.clone(),
-                reason: "base_url must start with http:// or https://".to_string(),
-            }
-            .into());
-        }
+    /// Build the HTTP client for an endpoint, binding it to the endpoint's
+    /// Unix socket when `base_url` uses the `unix://` scheme.
+    fn build_http_client(endpoint: &Endpoint) -> Result<reqwest::Client> {
+        let builder = reqwest::Client::builder();
+
+        let builder = match endpoint.unix_socket_path() {
+            Some(socket_path) => builder.unix_socket(socket_path),
+            None => builder,
+        };
 
-        // For now we assume the caller has validated the `/v1` suffix. Later we
-        // can normalise base URLs here if necessary.
-        Ok(Endpoint::new(&cfg.name, &base_url.to_string()))
+        builder.build().map_err(|e| {
+            EndpointRegistryError::InvalidEndpointUrl {
+                name: endpoint.name.clone(),
+                reason: format!("failed to build HTTP client: {}", e),
+            }
+            .into()
+        })
     }
 
     /// Perform a basic health check for all configured endpoints.
@@ -294,7 +825,14 @@ impl EndpointRegistry {
     /// marks all endpoints as healthy. It is retained for callers that don't
     /// require HTTP probing.
     pub fn health_check_all(&mut self) -> Result<()> {
+        let mut events = Vec::new();
+
         for (name, entry) in self.endpoints.iter_mut() {
+            if !entry.healthy {
+                events.push(InventoryEvent::EndpointUp {
+                    endpoint: name.clone(),
+                });
+            }
             entry.healthy = true;
 
             if let Some(metrics) = &self.metrics {
@@ -302,41 +840,87 @@ impl EndpointRegistry {
             }
         }
 
+        for event in events {
+            self.emit(event);
+        }
+
         Ok(())
     }
 
     /// Perform an HTTP-based health check for all configured endpoints.
     ///
     /// This initial implementation:
-    /// - Issues a GET request to `{base_url}` (typically `/v1`).
+    /// - Issues a GET request to `{base_url}` (typically `/v1`), over the
+    ///   endpoint's Unix socket for `unix://` endpoints, giving up after
+    ///   `health.timeout_secs`.
     /// - Considers 2xx responses as healthy.
-    /// - Marks other responses or network errors as unhealthy.
+    /// - Marks other responses or network errors as unhealthy, and forgets
+    ///   the endpoint's detected runtime so it is fingerprinted again once
+    ///   it recovers (it may come back as a different server).
     /// - Emits basic success/failure metrics when a `MetricsRecorder` is present.
+    /// - Emits `EndpointUp` / `EndpointDown` inventory events when an
+    ///   endpoint's health flips.
     ///
-    /// It is async so it can be used from Tokio-based code paths in `labmand`.
-    pub async fn health_check_all_http(&mut self) -> Result<()> {
-        let client = reqwest::Client::new();
+    /// The registry lock is released while the requests are in flight, as in
+    /// `discover_models_all_http`.
+    pub async fn health_check_all_http(
+        registry: &Arc<tokio::sync::Mutex<EndpointRegistry>>,
+    ) -> Result<()> {
+        let (targets, check_timeout) = {
+            let guard = registry.lock().await;
+            let targets: Vec<_> = guard
+                .endpoints
+                .iter()
+                .map(|(name, entry)| {
+                    (
+                        name.clone(),
+                        entry.client.clone(),
+                        entry.endpoint.base_url.clone(),
+                        entry.endpoint.http_base_url(),
+                    )
+                })
+                .collect();
+            (targets, guard.check_timeout)
+        };
+
+        let mut results = Vec::with_capacity(targets.len());
+        for (name, client, base_url, url) in targets {
+            let resp = client.get(&url).timeout(check_timeout).send().await;
+            results.push((name, base_url, resp.map(|r| r.status())));
+        }
 
-        for (name, entry) in self.endpoints.iter_mut() {
-            let url = &entry.endpoint.base_url;
-            let resp = client.get(url).send().await;
+        let mut guard = registry.lock().await;
+        let registry = &mut *guard;
+        let mut events = Vec::new();
+
+        for (name, base_url, resp) in results {
+            // Skip endpoints that were removed or replaced in the meantime.
+            let Some(entry) = registry
+                .endpoints
+                .get_mut(&name)
+                .filter(|e| e.endpoint.base_url == base_url)
+            else {
+                continue;
+            };
+            let was_healthy = entry.healthy;
+            let mut down_reason = String::new();
 
             match resp {
                 // Treat 2xx responses as healthy.
-                Ok(r) if r.status().is_success() => {
+                Ok(status) if status.is_success() => {
                     entry.healthy = true;
 
-                    if let Some(metrics) = &self.metrics {
+                    if let Some(metrics) = &registry.metrics {
                         metrics.record_request_end(Some(name.as_str()), None, true, None);
                     }
                 }
                 // Also treat 404 at the base_url as "reachable" for now so that
                 // model discovery can still run. Many OpenAI-compatible servers
                 // return 404 for bare `/v1` even though `/v1/models` works.
-                Ok(r) if r.status().as_u16() == 404 => {
+                Ok(status) if status.as_u16() == 404 => {
                     entry.healthy = true;
 
-                    if let Some(metrics) = &self.metrics {
+                    if let Some(metrics) = &registry.metrics {
                         metrics.record_request_end(Some(name.as_str()), None, true, None);
                     }
 
@@ -346,32 +930,52 @@ impl EndpointRegistry {
                         entry.endpoint.name
                     );
                 }
-                Ok(r) => {
+                Ok(status) => {
                     entry.healthy = false;
-                    let status = r.status();
+                    entry.endpoint.runtime = None;
+                    entry.endpoint.load = None;
                     tracing::warn!(
                         "endpoint '{}' unhealthy: HTTP {}",
                         entry.endpoint.name,
                         status
                     );
+                    down_reason = format!("HTTP {}", status);
 
-                    if let Some(metrics) = &self.metrics {
+                    if let Some(metrics) = &registry.metrics {
                         metrics.record_error(Some(name.as_str()), "health_http_status");
                     }
                 }
                 Err(e) => {
                     entry.healthy = false;
+                    entry.endpoint.runtime = None;
+                    entry.endpoint.load = None;
                     tracing::warn!(
                         "endpoint '{}' unhealthy: request error: {}",
                         entry.endpoint.name,
                         e
                     );
+                    down_reason = format!("request error: {}", e);
 
-                    if let Some(metrics) = &self.metrics {
+                    if let Some(metrics) = &registry.metrics {
                         metrics.record_error(Some(name.as_str()), "health_http_error");
                     }
                 }
             }
+
+            match (was_healthy, entry.healthy) {
+                (false, true) => events.push(InventoryEvent::EndpointUp {
+                    endpoint: name.clone(),
+                }),
+                (true, false) => events.push(InventoryEvent::EndpointDown {
+                    endpoint: name.clone(),
+                    reason: down_reason,
+                }),
+                _ => {}
+            }
+        }
+
+        for event in events {
+            registry.emit(event);
         }
 
         Ok(())
@@ -381,143 +985,449 @@ impl EndpointRegistry {
     ///
     /// For each endpoint:
     /// - Skips if `healthy == false`.
-    /// - Issues a GET to `{base_url}/models` or `{base_url}/v1/models` depending
-    ///   on whether `base_url` already ends with `/v1`.
-    /// - Parses the response into `ModelListResponse`.
+    /// - Issues a GET to `{base_url}/models` and parses the response into
+    ///   `ModelListResponse`. Endpoints with `kind = "ollama"` are instead
+    ///   discovered via the native API (see the [`ollama`] module).
+    /// - Detects the runtime behind the endpoint (see the `fingerprint`
+    ///   module) if it isn't known yet, and records it on
+    ///   `Endpoint::runtime`.
     /// - Applies `models_include` / `models_exclude` filters.
+    /// - Enriches the remaining models with context length, output limits and
+    ///   modalities from the runtime's native APIs.
+    /// - Applies `[[models]]` aliases and metadata overrides (see the
+    ///   `aliases` module).
     /// - Populates `discovered_models` with the filtered list.
-    /// - Updates both the plain `model_index` and the slug-based `hash_index`.
-    pub async fn discover_models_all_http(&mut self) -> Result<()> {
-        let client = reqwest::Client::new();
-
-        // Clear and rebuild both indices from scratch on each discovery pass.
-        self.model_index.clear();
-        self.hash_index.clear();
+    ///
+    /// As with load scraping, the registry lock is released while the HTTP
+    /// requests are in flight, so slow upstreams don't block the proxy.
+    /// Each endpoint's discovery, fingerprinting and enrichment together
+    /// must finish within `health.timeout_secs`, otherwise the endpoint keeps
+    /// its previous models. Results for an endpoint whose base URL or kind was changed by
+    /// `apply_config` in the meantime are discarded.
+    ///
+    /// Once all endpoints have been visited, both the plain `model_index` and
+    /// the slug-based `hash_index` are rebuilt via `rebuild_model_index`.
+    pub async fn discover_models_all_http(
+        registry: &Arc<tokio::sync::Mutex<EndpointRegistry>>,
+    ) -> Result<()> {
+        let (targets, check_timeout) = {
+            let guard = registry.lock().await;
+            let targets: Vec<_> = guard
+                .endpoints
+                .iter()
+                .filter_map(|(name, entry)| {
+                    if !entry.healthy {
+                        tracing::warn!(
+                            "skipping model discovery for unhealthy endpoint '{}'",
+                            entry.endpoint.name
+                        );
+                        return None;
+                    }
+                    Some(DiscoveryTarget {
+                        name: name.clone(),
+                        client: entry.client.clone(),
+                        base_url: entry.endpoint.base_url.clone(),
+                        http_base_url: entry.endpoint.http_base_url(),
+                        kind: entry.meta.kind,
+                        runtime: entry.endpoint.runtime,
+                        models_include: entry.meta.models_include.clone(),
+                        models_exclude: entry.meta.models_exclude.clone(),
+                    })
+                })
+                .collect();
+            (targets, guard.check_timeout)
+        };
+
+        let mut results = Vec::with_capacity(targets.len());
+        for target in targets {
+            // The timeout covers the whole step, including fingerprinting
+            // and enrichment, so one slow endpoint cannot stall the pass.
+            let result = tokio::time::timeout(check_timeout, discover_endpoint(&target))
+                .await
+                .unwrap_or(Err(DiscoveryError::Timeout(check_timeout)));
+            results.push((target, result));
+        }
 
-        for (name, entry) in self.endpoints.iter_mut() {
-            if !entry.healthy {
-                tracing::warn!(
-                    "skipping model discovery for unhealthy endpoint '{}'",
-                    entry.endpoint.name
+        let mut guard = registry.lock().await;
+        let metrics = guard.metrics.clone();
+        for (target, result) in results {
+            let name = &target.name;
+            let Some(entry) = guard
+                .endpoints
+                .get_mut(name)
+                .filter(|e| e.endpoint.base_url == target.base_url && e.meta.kind == target.kind)
+            else {
+                tracing::debug!(
+                    "endpoint '{}' changed during model discovery; discarding results",
+                    name
                 );
                 continue;
-            }
-
-            let base_url = entry.endpoint.base_url.trim_end_matches('/');
-            let models_url = if base_url.ends_with("/v1") {
-                format!("{}/models", base_url)
-            } else {
-                format!("{}/v1/models", base_url)
             };
 
-            let resp = client.get(&models_url).send().await;
-
-            let list: ModelListResponse = match resp {
-                Ok(r) if r.status().is_success() => match r.json().await {
-                    Ok(json) => json,
-                    Err(e) => {
-                        tracing::warn!(
-                            "endpoint '{}' model discovery JSON parse error: {}",
-                            entry.endpoint.name,
-                            e
-                        );
-                        if let Some(metrics) = &self.metrics {
-                            metrics.record_error(Some(name.as_str()), "model_discovery_parse");
-                        }
-                        continue;
-                    }
-                },
-                Ok(r) => {
-                    tracing::warn!(
-                        "endpoint '{}' model discovery HTTP {}",
-                        entry.endpoint.name,
-                        r.status()
-                    );
-                    if let Some(metrics) = &self.metrics {
-                        metrics.record_error(Some(name.as_str()), "model_discovery_http_status");
-                    }
-                    continue;
-                }
+            let (models, runtime) = match result {
+                Ok(discovered) => discovered,
                 Err(e) => {
-                    tracing::warn!(
-                        "endpoint '{}' model discovery request error: {}",
-                        entry.endpoint.name,
-                        e
-                    );
-                    if let Some(metrics) = &self.metrics {
-                        metrics.record_error(Some(name.as_str()), "model_discovery_error");
+                    tracing::warn!("endpoint '{}' model discovery failed: {}", name, e);
+                    if let Some(metrics) = &metrics {
+                        metrics.record_error(Some(name.as_str()), e.metric_kind());
                     }
                     continue;
                 }
             };
 
-            let mut models = list.data;
+            if target.runtime.is_none() {
+                tracing::info!("endpoint '{}' runtime detected: {}", name, runtime);
+            }
+            entry.endpoint.runtime = Some(runtime);
 
-            // Apply include filter
-            if let Some(include) = &entry.meta.models_include {
-                models.retain(|m| {
-                    include
-                        .iter()
-                        .any(|pat| glob_match(pat.as_str(), m.id.as_str()))
-                });
+            // Update the entry's discovered models.
+            entry.discovered_models = aliases::apply(name, &entry.meta.models, models);
+
+            if let Some(metrics) = &metrics {
+                metrics.record_request_end(Some(name.as_str()), None, true, None);
             }
+        }
 
-            // Apply exclude filter
-            if let Some(exclude) = &entry.meta.models_exclude {
-                models.retain(|m| {
-                    !exclude
-                        .iter()
-                        .any(|pat| glob_match(pat.as_str(), m.id.as_str()))
+        guard.rebuild_model_index();
+
+        Ok(())
+    }
+
+    /// Re-evaluate endpoint schedules at `now`.
+    ///
+    /// Endpoints whose `ScheduleState` changed are announced with an
+    /// `EndpointScheduleChanged` inventory event, and the model indices are
+    /// rebuilt so that draining and closed endpoints stop receiving new
+    /// requests (and reopened ones are routed again). Requests already in
+    /// flight are not affected.
+    pub fn update_schedules(&mut self, now: DateTime<Utc>) {
+        let mut events = Vec::new();
+
+        for (name, entry) in self.endpoints.iter_mut() {
+            let Some(schedule) = &entry.meta.schedule else {
+                continue;
+            };
+            let state = schedule.state_at(now);
+            if state != entry.schedule_state {
+                entry.schedule_state = state;
+                events.push(InventoryEvent::EndpointScheduleChanged {
+                    endpoint: name.clone(),
+                    state,
                 });
             }
+        }
 
-            // Update the entry's discovered models.
-            entry.discovered_models = models;
+        if events.is_empty() {
+            return;
+        }
+        for event in events {
+            self.emit(event);
+        }
+        self.rebuild_model_index();
+    }
+
+    /// Rebuild `model_index` and `hash_index` from the discovered models of
+    /// all currently routable endpoints (healthy and inside their schedule
+    /// window), skipping models that are currently
+    /// unavailable (see `ModelHealth::is_available`).
+    ///
+    /// Both indices are cleared and rebuilt from scratch. This is called at
+    /// the end of each discovery pass, and can also be called directly after
+    /// adjusting `discovered_models` or health state by other means. Slugs
+    /// that appeared or disappeared compared to the previous index are
+    /// published as `ModelAdded` / `ModelRemoved` inventory events.
+    pub fn rebuild_model_index(&mut self) {
+        self.model_index.clear();
+        self.slug_collisions.clear();
+        let previous = std::mem::take(&mut self.hash_index);
+
+        // Visit endpoints in name order so that collisions are resolved the
+        // same way on every rebuild.
+        let mut names: Vec<&String> = self.endpoints.keys().collect();
+        names.sort();
+
+        for name in names {
+            let entry = &self.endpoints[name];
+            if !entry.is_routable() {
+                continue;
+            }
 
-            // Update model_index and hash_index for this endpoint.
             let endpoint_slug = entry
                 .endpoint
                 .base_url
                 .trim()
                 .trim_start_matches("http://")
                 .trim_start_matches("https://")
+                .trim_start_matches(UNIX_SOCKET_SCHEME)
                 .to_string();
 
             let tenant_str = entry.tenant.as_deref().unwrap_or("");
 
             for model in &entry.discovered_models {
+                if !entry.is_model_available(&model.id) {
+                    continue;
+                }
+
                 // Plain model index: model_id -> [endpoint_names...]
                 self.model_index
                     .entry(model.id.clone())
-                    .or_insert_with(Vec::new)
+                    .or_default()
                     .push(name.clone());
 
                 // Slug-based index: opaque slug -> (tenant, endpoint_name, model_id)
-                let slug =
-                    labman_core::slug::encode_model_slug(tenant_str, &endpoint_slug, &model.id);
-
-                self.hash_index.insert(
-                    slug,
-                    HashedModelMapping {
-                        tenant: entry.tenant.clone(),
-                        endpoint_name: name.clone(),
-                        model_id: model.id.clone(),
-                    },
-                );
+                let slug = self
+                    .slug_scheme
+                    .encode(tenant_str, name, &endpoint_slug, &model.id);
+                let mapping = HashedModelMapping {
+                    tenant: entry.tenant.clone(),
+                    endpoint_name: name.clone(),
+                    model_id: model.id.clone(),
+                    upstream_model_id: model.upstream_model_id().to_string(),
+                };
+
+                match self.hash_index.get(&slug) {
+                    None => {
+                        self.hash_index.insert(slug, mapping);
+                    }
+                    // The same model listed twice by one endpoint.
+                    Some(kept)
+                        if kept.endpoint_name == mapping.endpoint_name
+                            && kept.model_id == mapping.model_id => {}
+                    Some(kept) => {
+                        tracing::error!(
+                            "model slug collision: '{}' maps to both model '{}' on endpoint '{}' \
+                             and model '{}' on endpoint '{}'; the latter will not be routable \
+                             by slug (use distinct endpoint base URLs or a slug scheme with a \
+                             longer digest or name keying)",
+                            slug,
+                            kept.model_id,
+                            kept.endpoint_name,
+                            mapping.model_id,
+                            mapping.endpoint_name
+                        );
+                        if let Some(metrics) = &self.metrics {
+                            metrics.record_error(Some(name.as_str()), "slug_collision");
+                        }
+                        self.slug_collisions.push(SlugCollision {
+                            kept: kept.clone(),
+                            dropped: mapping,
+                            slug,
+                        });
+                    }
+                }
             }
+        }
 
-            if let Some(metrics) = &self.metrics {
-                metrics.record_request_end(Some(name.as_str()), None, true, None);
+        self.emit_inventory_diff(&previous);
+        self.retain_unavailable_slugs(previous);
+    }
+
+    /// Remember slugs that just left `hash_index` because their endpoint or
+    /// model became unavailable, and forget retained slugs that are routable
+    /// again, expired, or whose model is genuinely gone.
+    fn retain_unavailable_slugs(&mut self, previous: HashMap<String, HashedModelMapping>) {
+        let now = Utc::now();
+
+        for (slug, mapping) in previous {
+            if !self.hash_index.contains_key(&slug) {
+                self.retained_slugs.entry(slug).or_insert((mapping, now));
             }
         }
 
-        Ok(())
+        let endpoints = &self.endpoints;
+        let hash_index = &self.hash_index;
+        self.retained_slugs.retain(|slug, (mapping, since)| {
+            let fresh = now
+                .signed_duration_since(*since)
+                .to_std()
+                .is_ok_and(|age| age < SLUG_RETENTION);
+
+            // A model that vanished from a healthy endpoint's discovery
+            // result is gone for good; only outages are bridged.
+            let temporarily_unavailable = endpoints.get(&mapping.endpoint_name).is_some_and(|e| {
+                e.discovered_models.iter().any(|m| m.id == mapping.model_id)
+                    && (!e.is_routable() || !e.is_model_available(&mapping.model_id))
+            });
+
+            fresh && temporarily_unavailable && !hash_index.contains_key(slug)
+        });
     }
 
-    /// Look up a previously recorded hashed/slugged model identifier and
-    /// return the associated tenant/endpoint/model triple, if known.
+    /// Resolve an opaque model slug, distinguishing models that are known
+    /// but temporarily unavailable from unknown slugs.
     ///
-    /// The `model_slug` parameter is the exact string that appears in the
+    /// A slug in `hash_index` whose endpoint has failed a health check or
+    /// left its schedule window since the last rebuild is reported as
+    /// unavailable as well.
+    pub fn resolve_slug(&self, model_slug: &str) -> SlugResolution<'_> {
+        if let Some(mapping) = self.hash_index.get(model_slug) {
+            let healthy = self
+                .endpoints
+                .get(&mapping.endpoint_name)
+                .is_some_and(|e| e.is_routable());
+            return if healthy {
+                SlugResolution::Available(mapping)
+            } else {
+                SlugResolution::Unavailable(mapping)
+            };
+        }
+
+        match self.retained_slugs.get(model_slug) {
+            Some((mapping, since))
+                if Utc::now()
+                    .signed_duration_since(*since)
+                    .to_std()
+                    .is_ok_and(|age| age < SLUG_RETENTION) =>
+            {
+                SlugResolution::Unavailable(mapping)
+            }
+            _ => SlugResolution::Unknown,
+        }
+    }
+
+    /// Build the slug catalog published to the control plane.
+    ///
+    /// The catalog lists every routable slug plus slugs that are retained as
+    /// temporarily unavailable (see `resolve_slug`), each with capacity hints
+    /// from the endpoint configuration, runtime load and model metadata.
+    pub fn slug_catalog(&self) -> SlugCatalog {
+        let mut catalog = SlugCatalog::new(self.slug_scheme);
+
+        let routable = self.hash_index.keys();
+        let retained = self.retained_slugs.keys();
+        for slug in routable.chain(retained) {
+            let (mapping, health) = match self.resolve_slug(slug) {
+                SlugResolution::Available(mapping) => (mapping, SlugHealth::Available),
+                SlugResolution::Unavailable(mapping) => (mapping, SlugHealth::Unavailable),
+                // Expired retention that has not been pruned yet.
+                SlugResolution::Unknown => continue,
+            };
+
+            let entry = self.endpoints.get(&mapping.endpoint_name);
+            let model = entry.and_then(|e| {
+                e.discovered_models
+                    .iter()
+                    .find(|m| m.id == mapping.model_id)
+            });
+
+            catalog.entries.push(SlugCatalogEntry {
+                slug: slug.clone(),
+                tenant: mapping.tenant.clone(),
+                endpoint: mapping.endpoint_name.clone(),
+                model_id: mapping.model_id.clone(),
+                health,
+                capacity: CapacityHints {
+                    max_concurrent: entry.and_then(|e| e.meta.max_concurrent),
+                    active_requests: entry.map_or(0, |e| e.active_requests()),
+                    model_max_concurrent: entry
+                        .zip(model)
+                        .and_then(|(e, m)| e.meta.model_concurrency_limit(m.upstream_model_id())),
+                    load: entry.and_then(|e| e.endpoint.load.clone()),
+                    context_length: model.and_then(|m| m.context_length),
+                    max_output_tokens: model.and_then(|m| m.max_output_tokens),
+                },
+            });
+        }
+
+        catalog
+            .entries
+            .sort_by(|a, b| (&a.endpoint, &a.model_id).cmp(&(&b.endpoint, &b.model_id)));
+        catalog
+    }
+
+    /// Emit `ModelRemoved` / `ModelAdded` events for the difference between
+    /// a previous `hash_index` and the current one.
+    fn emit_inventory_diff(&self, previous: &HashMap<String, HashedModelMapping>) {
+        // HashMap order is arbitrary; sort so logs and subscribers see a
+        // deterministic sequence (removals first, then additions).
+        let sorted_difference =
+            |from: &HashMap<String, HashedModelMapping>,
+             to: &HashMap<String, HashedModelMapping>| {
+                let mut diff: Vec<(String, HashedModelMapping)> = from
+                    .iter()
+                    .filter(|(slug, _)| !to.contains_key(*slug))
+                    .map(|(slug, mapping)| (slug.clone(), mapping.clone()))
+                    .collect();
+                diff.sort_by(|(_, a), (_, b)| {
+                    (&a.endpoint_name, &a.model_id).cmp(&(&b.endpoint_name, &b.model_id))
+                });
+                diff
+            };
+
+        for (slug, mapping) in sorted_difference(previous, &self.hash_index) {
+            self.emit(InventoryEvent::ModelRemoved {
+                endpoint: mapping.endpoint_name,
+                model_id: mapping.model_id,
+                slug,
+            });
+        }
+        for (slug, mapping) in sorted_difference(&self.hash_index, previous) {
+            self.emit(InventoryEvent::ModelAdded {
+                endpoint: mapping.endpoint_name,
+                model_id: mapping.model_id,
+                slug,
+            });
+        }
+    }
+
+    /// Record that `model_id` on `endpoint_name` successfully served a
+    /// request.
+    ///
+    /// Clears any failure streak and, if the model was unhealthy, brings it
+    /// back into rotation immediately.
+    pub fn record_model_success(&mut self, endpoint_name: &str, model_id: &str) {
+        let Some(entry) = self.endpoints.get_mut(endpoint_name) else {
+            return;
+        };
+        let health = entry.model_health.entry(model_id.to_string()).or_default();
+        health.last_served = Some(Utc::now());
+
+        if health.succeed() {
+            tracing::info!(
+                "endpoint '{}' model '{}' recovered; back in rotation",
+                endpoint_name,
+                model_id
+            );
+            self.rebuild_model_index();
+        }
+    }
+
+    /// Record that a request to `model_id` on `endpoint_name` failed in a way
+    /// that points at the model rather than the caller (upstream 5xx, model
+    /// not found, transport errors).
+    ///
+    /// After `MODEL_FAILURE_THRESHOLD` consecutive failures the model is
+    /// removed from the model indices until it recovers.
+    pub fn record_model_failure(
+        &mut self,
+        endpoint_name: &str,
+        model_id: &str,
+        error: impl Into<String>,
+    ) {
+        let Some(entry) = self.endpoints.get_mut(endpoint_name) else {
+            return;
+        };
+        let health = entry.model_health.entry(model_id.to_string()).or_default();
+
+        if health.fail(error.into(), MODEL_FAILURE_THRESHOLD) {
+            tracing::warn!(
+                "endpoint '{}' model '{}' marked unhealthy after {} consecutive failures: {}",
+                endpoint_name,
+                model_id,
+                health.consecutive_failures,
+                health.last_error.as_deref().unwrap_or("")
+            );
+            if let Some(metrics) = &self.metrics {
+                metrics.record_error(Some(endpoint_name), "model_unhealthy");
+            }
+            self.rebuild_model_index();
+        }
+    }
+
+    /// Look up a previously recorded hashed/slugged model identifier and
+    /// return the associated tenant/endpoint/model triple, if known.
+    ///
+    /// The `model_slug` parameter is the exact string that appears in the
     /// OpenAI `model` field when requests are routed via the control plane.
     /// Labman treats it as opaque and only relies on the mapping built during
     /// model discovery.
@@ -525,26 +1435,359 @@ impl EndpointRegistry {
         self.hash_index.get(model_slug)
     }
 
+    /// The scheme used to derive model slugs on this node.
+    pub fn slug_scheme(&self) -> SlugScheme {
+        self.slug_scheme
+    }
+
+    /// Slug collisions detected by the last index rebuild.
+    ///
+    /// This should always be empty; a non-empty list means some models are
+    /// not reachable by slug (see `SlugCollision`).
+    pub fn slug_collisions(&self) -> &[SlugCollision] {
+        &self.slug_collisions
+    }
+
+    /// Admit a request for `model_id` (the canonical ID) on an endpoint.
+    ///
+    /// Returns a permit that holds one slot of the endpoint's
+    /// `max_concurrent` and of the model's `model_limits` entry until it is
+    /// dropped, or an error if either limit is reached.
+    pub fn try_admit(
+        &mut self,
+        endpoint_name: &str,
+        model_id: &str,
+    ) -> std::result::Result<ConcurrencyPermit, AdmissionError> {
+        let entry = self
+            .endpoints
+            .get_mut(endpoint_name)
+            .ok_or_else(|| AdmissionError::UnknownEndpoint(endpoint_name.to_string()))?;
+
+        let upstream_model_id = entry
+            .discovered_models
+            .iter()
+            .find(|m| m.id == model_id)
+            .map_or(model_id, |m| m.upstream_model_id());
+        let model_limit = entry.meta.model_concurrency_limit(upstream_model_id);
+        let model_counter = entry
+            .model_active_requests
+            .entry(model_id.to_string())
+            .or_default();
+
+        ConcurrencyPermit::acquire(
+            (&entry.active_requests, entry.meta.max_concurrent),
+            (model_counter, model_limit),
+        )
+        .map_err(|exhausted| match exhausted {
+            admission::Exhausted::Endpoint => AdmissionError::EndpointAtCapacity {
+                endpoint: endpoint_name.to_string(),
+                limit: entry.meta.max_concurrent.unwrap_or_default(),
+            },
+            admission::Exhausted::Model => AdmissionError::ModelAtCapacity {
+                endpoint: endpoint_name.to_string(),
+                model: model_id.to_string(),
+                limit: model_limit.unwrap_or_default(),
+            },
+        })
+    }
+
     /// Select an endpoint for a given model.
     ///
-    /// Current behaviour:
     /// - Looks up the model in `model_index`.
-    /// - Filters to endpoints that are currently marked healthy.
-    /// - Returns the first matching endpoint entry, if any.
+    /// - Filters to endpoints that are currently routable (healthy and inside
+    ///   their schedule window).
+    /// - Among healthy candidates, returns the one with the lowest
+    ///   runtime-reported load score (see `EndpointLoad::score`). Endpoints
+    ///   without load data score as idle; ties keep index order.
     ///
-    /// Future work:
-    /// - Integrate `active_requests` and `max_concurrent`.
-    /// - Implement better scheduling (round-robin, least-loaded, etc.).
+    /// The proxy does not use this: requests carry an endpoint-specific slug
+    /// chosen by the control plane, so scraped load reaches routing only
+    /// through `NodeCapabilities::endpoint_load` and the catalog's
+    /// `CapacityHints`. Concurrency limits are enforced at admission (see
+    /// `try_admit`).
     pub fn select_endpoint_for_model(&self, model_id: &str) -> Option<(&String, &EndpointEntry)> {
         let endpoint_names = self.model_index.get(model_id)?;
-        for name in endpoint_names {
-            if let Some(entry) = self.endpoints.get(name) {
-                if entry.healthy {
-                    return self.endpoints.get_key_value(name);
+        endpoint_names
+            .iter()
+            .filter_map(|name| self.endpoints.get_key_value(name))
+            .filter(|(_, entry)| entry.is_routable())
+            .min_by(|(_, a), (_, b)| {
+                let score = |e: &EndpointEntry| e.endpoint.load.as_ref().map_or(0.0, |l| l.score());
+                score(a).total_cmp(&score(b))
+            })
+    }
+
+    /// Scrape runtime-reported load from every endpoint that is due.
+    ///
+    /// Only healthy endpoints whose fingerprinted runtime exposes load
+    /// (vLLM, llama.cpp) and whose `load_scrape_interval` has elapsed are
+    /// scraped. The registry lock is released while the HTTP requests are in
+    /// flight so that the proxy isn't blocked by slow upstreams.
+    ///
+    /// On success the load is stored on `Endpoint::load` and exported via the
+    /// metrics recorder; on failure it is cleared so that stale values do not
+    /// influence routing.
+    pub async fn scrape_load_all_http(registry: &Arc<tokio::sync::Mutex<EndpointRegistry>>) {
+        let now = Instant::now();
+        let targets: Vec<_> = {
+            let mut guard = registry.lock().await;
+            guard
+                .endpoints
+                .iter_mut()
+                .filter_map(|(name, entry)| {
+                    let interval = entry.meta.load_scrape_interval?;
+                    let runtime = entry
+                        .endpoint
+                        .runtime
+                        .filter(|r| load::supports_runtime(*r))?;
+                    if !entry.healthy
+                        || entry
+                            .last_load_scrape
+                            .is_some_and(|last| now.duration_since(last) < interval)
+                    {
+                        return None;
+                    }
+                    entry.last_load_scrape = Some(now);
+                    Some((
+                        name.clone(),
+                        entry.client.clone(),
+                        entry.endpoint.http_base_url(),
+                        runtime,
+                    ))
+                })
+                .collect()
+        };
+
+        for (name, client, base_url, runtime) in targets {
+            let result = load::scrape(&client, &base_url, runtime).await;
+
+            let mut guard = registry.lock().await;
+            let metrics = guard.metrics.clone();
+            let Some(entry) = guard.endpoints.get_mut(&name) else {
+                continue;
+            };
+
+            match result {
+                Ok(load) => {
+                    if let Some(metrics) = &metrics {
+                        metrics.record_endpoint_load(
+                            &name,
+                            load.running_requests,
+                            load.waiting_requests,
+                            load.kv_cache_usage,
+                        );
+                    }
+                    entry.endpoint.load = Some(load);
+                }
+                Err(e) => {
+                    tracing::debug!("endpoint '{}' load scrape failed: {}", name, e);
+                    entry.endpoint.load = None;
+                    if let Some(metrics) = &metrics {
+                        metrics.record_error(Some(name.as_str()), "load_scrape");
+                    }
                 }
             }
         }
-        None
+    }
+
+    /// Run the synthetic inference probe on every endpoint that is due.
+    ///
+    /// Only healthy endpoints with a `probe` configured whose interval has
+    /// elapsed are probed, and only if the probe model has been discovered
+    /// on them. As with load scraping, the registry lock is not held while
+    /// the completion is in flight.
+    ///
+    /// A failed probe marks just the probed model unhealthy (see
+    /// `ModelHealth`); the endpoint's other models keep being served. A later
+    /// successful probe restores it.
+    /// Model indices are rebuilt whenever a model's health changes.
+    pub async fn probe_models_all_http(registry: &Arc<tokio::sync::Mutex<EndpointRegistry>>) {
+        let now = Instant::now();
+        let targets: Vec<_> = {
+            let mut guard = registry.lock().await;
+            guard
+                .endpoints
+                .iter_mut()
+                .filter_map(|(name, entry)| {
+                    let probe = entry.meta.probe.as_ref()?;
+                    let interval = Duration::from_secs(probe.interval_secs);
+                    // Probes use the GPU, so they stay off outside the
+                    // endpoint's schedule.
+                    if !entry.is_routable()
+                        || entry
+                            .last_probe
+                            .is_some_and(|last| now.duration_since(last) < interval)
+                    {
+                        return None;
+                    }
+                    // The probe model may be given by its canonical or its
+                    // upstream ID; health is tracked under the canonical one.
+                    let Some(model) = entry
+                        .discovered_models
+                        .iter()
+                        .find(|m| m.id == probe.model || m.upstream_model_id() == probe.model)
+                    else {
+                        tracing::debug!(
+                            "endpoint '{}' probe model '{}' not discovered; skipping probe",
+                            name,
+                            probe.model
+                        );
+                        return None;
+                    };
+                    let model_ids = (model.id.clone(), model.upstream_model_id().to_string());
+                    entry.last_probe = Some(now);
+                    Some((
+                        name.clone(),
+                        entry.client.clone(),
+                        entry.endpoint.http_base_url(),
+                        model_ids,
+                        Duration::from_secs(probe.timeout_secs),
+                    ))
+                })
+                .collect()
+        };
+
+        for (name, client, base_url, (model, upstream_model), timeout) in targets {
+            let result = probe::run(&client, &base_url, &upstream_model, timeout).await;
+
+            let mut guard = registry.lock().await;
+            let metrics = guard.metrics.clone();
+            let Some(entry) = guard.endpoints.get_mut(&name) else {
+                continue;
+            };
+
+            let health = entry.model_health.entry(model.clone()).or_default();
+            let changed = match result {
+                Ok(latency) => {
+                    if let Some(metrics) = &metrics {
+                        metrics.record_probe(&name, &model, true, Some(latency.as_secs_f64()));
+                    }
+                    entry.probe_latency = Some(latency);
+                    let recovered = health.succeed();
+                    if recovered {
+                        tracing::info!("endpoint '{}' model '{}' probe recovered", name, model);
+                    }
+                    recovered
+                }
+                Err(e) => {
+                    tracing::warn!("endpoint '{}' model '{}' probe failed: {}", name, model, e);
+                    if let Some(metrics) = &metrics {
+                        metrics.record_probe(&name, &model, false, None);
+                    }
+                    // A probe is deliberate, so one failure is conclusive.
+                    health.fail(e.to_string(), 1)
+                }
+            };
+
+            if changed {
+                guard.rebuild_model_index();
+            }
+        }
+    }
+
+    /// Spawn a periodic task that runs `probe_models_all_http`.
+    ///
+    /// The task wakes at the shortest configured probe interval and re-reads
+    /// it whenever `apply_config` runs. While no endpoint has a probe
+    /// configured the task idles until the next configuration change.
+    pub fn spawn_periodic_probes<S>(
+        registry: Arc<tokio::sync::Mutex<EndpointRegistry>>,
+        shutdown: S,
+    ) where
+        S: std::future::Future<Output = ()> + Send + 'static,
+    {
+        tokio::spawn(async move {
+            let probed = registry.clone();
+            Self::run_periodically(
+                registry,
+                |guard| {
+                    guard
+                        .endpoints
+                        .values()
+                        .filter_map(|e| e.meta.probe.as_ref())
+                        .map(|p| Duration::from_secs(p.interval_secs))
+                        .min()
+                },
+                || Self::probe_models_all_http(&probed),
+                shutdown,
+            )
+            .await;
+            tracing::info!("stopping periodic endpoint probes");
+        });
+    }
+
+    /// Spawn a periodic task that runs `scrape_load_all_http`.
+    ///
+    /// The task wakes at the shortest configured `load_scrape_interval`;
+    /// endpoints with longer intervals are skipped until they are due. The
+    /// interval is re-read whenever `apply_config` runs, and while scraping
+    /// is disabled for every endpoint the task idles.
+    pub fn spawn_periodic_load_scrape<S>(
+        registry: Arc<tokio::sync::Mutex<EndpointRegistry>>,
+        shutdown: S,
+    ) where
+        S: std::future::Future<Output = ()> + Send + 'static,
+    {
+        tokio::spawn(async move {
+            let scraped = registry.clone();
+            Self::run_periodically(
+                registry,
+                |guard| {
+                    guard
+                        .endpoints
+                        .values()
+                        .filter_map(|e| e.meta.load_scrape_interval)
+                        .min()
+                },
+                || Self::scrape_load_all_http(&scraped),
+                shutdown,
+            )
+            .await;
+            tracing::info!("stopping periodic endpoint load scraper");
+        });
+    }
+
+    /// Run `pass` every `tick` until `shutdown` resolves.
+    ///
+    /// `tick` is evaluated under the registry lock before every pass and
+    /// again after each `apply_config`, so reloads that add, remove or
+    /// shorten intervals take effect without respawning the task. While
+    /// `tick` returns `None` no pass runs.
+    async fn run_periodically<T, P, F, S>(
+        registry: Arc<tokio::sync::Mutex<EndpointRegistry>>,
+        tick: T,
+        pass: P,
+        shutdown: S,
+    ) where
+        T: Fn(&EndpointRegistry) -> Option<Duration>,
+        P: Fn() -> F,
+        F: std::future::Future<Output = ()>,
+        S: std::future::Future<Output = ()>,
+    {
+        let config_changed = registry.lock().await.config_changed.clone();
+        tokio::pin!(shutdown);
+
+        loop {
+            // Created before reading the interval so a reload in between
+            // still wakes us.
+            let changed = config_changed.notified();
+            let interval = tick(&*registry.lock().await);
+            if interval.is_some() {
+                pass().await;
+            }
+
+            let wait = async {
+                match interval {
+                    Some(interval) => tokio::time::sleep(interval).await,
+                    None => std::future::pending().await,
+                }
+            };
+            tokio::select! {
+                _ = wait => {}
+                _ = changed => {}
+                _ = &mut shutdown => break,
+            }
+        }
     }
 
     /// Spawn a periodic HTTP-based health checker and model discovery task.
@@ -552,9 +1795,12 @@ impl EndpointRegistry {
     /// This is intended to be called from an async context with a shared
     /// `Arc<tokio::sync::Mutex<EndpointRegistry>>`. It will:
     ///
+    /// - Re-evaluate endpoint schedules (`update_schedules`) on the given
+    ///   interval, so schedule transitions are picked up within one tick.
     /// - Run `health_check_all_http` on the given interval.
-    /// - After each successful health pass, run `discover_models_all_http` so
-    ///   that model information stays reasonably fresh.
+    /// - After a successful health pass, run `discover_models_all_http` once
+    ///   `discovery_interval` has passed since the last discovery, so that
+    ///   model information stays reasonably fresh.
     /// - Log any internal errors but keep the task alive.
     ///
     /// The task will run until the provided `shutdown` future resolves.
@@ -564,29 +1810,44 @@ impl EndpointRegistry {
     /// ```ignore
     /// let registry = Arc::new(tokio::sync::Mutex::new(registry));
     /// let shutdown = shutdown_signal(); // some Future that resolves on shutdown
-    /// EndpointRegistry::spawn_periodic_health_check(registry.clone(), Duration::from_secs(30), shutdown);
+    /// EndpointRegistry::spawn_periodic_health_check(
+    ///     registry.clone(),
+    ///     Duration::from_secs(30),
+    ///     Duration::from_secs(300),
+    ///     shutdown,
+    /// );
     /// ```
     pub fn spawn_periodic_health_check<S>(
         registry: Arc<tokio::sync::Mutex<EndpointRegistry>>,
         interval: Duration,
+        discovery_interval: Duration,
         shutdown: S,
     ) where
         S: std::future::Future<Output = ()> + Send + 'static,
     {
         tokio::spawn(async move {
             let mut ticker = tokio::time::interval(interval);
+            let mut last_discovery: Option<tokio::time::Instant> = None;
             tokio::pin!(shutdown);
 
             loop {
                 tokio::select! {
-                    _ = ticker.tick() => {
-                        let mut guard = registry.lock().await;
-                        if let Err(err) = guard.health_check_all_http().await {
+                    tick = ticker.tick() => {
+                        registry.lock().await.update_schedules(Utc::now());
+
+                        if let Err(err) = Self::health_check_all_http(&registry).await {
                             tracing::warn!("periodic endpoint HTTP health check failed: {}", err);
                             continue;
                         }
 
-                        if let Err(err) = guard.discover_models_all_http().await {
+                        // Compare scheduled tick times rather than wall-clock
+                        // time, so a slow health pass doesn't skip a discovery.
+                        if last_discovery.is_some_and(|at| tick - at < discovery_interval) {
+                            continue;
+                        }
+                        last_discovery = Some(tick);
+
+                        if let Err(err) = Self::discover_models_all_http(&registry).await {
                             tracing::warn!("periodic endpoint model discovery failed: {}", err);
                         }
                     }
@@ -600,6 +1861,99 @@ impl EndpointRegistry {
     }
 }
 
+/// What `discover_models_all_http` needs to know about an endpoint to
+/// discover its models without holding the registry lock.
+struct DiscoveryTarget {
+    name: String,
+    client: reqwest::Client,
+    /// `Endpoint::base_url`, to detect endpoints replaced in the meantime.
+    base_url: String,
+    http_base_url: String,
+    kind: EndpointKind,
+    /// The runtime detected earlier, if any.
+    runtime: Option<RuntimeKind>,
+    models_include: Option<Vec<String>>,
+    models_exclude: Option<Vec<String>>,
+}
+
+/// List, fingerprint, filter and enrich the models of one endpoint.
+async fn discover_endpoint(
+    target: &DiscoveryTarget,
+) -> std::result::Result<(Vec<ModelDescriptor>, RuntimeKind), DiscoveryError> {
+    let client = &target.client;
+    let base_url = &target.http_base_url;
+
+    let (mut models, server_header) = match target.kind {
+        EndpointKind::OpenAi => discover_openai_models(client, base_url).await?,
+        EndpointKind::Ollama => (ollama::discover_models(client, base_url).await?, None),
+    };
+
+    // Fingerprint the runtime once per healthy period; native Ollama
+    // endpoints are known without probing.
+    let runtime = match (target.runtime, target.kind) {
+        (Some(runtime), _) => runtime,
+        (None, EndpointKind::Ollama) => RuntimeKind::Ollama,
+        (None, EndpointKind::OpenAi) => {
+            fingerprint::detect_runtime(client, base_url, server_header.as_deref(), &models).await
+        }
+    };
+
+    // Apply include filter
+    if let Some(include) = &target.models_include {
+        models.retain(|m| {
+            include
+                .iter()
+                .any(|pat| glob_match(pat.as_str(), m.id.as_str()))
+        });
+    }
+
+    // Apply exclude filter
+    if let Some(exclude) = &target.models_exclude {
+        models.retain(|m| {
+            !exclude
+                .iter()
+                .any(|pat| glob_match(pat.as_str(), m.id.as_str()))
+        });
+    }
+
+    // Native Ollama discovery already queried `/api/show`.
+    if target.kind == EndpointKind::OpenAi {
+        fingerprint::enrich_models(client, base_url, runtime, &mut models).await;
+    }
+
+    Ok((models, runtime))
+}
+
+/// Fetch the model list from an OpenAI-compatible `/v1/models` route.
+///
+/// Also returns the response's `Server` header, which is used as a hint when
+/// fingerprinting the runtime.
+async fn discover_openai_models(
+    client: &reqwest::Client,
+    http_base_url: &str,
+) -> std::result::Result<(Vec<ModelDescriptor>, Option<String>), DiscoveryError> {
+    // Base URLs are normalised to include the `/v1` prefix.
+    let models_url = format!("{}/models", http_base_url);
+
+    let resp = client
+        .get(&models_url)
+        .send()
+        .await
+        .map_err(DiscoveryError::Request)?;
+    if !resp.status().is_success() {
+        return Err(DiscoveryError::Status(resp.status()));
+    }
+
+    let server_header = resp
+        .headers()
+        .get(reqwest::header::SERVER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+
+    let list: ModelListResponse = resp.json().await.map_err(DiscoveryError::Parse)?;
+    Ok((list.data, server_header))
+}
+
 /// Factory for building an `EndpointRegistry` that is wired with telemetry.
 ///
 /// This can be used by higher-level components (e.g. `labmand`) to create a
@@ -687,9 +2041,13 @@ mod tests {
         LabmanConfig {
             control_plane: labman_config::ControlPlaneConfig {
                 base_url: "https://control.local/api/v1".to_string(),
-                node_token: "test-token".to_string(),
+                node_token: "test-token".into(),
+                node_token_file: None,
                 region: Some("test-region".to_string()),
                 description: Some("test node".to_string()),
+                heartbeat_interval_secs: 30,
+                state_file: None,
+                slug_scheme: Default::default(),
             },
             wireguard: WireGuardConfig {
                 interface_name: "labman0".to_string(),
@@ -704,13 +2062,21 @@ mod tests {
                 listen_port: 8080,
                 listen_addr: None,
             },
+            portman: Default::default(),
             telemetry: Some(TelemetryConfig {
                 log_level: Some("info".to_string()),
                 log_format: Some("text".to_string()),
+                log_output: None,
+                log_file: None,
+                log_file_max_size_mb: 10,
+                log_file_max_files: 5,
                 disable_metrics: false,
                 metrics_port: 9090,
+                metrics_addr: None,
             }),
+            health: Default::default(),
             endpoints: vec![],
+            models: Vec::new(),
         }
     }
 
@@ -732,6 +2098,8 @@ mod tests {
                 max_concurrent: None,
                 models_include: None,
                 models_exclude: None,
+                tenant: None,
+                ..Default::default()
             },
             EndpointConfig {
                 name: "dup".to_string(),
@@ -739,6 +2107,8 @@ mod tests {
                 max_concurrent: None,
                 models_include: None,
                 models_exclude: None,
+                tenant: None,
+                ..Default::default()
             },
         ];
 
@@ -755,6 +2125,8 @@ mod tests {
             max_concurrent: Some(8),
             models_include: Some(vec!["llama*".to_string()]),
             models_exclude: Some(vec!["*test*".to_string()]),
+            tenant: None,
+            ..Default::default()
         }];
 
         let registry = EndpointRegistry::from_config(&cfg).expect("build registry");
@@ -774,6 +2146,417 @@ mod tests {
         );
     }
 
+    #[test]
+    fn equivalent_base_urls_produce_the_same_slugs() {
+        let slugs = |base_url: &str| {
+            let mut cfg = minimal_config();
+            cfg.endpoints = vec![EndpointConfig {
+                name: "ollama".to_string(),
+                base_url: base_url.to_string(),
+                ..Default::default()
+            }];
+            let mut registry = EndpointRegistry::from_config(&cfg).expect("build registry");
+            let entry = registry.get_mut("ollama").unwrap();
+            assert_eq!(entry.endpoint.base_url, "http://10.0.0.5:11434/v1");
+            entry.discovered_models = vec![ModelDescriptor::new("llama3")];
+            entry.healthy = true;
+            registry.rebuild_model_index();
+
+            let mut slugs: Vec<String> = registry.hash_index.keys().cloned().collect();
+            slugs.sort();
+            slugs
+        };
+
+        let canonical = slugs("http://10.0.0.5:11434/v1");
+        assert_eq!(canonical.len(), 1);
+        assert_eq!(slugs("http://10.0.0.5:11434"), canonical);
+        assert_eq!(slugs("HTTP://10.0.0.5:11434/v1/"), canonical);
+    }
+
+    #[test]
+    fn registry_accepts_unix_socket_endpoint() {
+        let mut cfg = minimal_config();
+        cfg.endpoints = vec![EndpointConfig {
+            name: "ollama-sock".to_string(),
+            base_url: "unix:///run/ollama.sock:/v1".to_string(),
+            max_concurrent: None,
+            models_include: None,
+            models_exclude: None,
+            tenant: None,
+            ..Default::default()
+        }];
+
+        let registry = EndpointRegistry::from_config(&cfg).expect("build registry");
+        let entry = registry.get("ollama-sock").expect("endpoint present");
+        assert_eq!(entry.endpoint.unix_socket_path(), Some("/run/ollama.sock"));
+        assert_eq!(entry.endpoint.http_base_url(), "http://localhost/v1");
+    }
+
+    /// Serve a fixed JSON body to every HTTP/1.1 request on a Unix socket.
+    async fn serve_unix_json(listener: tokio::net::UnixListener, body: &'static str) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        loop {
+            let Ok((mut stream, _)) = listener.accept().await else {
+                return;
+            };
+            tokio::spawn(async move {
+                let mut buf = [0u8; 4096];
+                let _ = stream.read(&mut buf).await;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            });
+        }
+    }
+
+    /// Serve fixed JSON bodies keyed by request path on a Unix socket; other
+    /// paths get a 404.
+    /// Route body that makes `serve_unix_routes` accept the request but never
+    /// answer it.
+    const STALL: &str = "<stall>";
+
+    async fn serve_unix_routes(
+        listener: tokio::net::UnixListener,
+        routes: &'static [(&'static str, &'static str)],
+    ) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        loop {
+            let Ok((mut stream, _)) = listener.accept().await else {
+                return;
+            };
+            tokio::spawn(async move {
+                let mut buf = [0u8; 4096];
+                let n = stream.read(&mut buf).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]);
+                let path = request.split_whitespace().nth(1).unwrap_or("/");
+                let response = match routes.iter().find(|(p, _)| *p == path) {
+                    Some((_, body)) if *body == STALL => return std::future::pending().await,
+                    Some((_, body)) => format!(
+                        "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{}",
+                        body.len(),
+                        body
+                    ),
+                    None => "HTTP/1.1 404 Not Found\r\ncontent-length: 0\r\nconnection: close\r\n\r\n"
+                        .to_string(),
+                };
+                let _ = stream.write_all(response.as_bytes()).await;
+            });
+        }
+    }
+
+    #[tokio::test]
+    async fn discovery_fingerprints_llama_cpp_and_enriches_models() {
+        let socket_path = std::env::temp_dir().join(format!(
+            "labman-endpoints-fingerprint-{}.sock",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&socket_path);
+        let listener = tokio::net::UnixListener::bind(&socket_path).expect("bind unix socket");
+        tokio::spawn(serve_unix_routes(
+            listener,
+            &[
+                ("/v1", "{}"),
+                (
+                    "/v1/models",
+                    r#"{"object":"list","data":[{"id":"qwen2.5-7b","owned_by":"someone"}]}"#,
+                ),
+                (
+                    "/props",
+                    r#"{"default_generation_settings":{"n_ctx":8192,"params":{"n_predict":1024}},"modalities":{"vision":false}}"#,
+                ),
+            ],
+        ));
+
+        let mut cfg = minimal_config();
+        cfg.endpoints = vec![EndpointConfig {
+            name: "llama".to_string(),
+            base_url: format!("unix://{}:/v1", socket_path.display()),
+            ..Default::default()
+        }];
+
+        let registry = Arc::new(tokio::sync::Mutex::new(
+            EndpointRegistry::from_config(&cfg).expect("build registry"),
+        ));
+        EndpointRegistry::health_check_all_http(&registry)
+            .await
+            .expect("health check");
+        EndpointRegistry::discover_models_all_http(&registry)
+            .await
+            .expect("model discovery");
+        let registry = registry.lock().await;
+
+        let entry = registry.get("llama").unwrap();
+        assert_eq!(entry.endpoint.runtime, Some(RuntimeKind::LlamaCpp));
+        let model = &entry.discovered_models[0];
+        assert_eq!(model.context_length, Some(8192));
+        assert_eq!(model.max_output_tokens, Some(1024));
+        assert_eq!(model.modalities, vec!["text"]);
+
+        let _ = std::fs::remove_file(&socket_path);
+    }
+
+    #[tokio::test]
+    async fn discovery_releases_the_registry_lock_while_probing() {
+        let socket_path = std::env::temp_dir().join(format!(
+            "labman-endpoints-unlocked-{}.sock",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&socket_path);
+        let listener = tokio::net::UnixListener::bind(&socket_path).expect("bind unix socket");
+        tokio::spawn(serve_unix_routes(
+            listener,
+            &[
+                (
+                    "/v1/models",
+                    r#"{"object":"list","data":[{"id":"m","owned_by":"someone"}]}"#,
+                ),
+                // The first fingerprint probe never gets an answer.
+                ("/api/version", STALL),
+            ],
+        ));
+
+        let mut cfg = minimal_config();
+        cfg.endpoints = vec![EndpointConfig {
+            name: "stalled".to_string(),
+            base_url: format!("unix://{}:/v1", socket_path.display()),
+            ..Default::default()
+        }];
+
+        let mut registry = EndpointRegistry::from_config(&cfg).expect("build registry");
+        registry.endpoints.get_mut("stalled").unwrap().healthy = true;
+        let registry = Arc::new(tokio::sync::Mutex::new(registry));
+        let discovery = tokio::spawn({
+            let registry = registry.clone();
+            async move { EndpointRegistry::discover_models_all_http(&registry).await }
+        });
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(!discovery.is_finished(), "discovery waits for the probe");
+        let guard = tokio::time::timeout(Duration::from_secs(1), registry.lock())
+            .await
+            .expect("registry usable while the upstream stalls");
+        assert!(matches!(
+            guard.resolve_slug("unknown"),
+            SlugResolution::Unknown
+        ));
+        drop(guard);
+        discovery.abort();
+
+        let _ = std::fs::remove_file(&socket_path);
+    }
+
+    #[tokio::test]
+    async fn discovery_timeout_covers_fingerprinting() {
+        let socket_path = std::env::temp_dir().join(format!(
+            "labman-endpoints-slow-fingerprint-{}.sock",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&socket_path);
+        let listener = tokio::net::UnixListener::bind(&socket_path).expect("bind unix socket");
+        tokio::spawn(serve_unix_routes(
+            listener,
+            &[
+                (
+                    "/v1/models",
+                    r#"{"object":"list","data":[{"id":"m","owned_by":"someone"}]}"#,
+                ),
+                ("/api/version", STALL),
+            ],
+        ));
+
+        let mut cfg = minimal_config();
+        cfg.health.timeout_secs = 1;
+        cfg.endpoints = vec![EndpointConfig {
+            name: "stalled".to_string(),
+            base_url: format!("unix://{}:/v1", socket_path.display()),
+            ..Default::default()
+        }];
+
+        let mut registry = EndpointRegistry::from_config(&cfg).expect("build registry");
+        registry.endpoints.get_mut("stalled").unwrap().healthy = true;
+        let registry = Arc::new(tokio::sync::Mutex::new(registry));
+
+        // The model list answers at once; the stalled probe after it must
+        // still be cut off by health.timeout_secs.
+        tokio::time::timeout(
+            Duration::from_secs(5),
+            EndpointRegistry::discover_models_all_http(&registry),
+        )
+        .await
+        .expect("discovery bounded by health.timeout_secs")
+        .expect("discovery");
+
+        let guard = registry.lock().await;
+        let entry = guard.get("stalled").unwrap();
+        assert!(entry.discovered_models.is_empty());
+        assert!(entry.endpoint.runtime.is_none());
+        drop(guard);
+
+        let _ = std::fs::remove_file(&socket_path);
+    }
+
+    #[tokio::test]
+    async fn discovery_applies_model_aliases_to_index_and_slugs() {
+        let socket_path = std::env::temp_dir().join(format!(
+            "labman-endpoints-aliases-{}.sock",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&socket_path);
+        let listener = tokio::net::UnixListener::bind(&socket_path).expect("bind unix socket");
+        tokio::spawn(serve_unix_routes(
+            listener,
+            &[
+                ("/v1", "{}"),
+                (
+                    "/v1/models",
+                    r#"{"object":"list","data":[{"id":"meta-llama/Llama-3.1-8B-Instruct","owned_by":"vllm","max_model_len":8192}]}"#,
+                ),
+            ],
+        ));
+
+        let mut cfg = minimal_config();
+        cfg.endpoints = vec![EndpointConfig {
+            name: "vllm".to_string(),
+            base_url: format!("unix://{}:/v1", socket_path.display()),
+            load_scrape_interval_secs: Some(0),
+            ..Default::default()
+        }];
+        cfg.models = vec![ModelConfig {
+            id: "llama-3.1-8b-instruct".to_string(),
+            patterns: vec!["meta-llama/Llama-3.1-8B-Instruct".to_string()],
+            context_length: Some(32768),
+            ..Default::default()
+        }];
+
+        let registry = Arc::new(tokio::sync::Mutex::new(
+            EndpointRegistry::from_config(&cfg).expect("build registry"),
+        ));
+        EndpointRegistry::health_check_all_http(&registry)
+            .await
+            .expect("health check");
+        EndpointRegistry::discover_models_all_http(&registry)
+            .await
+            .expect("model discovery");
+        let registry = registry.lock().await;
+
+        let caps = registry.to_node_capabilities();
+        assert_eq!(caps.models.len(), 1);
+        assert_eq!(caps.models[0].id, "llama-3.1-8b-instruct");
+        assert_eq!(caps.models[0].context_length, Some(32768));
+        assert_eq!(
+            registry.model_index().get("llama-3.1-8b-instruct"),
+            Some(&vec!["vllm".to_string()])
+        );
+
+        let slug = labman_core::slug::encode_model_slug(
+            "",
+            &format!("{}:/v1", socket_path.display()),
+            "llama-3.1-8b-instruct",
+        );
+        let mapping = registry.lookup_hashed_model(&slug).expect("aliased slug");
+        assert_eq!(mapping.model_id, "llama-3.1-8b-instruct");
+        assert_eq!(
+            mapping.upstream_model_id,
+            "meta-llama/Llama-3.1-8B-Instruct"
+        );
+
+        let _ = std::fs::remove_file(&socket_path);
+    }
+
+    #[tokio::test]
+    async fn health_and_discovery_work_over_unix_socket() {
+        let socket_path =
+            std::env::temp_dir().join(format!("labman-endpoints-test-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&socket_path);
+        let listener = tokio::net::UnixListener::bind(&socket_path).expect("bind unix socket");
+        tokio::spawn(serve_unix_json(
+            listener,
+            r#"{"object":"list","data":[{"id":"llama3.2:3b"}]}"#,
+        ));
+
+        let mut cfg = minimal_config();
+        cfg.endpoints = vec![EndpointConfig {
+            name: "ollama-sock".to_string(),
+            base_url: format!("unix://{}:/v1", socket_path.display()),
+            max_concurrent: None,
+            models_include: None,
+            models_exclude: None,
+            tenant: None,
+            ..Default::default()
+        }];
+
+        let registry = Arc::new(tokio::sync::Mutex::new(
+            EndpointRegistry::from_config(&cfg).expect("build registry"),
+        ));
+        EndpointRegistry::health_check_all_http(&registry)
+            .await
+            .expect("health check");
+        assert!(registry.lock().await.get("ollama-sock").unwrap().healthy);
+
+        EndpointRegistry::discover_models_all_http(&registry)
+            .await
+            .expect("model discovery");
+        let registry = registry.lock().await;
+        let (name, _) = registry
+            .select_endpoint_for_model("llama3.2:3b")
+            .expect("model discovered over unix socket");
+        assert_eq!(name.as_str(), "ollama-sock");
+
+        let _ = std::fs::remove_file(&socket_path);
+    }
+
+    #[tokio::test]
+    async fn health_check_gives_up_after_the_configured_timeout() {
+        let socket_path = std::env::temp_dir().join(format!(
+            "labman-endpoints-stalled-{}.sock",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&socket_path);
+        let listener = tokio::net::UnixListener::bind(&socket_path).expect("bind unix socket");
+        // Accept connections but never answer.
+        tokio::spawn(async move {
+            let mut open = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                open.push(stream);
+            }
+        });
+
+        let mut cfg = minimal_config();
+        cfg.health.timeout_secs = 1;
+        cfg.endpoints = vec![EndpointConfig {
+            name: "stalled".to_string(),
+            base_url: format!("unix://{}:/v1", socket_path.display()),
+            ..Default::default()
+        }];
+
+        let mut registry = EndpointRegistry::from_config(&cfg).expect("build registry");
+        registry.endpoints.get_mut("stalled").unwrap().healthy = true;
+        let registry = Arc::new(tokio::sync::Mutex::new(registry));
+        let check = tokio::spawn({
+            let registry = registry.clone();
+            async move { EndpointRegistry::health_check_all_http(&registry).await }
+        });
+
+        // The registry stays usable while the request is pending.
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(!check.is_finished());
+        assert!(registry.try_lock().is_ok());
+
+        tokio::time::timeout(Duration::from_secs(5), check)
+            .await
+            .expect("health check bounded by health.timeout_secs")
+            .unwrap()
+            .expect("health check");
+        assert!(!registry.lock().await.get("stalled").unwrap().healthy);
+
+        let _ = std::fs::remove_file(&socket_path);
+    }
+
     #[test]
     fn labman_config_to_node_info_still_compiles_with_registry_present() {
         let cfg = minimal_config();
@@ -806,6 +2589,8 @@ mod tests {
                 max_concurrent: Some(2),
                 models_include: None,
                 models_exclude: None,
+                tenant: None,
+                ..Default::default()
             },
             EndpointConfig {
                 name: "ep2".to_string(),
@@ -813,6 +2598,8 @@ mod tests {
                 max_concurrent: Some(3),
                 models_include: None,
                 models_exclude: None,
+                tenant: None,
+                ..Default::default()
             },
         ];
 
@@ -853,6 +2640,8 @@ mod tests {
                 max_concurrent: Some(2),
                 models_include: None,
                 models_exclude: None,
+                tenant: None,
+                ..Default::default()
             },
             EndpointConfig {
                 name: "unhealthy-ep".to_string(),
@@ -860,6 +2649,8 @@ mod tests {
                 max_concurrent: Some(2),
                 models_include: None,
                 models_exclude: None,
+                tenant: None,
+                ..Default::default()
             },
         ];
 
@@ -886,4 +2677,697 @@ mod tests {
         let none = registry.select_endpoint_for_model("non-existent-model");
         assert!(none.is_none());
     }
+
+    fn load(running: u64, waiting: u64) -> labman_core::EndpointLoad {
+        labman_core::EndpointLoad {
+            running_requests: Some(running),
+            waiting_requests: Some(waiting),
+            kv_cache_usage: None,
+            total_slots: None,
+            scraped_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn select_endpoint_for_model_prefers_least_loaded() {
+        let mut cfg = minimal_config();
+        cfg.endpoints = ["busy", "idle"]
+            .iter()
+            .map(|name| EndpointConfig {
+                name: name.to_string(),
+                base_url: format!("http://{}.local:8000/v1", name),
+                ..Default::default()
+            })
+            .collect();
+
+        let mut registry = EndpointRegistry::from_config(&cfg).expect("build registry");
+        for (name, load) in [("busy", load(4, 3)), ("idle", load(1, 0))] {
+            let entry = registry.get_mut(name).unwrap();
+            entry.discovered_models = vec![ModelDescriptor::new("qwen")];
+            entry.healthy = true;
+            entry.endpoint.load = Some(load);
+        }
+        registry.rebuild_model_index();
+
+        let (name, _) = registry.select_endpoint_for_model("qwen").unwrap();
+        assert_eq!(name.as_str(), "idle");
+
+        let caps = registry.to_node_capabilities();
+        assert_eq!(caps.endpoint_load["busy"].waiting_requests, Some(3));
+        assert_eq!(caps.endpoint_load.len(), 2);
+    }
+
+    #[test]
+    fn inventory_changes_are_published_to_subscribers() {
+        let mut cfg = minimal_config();
+        cfg.endpoints = vec![EndpointConfig {
+            name: "ep".to_string(),
+            base_url: "http://127.0.0.1:8000/v1".to_string(),
+            ..Default::default()
+        }];
+        let mut registry = EndpointRegistry::from_config(&cfg).expect("build registry");
+        let mut events = registry.subscribe();
+        let mut drain = || {
+            let mut out = Vec::new();
+            while let Ok(event) = events.try_recv() {
+                out.push(event);
+            }
+            out
+        };
+
+        registry.health_check_all().unwrap();
+        registry.get_mut("ep").unwrap().discovered_models =
+            vec![ModelDescriptor::new("a"), ModelDescriptor::new("b")];
+        registry.rebuild_model_index();
+
+        let kinds: Vec<_> = drain().iter().map(|e| e.kind()).collect();
+        assert_eq!(kinds, vec!["endpoint_up", "model_added", "model_added"]);
+
+        // Unchanged inventory publishes nothing.
+        registry.rebuild_model_index();
+        assert!(drain().is_empty());
+
+        registry.get_mut("ep").unwrap().discovered_models =
+            vec![ModelDescriptor::new("b"), ModelDescriptor::new("c")];
+        registry.rebuild_model_index();
+        let events = drain();
+        assert_eq!(events.len(), 2);
+        assert!(matches!(
+            &events[0],
+            InventoryEvent::ModelRemoved { model_id, .. } if model_id == "a"
+        ));
+        assert!(matches!(
+            &events[1],
+            InventoryEvent::ModelAdded { endpoint, model_id, slug }
+                if endpoint == "ep"
+                    && model_id == "c"
+                    && registry.lookup_hashed_model(slug).is_some()
+        ));
+    }
+
+    #[test]
+    fn slug_collisions_are_detected_instead_of_overwritten() {
+        let mut cfg = minimal_config();
+        cfg.endpoints = ["a", "b"]
+            .iter()
+            .map(|name| EndpointConfig {
+                name: name.to_string(),
+                // Same URL twice (e.g. a copy-pasted block): identical v0 slugs.
+                base_url: "http://127.0.0.1:8000/v1".to_string(),
+                ..Default::default()
+            })
+            .collect();
+
+        let build = |cfg: &LabmanConfig| {
+            let mut registry = EndpointRegistry::from_config(cfg).expect("build registry");
+            for name in ["a", "b"] {
+                let entry = registry.get_mut(name).unwrap();
+                entry.healthy = true;
+                entry.discovered_models = vec![ModelDescriptor::new("m")];
+            }
+            registry.rebuild_model_index();
+            registry
+        };
+
+        let registry = build(&cfg);
+        let slug = labman_core::slug::encode_model_slug("", "127.0.0.1:8000/v1", "m");
+        assert_eq!(
+            registry.lookup_hashed_model(&slug).unwrap().endpoint_name,
+            "a"
+        );
+        let collisions = registry.slug_collisions();
+        assert_eq!(collisions.len(), 1);
+        assert_eq!(collisions[0].slug, slug);
+        assert_eq!(collisions[0].dropped.endpoint_name, "b");
+
+        // Keying on the endpoint name separates the two.
+        cfg.control_plane.slug_scheme = SlugScheme {
+            version: labman_core::SlugVersion::V1,
+            endpoint_key: labman_core::SlugEndpointKey::Name,
+            long_digest: false,
+        };
+        let registry = build(&cfg);
+        assert!(registry.slug_collisions().is_empty());
+        let slug_b = cfg
+            .control_plane
+            .slug_scheme
+            .encode("", "b", "127.0.0.1:8000/v1", "m");
+        assert_eq!(
+            registry.lookup_hashed_model(&slug_b).unwrap().endpoint_name,
+            "b"
+        );
+        assert_eq!(
+            registry.to_node_capabilities().slug_scheme,
+            cfg.control_plane.slug_scheme
+        );
+    }
+
+    #[test]
+    fn apply_config_updates_endpoints_in_place() {
+        let endpoint = |name: &str, port: u16| EndpointConfig {
+            name: name.to_string(),
+            base_url: format!("http://127.0.0.1:{}/v1", port),
+            ..Default::default()
+        };
+        let mut cfg = minimal_config();
+        cfg.endpoints = vec![endpoint("kept", 8000), endpoint("moved", 8001)];
+        cfg.endpoints.push(endpoint("removed", 8002));
+        let mut registry = EndpointRegistry::from_config(&cfg).expect("build registry");
+        for entry in registry.endpoints.values_mut() {
+            entry.healthy = true;
+            entry.discovered_models = vec![ModelDescriptor::new("m")];
+        }
+        registry.rebuild_model_index();
+        let permit = registry.try_admit("kept", "m").unwrap();
+
+        let mut reloaded = cfg.clone();
+        reloaded.endpoints = vec![
+            EndpointConfig {
+                max_concurrent: Some(2),
+                tenant: Some("acme".to_string()),
+                ..endpoint("kept", 8000)
+            },
+            endpoint("moved", 9001),
+            endpoint("added", 8003),
+        ];
+        registry.apply_config(&reloaded).expect("apply config");
+
+        // Changed in place: health, models and in-flight requests survive.
+        let kept = registry.get("kept").unwrap();
+        assert!(kept.is_routable());
+        assert_eq!(kept.meta.max_concurrent, Some(2));
+        assert_eq!(kept.tenant.as_deref(), Some("acme"));
+        assert_eq!(kept.active_requests(), 1);
+        drop(permit);
+
+        // A new base URL starts from scratch, like a new endpoint.
+        let moved = registry.get("moved").unwrap();
+        assert_eq!(moved.endpoint.base_url, "http://127.0.0.1:9001/v1");
+        assert!(!moved.is_routable());
+        assert!(registry.get("added").is_some());
+        assert!(registry.get("removed").is_none());
+        assert_eq!(registry.model_index()["m"], vec!["kept"]);
+
+        // An invalid endpoint leaves the registry as it was.
+        let mut invalid = reloaded.clone();
+        invalid.endpoints.push(EndpointConfig {
+            base_url: "ftp://nope".to_string(),
+            ..endpoint("broken", 0)
+        });
+        assert!(registry.apply_config(&invalid).is_err());
+        assert!(registry.get("broken").is_none());
+        assert_eq!(registry.len(), 3);
+    }
+
+    #[test]
+    fn scheduled_endpoints_drain_and_reappear_with_their_window() {
+        use chrono::TimeZone;
+
+        let mut cfg = minimal_config();
+        cfg.endpoints = vec![EndpointConfig {
+            name: "gaming-pc".to_string(),
+            base_url: "http://127.0.0.1:8000/v1".to_string(),
+            schedule: Some(labman_config::ScheduleConfig {
+                timezone: Some("UTC".to_string()),
+                drain_secs: Some(600),
+                windows: vec![labman_config::ScheduleWindowConfig {
+                    days: None,
+                    start: Some("22:00".to_string()),
+                    end: Some("07:00".to_string()),
+                }],
+            }),
+            ..Default::default()
+        }];
+        let mut registry = EndpointRegistry::from_config(&cfg).expect("build registry");
+        let at = |hour, minute| Utc.with_ymd_and_hms(2026, 6, 5, hour, minute, 0).unwrap();
+
+        registry.update_schedules(at(23, 0));
+        let mut events = registry.subscribe();
+        {
+            let entry = registry.get_mut("gaming-pc").unwrap();
+            entry.healthy = true;
+            entry.discovered_models = vec![ModelDescriptor::new("m")];
+        }
+        registry.rebuild_model_index();
+        let slug = labman_core::slug::encode_model_slug("", "127.0.0.1:8000/v1", "m");
+        assert!(matches!(
+            registry.resolve_slug(&slug),
+            SlugResolution::Available(_)
+        ));
+        assert_eq!(registry.to_node_capabilities().models.len(), 1);
+
+        // Ten minutes before the window closes the endpoint drains.
+        registry.update_schedules(at(6, 55));
+        let entry = registry.get("gaming-pc").unwrap();
+        assert_eq!(entry.schedule_state(), ScheduleState::Draining);
+        assert!(!entry.is_routable());
+        assert!(matches!(
+            registry.resolve_slug(&slug),
+            SlugResolution::Unavailable(_)
+        ));
+        assert!(registry.to_node_capabilities().models.is_empty());
+
+        registry.update_schedules(at(12, 0));
+        assert_eq!(
+            registry.get("gaming-pc").unwrap().schedule_state(),
+            ScheduleState::Closed
+        );
+
+        registry.update_schedules(at(22, 0));
+        assert!(matches!(
+            registry.resolve_slug(&slug),
+            SlugResolution::Available(_)
+        ));
+        assert_eq!(registry.to_node_capabilities().models.len(), 1);
+
+        let mut states = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            if let InventoryEvent::EndpointScheduleChanged { state, .. } = event {
+                states.push(state);
+            }
+        }
+        assert_eq!(
+            states,
+            vec![
+                ScheduleState::Draining,
+                ScheduleState::Closed,
+                ScheduleState::Open
+            ]
+        );
+    }
+
+    #[test]
+    fn slugs_of_down_endpoints_resolve_as_unavailable_during_grace_period() {
+        let mut cfg = minimal_config();
+        cfg.endpoints = vec![EndpointConfig {
+            name: "ep".to_string(),
+            base_url: "http://127.0.0.1:8000/v1".to_string(),
+            ..Default::default()
+        }];
+        let mut registry = EndpointRegistry::from_config(&cfg).expect("build registry");
+        {
+            let entry = registry.get_mut("ep").unwrap();
+            entry.healthy = true;
+            entry.discovered_models = vec![ModelDescriptor::new("a"), ModelDescriptor::new("b")];
+        }
+        registry.rebuild_model_index();
+        let slug =
+            |model: &str| labman_core::slug::encode_model_slug("", "127.0.0.1:8000/v1", model);
+        assert!(matches!(
+            registry.resolve_slug(&slug("a")),
+            SlugResolution::Available(_)
+        ));
+
+        // Health flips before the next rebuild: already reported unavailable.
+        registry.get_mut("ep").unwrap().healthy = false;
+        assert!(matches!(
+            registry.resolve_slug(&slug("a")),
+            SlugResolution::Unavailable(_)
+        ));
+
+        // After the rebuild the slug is no longer routable but still known.
+        registry.rebuild_model_index();
+        assert!(registry.lookup_hashed_model(&slug("a")).is_none());
+        assert!(matches!(
+            registry.resolve_slug(&slug("a")),
+            SlugResolution::Unavailable(m) if m.model_id == "a"
+        ));
+        assert!(matches!(
+            registry.resolve_slug("no-such-slug"),
+            SlugResolution::Unknown
+        ));
+
+        // Once the grace period is over the slug is forgotten.
+        registry.retained_slugs.get_mut(&slug("a")).unwrap().1 =
+            Utc::now() - chrono::Duration::seconds(SLUG_RETENTION.as_secs() as i64 + 1);
+        assert!(matches!(
+            registry.resolve_slug(&slug("a")),
+            SlugResolution::Unknown
+        ));
+
+        // A model that disappears from a recovered endpoint is gone for good.
+        {
+            let entry = registry.get_mut("ep").unwrap();
+            entry.healthy = true;
+            entry.discovered_models = vec![ModelDescriptor::new("a")];
+        }
+        registry.rebuild_model_index();
+        assert!(matches!(
+            registry.resolve_slug(&slug("b")),
+            SlugResolution::Unknown
+        ));
+        assert!(matches!(
+            registry.resolve_slug(&slug("a")),
+            SlugResolution::Available(_)
+        ));
+    }
+
+    #[test]
+    fn per_model_limits_gate_admission_and_sum_into_capacity() {
+        let mut cfg = minimal_config();
+        cfg.endpoints = vec![EndpointConfig {
+            name: "ollama".to_string(),
+            base_url: "http://127.0.0.1:11434/v1".to_string(),
+            max_concurrent: Some(16),
+            model_limits: vec![
+                labman_config::ModelLimitConfig {
+                    pattern: "*70b*".to_string(),
+                    max_concurrent: 1,
+                },
+                labman_config::ModelLimitConfig {
+                    pattern: "*".to_string(),
+                    max_concurrent: 8,
+                },
+            ],
+            ..Default::default()
+        }];
+        let mut registry = EndpointRegistry::from_config(&cfg).expect("build registry");
+        {
+            let entry = registry.get_mut("ollama").unwrap();
+            entry.healthy = true;
+            entry.discovered_models = vec![
+                ModelDescriptor::new("llama3.1:70b"),
+                ModelDescriptor::new("llama3.1:8b"),
+            ];
+        }
+
+        // 1 + 8 is below the endpoint limit of 16.
+        assert_eq!(
+            registry.to_node_capabilities().max_concurrent_requests,
+            Some(9)
+        );
+
+        let big = registry.try_admit("ollama", "llama3.1:70b").unwrap();
+        assert!(matches!(
+            registry.try_admit("ollama", "llama3.1:70b"),
+            Err(AdmissionError::ModelAtCapacity { limit: 1, .. })
+        ));
+        let small = registry.try_admit("ollama", "llama3.1:8b").unwrap();
+        assert_eq!(registry.get("ollama").unwrap().active_requests(), 2);
+
+        drop(big);
+        drop(small);
+        assert_eq!(registry.get("ollama").unwrap().active_requests(), 0);
+        assert!(registry.try_admit("ollama", "llama3.1:70b").is_ok());
+        assert!(matches!(
+            registry.try_admit("missing", "m"),
+            Err(AdmissionError::UnknownEndpoint(_))
+        ));
+    }
+
+    #[test]
+    fn slug_catalog_lists_available_and_retained_slugs() {
+        let mut cfg = minimal_config();
+        cfg.endpoints = vec![
+            EndpointConfig {
+                name: "up".to_string(),
+                base_url: "http://127.0.0.1:8000/v1".to_string(),
+                max_concurrent: Some(4),
+                ..Default::default()
+            },
+            EndpointConfig {
+                name: "down".to_string(),
+                base_url: "http://127.0.0.1:8001/v1".to_string(),
+                tenant: Some("lab".to_string()),
+                ..Default::default()
+            },
+        ];
+        let mut registry = EndpointRegistry::from_config(&cfg).expect("build registry");
+        for name in ["up", "down"] {
+            let entry = registry.get_mut(name).unwrap();
+            entry.healthy = true;
+            let mut model = ModelDescriptor::new("m");
+            model.context_length = Some(8192);
+            entry.discovered_models = vec![model];
+        }
+        registry.rebuild_model_index();
+        registry.get_mut("down").unwrap().healthy = false;
+        registry.rebuild_model_index();
+
+        let catalog = registry.slug_catalog();
+        assert_eq!(catalog.slug_scheme, SlugScheme::default());
+        let endpoints: Vec<&str> = catalog
+            .entries
+            .iter()
+            .map(|e| e.endpoint.as_str())
+            .collect();
+        assert_eq!(endpoints, vec!["down", "up"]);
+
+        let down = &catalog.entries[0];
+        assert_eq!(down.health, SlugHealth::Unavailable);
+        assert_eq!(down.tenant.as_deref(), Some("lab"));
+        assert_eq!(
+            down.slug,
+            labman_core::slug::encode_model_slug("lab", "127.0.0.1:8001/v1", "m")
+        );
+
+        let up = &catalog.entries[1];
+        assert_eq!(up.health, SlugHealth::Available);
+        assert_eq!(up.capacity.max_concurrent, Some(4));
+        assert_eq!(up.capacity.context_length, Some(8192));
+        assert!(registry.lookup_hashed_model(&up.slug).is_some());
+    }
+
+    #[test]
+    fn model_failures_exclude_model_until_recovery() {
+        let mut cfg = minimal_config();
+        cfg.endpoints = vec![EndpointConfig {
+            name: "vllm".to_string(),
+            base_url: "http://127.0.0.1:8000/v1".to_string(),
+            ..Default::default()
+        }];
+
+        let mut registry = EndpointRegistry::from_config(&cfg).expect("build registry");
+        {
+            let entry = registry.get_mut("vllm").unwrap();
+            entry.healthy = true;
+            entry.discovered_models = vec![ModelDescriptor::new("a"), ModelDescriptor::new("b")];
+        }
+        registry.rebuild_model_index();
+        let slug_a = labman_core::slug::encode_model_slug("", "127.0.0.1:8000/v1", "a");
+        assert!(registry.lookup_hashed_model(&slug_a).is_some());
+
+        for _ in 0..MODEL_FAILURE_THRESHOLD - 1 {
+            registry.record_model_failure("vllm", "a", "HTTP 500");
+        }
+        assert!(registry.get("vllm").unwrap().is_model_healthy("a"));
+
+        registry.record_model_failure("vllm", "a", "HTTP 503");
+        let entry = registry.get("vllm").unwrap();
+        assert!(!entry.is_model_healthy("a"));
+        assert_eq!(
+            entry.model_health("a").unwrap().last_error.as_deref(),
+            Some("HTTP 503")
+        );
+        assert!(registry.lookup_hashed_model(&slug_a).is_none());
+        assert!(!registry.model_index().contains_key("a"));
+        assert!(registry.model_index().contains_key("b"));
+        assert_eq!(registry.to_node_capabilities().models.len(), 1);
+
+        // After the cooldown the model is offered traffic again...
+        registry
+            .get_mut("vllm")
+            .unwrap()
+            .model_health
+            .get_mut("a")
+            .unwrap()
+            .unhealthy_since = Some(Utc::now() - chrono::Duration::seconds(120));
+        registry.rebuild_model_index();
+        assert!(registry.lookup_hashed_model(&slug_a).is_some());
+
+        // ...and a successful request restores it fully.
+        registry.record_model_success("vllm", "a");
+        let health = registry.get("vllm").unwrap().model_health("a").unwrap();
+        assert!(health.is_healthy());
+        assert_eq!(health.consecutive_failures, 0);
+        assert!(health.last_served.is_some());
+    }
+
+    #[tokio::test]
+    async fn probe_failure_marks_only_the_probed_model_unhealthy() {
+        let dir = std::env::temp_dir();
+        let ok_path = dir.join(format!("labman-probe-ok-{}.sock", std::process::id()));
+        let broken_path = dir.join(format!("labman-probe-broken-{}.sock", std::process::id()));
+        for path in [&ok_path, &broken_path] {
+            let _ = std::fs::remove_file(path);
+        }
+        tokio::spawn(serve_unix_routes(
+            tokio::net::UnixListener::bind(&ok_path).expect("bind unix socket"),
+            &[("/v1/chat/completions", r#"{"choices":[]}"#)],
+        ));
+        // No chat route: every probe gets a 404.
+        tokio::spawn(serve_unix_routes(
+            tokio::net::UnixListener::bind(&broken_path).expect("bind unix socket"),
+            &[],
+        ));
+
+        let probe = labman_config::ProbeConfig {
+            model: "a".to_string(),
+            interval_secs: 60,
+            timeout_secs: 5,
+        };
+        let mut cfg = minimal_config();
+        cfg.endpoints = [("ok", &ok_path), ("broken", &broken_path)]
+            .iter()
+            .map(|(name, path)| EndpointConfig {
+                name: name.to_string(),
+                base_url: format!("unix://{}:/v1", path.display()),
+                probe: Some(probe.clone()),
+                ..Default::default()
+            })
+            .collect();
+
+        let mut registry = EndpointRegistry::from_config(&cfg).expect("build registry");
+        for name in ["ok", "broken"] {
+            let entry = registry.get_mut(name).unwrap();
+            entry.healthy = true;
+            entry.discovered_models = vec![ModelDescriptor::new("a"), ModelDescriptor::new("b")];
+        }
+        // A previous failure on the healthy endpoint should be cleared.
+        registry
+            .get_mut("ok")
+            .unwrap()
+            .model_health
+            .entry("a".to_string())
+            .or_default()
+            .fail("HTTP 500".to_string(), 1);
+        registry.rebuild_model_index();
+        let registry = Arc::new(tokio::sync::Mutex::new(registry));
+
+        EndpointRegistry::probe_models_all_http(&registry).await;
+
+        let guard = registry.lock().await;
+        let ok = guard.get("ok").unwrap();
+        assert!(ok.is_model_healthy("a"));
+        assert!(ok.last_probe_latency().is_some());
+
+        let broken = guard.get("broken").unwrap();
+        assert!(!broken.is_model_healthy("a"));
+        assert!(broken.is_model_healthy("b"));
+        assert_eq!(guard.model_index()["a"], vec!["ok".to_string()]);
+        assert_eq!(guard.model_index()["b"].len(), 2);
+        drop(guard);
+
+        for path in [&ok_path, &broken_path] {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    #[tokio::test]
+    async fn periodic_probes_pick_up_probes_added_by_a_reload() {
+        let socket_path =
+            std::env::temp_dir().join(format!("labman-probe-reload-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&socket_path);
+        tokio::spawn(serve_unix_routes(
+            tokio::net::UnixListener::bind(&socket_path).expect("bind unix socket"),
+            &[("/v1/chat/completions", r#"{"choices":[]}"#)],
+        ));
+
+        let mut cfg = minimal_config();
+        cfg.endpoints = vec![EndpointConfig {
+            name: "ok".to_string(),
+            base_url: format!("unix://{}:/v1", socket_path.display()),
+            ..Default::default()
+        }];
+        let mut registry = EndpointRegistry::from_config(&cfg).expect("build registry");
+        let entry = registry.get_mut("ok").unwrap();
+        entry.healthy = true;
+        entry.discovered_models = vec![ModelDescriptor::new("a")];
+        registry.rebuild_model_index();
+        let registry = Arc::new(tokio::sync::Mutex::new(registry));
+
+        // Nothing to probe yet: the task must stay around for later reloads.
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+        EndpointRegistry::spawn_periodic_probes(registry.clone(), async {
+            let _ = stopped.await;
+        });
+        tokio::task::yield_now().await;
+
+        cfg.endpoints[0].probe = Some(labman_config::ProbeConfig {
+            model: "a".to_string(),
+            interval_secs: 60,
+            timeout_secs: 5,
+        });
+        registry
+            .lock()
+            .await
+            .apply_config(&cfg)
+            .expect("apply config");
+
+        let probed = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                if registry
+                    .lock()
+                    .await
+                    .get("ok")
+                    .unwrap()
+                    .last_probe_latency()
+                    .is_some()
+                {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        })
+        .await;
+        assert!(probed.is_ok(), "probe added by the reload never ran");
+
+        let _ = stop.send(());
+        let _ = std::fs::remove_file(&socket_path);
+    }
+
+    #[tokio::test]
+    async fn load_scrape_reads_vllm_metrics() {
+        let socket_path =
+            std::env::temp_dir().join(format!("labman-endpoints-load-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&socket_path);
+        let listener = tokio::net::UnixListener::bind(&socket_path).expect("bind unix socket");
+        tokio::spawn(serve_unix_routes(
+            listener,
+            &[(
+                "/metrics",
+                "vllm:num_requests_running{model_name=\"m\"} 2.0\nvllm:num_requests_waiting{model_name=\"m\"} 1.0\n",
+            )],
+        ));
+
+        let mut cfg = minimal_config();
+        cfg.endpoints = vec![
+            EndpointConfig {
+                name: "vllm".to_string(),
+                base_url: format!("unix://{}:/v1", socket_path.display()),
+                ..Default::default()
+            },
+            EndpointConfig {
+                name: "disabled".to_string(),
+                base_url: format!("unix://{}:/v1", socket_path.display()),
+                load_scrape_interval_secs: Some(0),
+                ..Default::default()
+            },
+        ];
+
+        let mut registry = EndpointRegistry::from_config(&cfg).expect("build registry");
+        for name in ["vllm", "disabled"] {
+            let entry = registry.get_mut(name).unwrap();
+            entry.healthy = true;
+            entry.endpoint.runtime = Some(RuntimeKind::Vllm);
+        }
+        let registry = Arc::new(tokio::sync::Mutex::new(registry));
+
+        EndpointRegistry::scrape_load_all_http(&registry).await;
+
+        let guard = registry.lock().await;
+        let load = guard
+            .get("vllm")
+            .unwrap()
+            .endpoint
+            .load
+            .clone()
+            .expect("load scraped");
+        assert_eq!(load.running_requests, Some(2));
+        assert_eq!(load.waiting_requests, Some(1));
+        assert!(guard.get("disabled").unwrap().endpoint.load.is_none());
+        drop(guard);
+
+        let _ = std::fs::remove_file(&socket_path);
+    }
 }
@@ -0,0 +1,228 @@
+//! Scraping runtime-reported load from vLLM and llama.cpp endpoints.
+//!
+//! labman's own request accounting only sees traffic that passes through the
+//! proxy, but GPUs are frequently shared with other clients. vLLM and
+//! llama.cpp both expose their internal queue state, which is a much better
+//! signal for routing:
+//!
+//! - vLLM: Prometheus `GET /metrics` with `vllm:num_requests_running`,
+//!   `vllm:num_requests_waiting` and `vllm:gpu_cache_usage_perc` (renamed to
+//!   `vllm:kv_cache_usage_perc` in newer releases).
+//! - llama.cpp: Prometheus `GET /metrics` (only with `--metrics`) with
+//!   `llamacpp:requests_processing`, `llamacpp:requests_deferred` and
+//!   `llamacpp:kv_cache_usage_ratio`; otherwise `GET /slots`, which lists
+//!   each slot and whether it is processing.
+//!
+//! Samples with several label sets (e.g. one per served model) are summed.
+
+use std::collections::HashMap;
+
+use chrono::Utc;
+use labman_core::{EndpointLoad, RuntimeKind};
+use serde_json::Value;
+
+use crate::{ollama, DiscoveryError};
+
+/// Whether load can be scraped from endpoints running `runtime`.
+pub(crate) fn supports_runtime(runtime: RuntimeKind) -> bool {
+    matches!(runtime, RuntimeKind::Vllm | RuntimeKind::LlamaCpp)
+}
+
+/// Scrape the current load of an endpoint.
+pub(crate) async fn scrape(
+    client: &reqwest::Client,
+    http_base_url: &str,
+    runtime: RuntimeKind,
+) -> Result<EndpointLoad, DiscoveryError> {
+    // Metrics and slots live at the server root, next to `/v1`.
+    let root = ollama::native_root(http_base_url);
+
+    match runtime {
+        RuntimeKind::Vllm => {
+            let text = get_text(client, &format!("{}/metrics", root)).await?;
+            Ok(vllm_load(&parse_samples(&text)))
+        }
+        RuntimeKind::LlamaCpp => {
+            match get_text(client, &format!("{}/metrics", root)).await {
+                Ok(text) => Ok(llama_cpp_metrics_load(&parse_samples(&text))),
+                // `/metrics` is opt-in on llama-server; fall back to `/slots`.
+                Err(DiscoveryError::Status(_)) => {
+                    let resp = client
+                        .get(format!("{}/slots", root))
+                        .send()
+                        .await
+                        .map_err(DiscoveryError::Request)?;
+                    if !resp.status().is_success() {
+                        return Err(DiscoveryError::Status(resp.status()));
+                    }
+                    let slots: Value = resp.json().await.map_err(DiscoveryError::Parse)?;
+                    Ok(llama_cpp_slots_load(&slots))
+                }
+                Err(e) => Err(e),
+            }
+        }
+        _ => Ok(empty_load()),
+    }
+}
+
+async fn get_text(client: &reqwest::Client, url: &str) -> Result<String, DiscoveryError> {
+    let resp = client
+        .get(url)
+        .send()
+        .await
+        .map_err(DiscoveryError::Request)?;
+    if !resp.status().is_success() {
+        return Err(DiscoveryError::Status(resp.status()));
+    }
+    resp.text().await.map_err(DiscoveryError::Parse)
+}
+
+/// Parse Prometheus text exposition format into per-metric sums.
+///
+/// Comments, malformed lines and non-finite values are skipped. Labels are
+/// dropped and values of the same metric name are added together.
+fn parse_samples(text: &str) -> HashMap<&str, f64> {
+    let mut samples = HashMap::new();
+
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (name, rest) = match line.find('{') {
+            Some(idx) => {
+                let Some(end) = line[idx..].find('}') else {
+                    continue;
+                };
+                (&line[..idx], &line[idx + end + 1..])
+            }
+            None => match line.split_once(char::is_whitespace) {
+                Some((name, rest)) => (name, rest),
+                None => continue,
+            },
+        };
+
+        // An optional timestamp may follow the value.
+        let Some(value) = rest
+            .split_whitespace()
+            .next()
+            .and_then(|v| v.parse::<f64>().ok())
+        else {
+            continue;
+        };
+        if !value.is_finite() {
+            continue;
+        }
+
+        *samples.entry(name).or_insert(0.0) += value;
+    }
+
+    samples
+}
+
+fn vllm_load(samples: &HashMap<&str, f64>) -> EndpointLoad {
+    EndpointLoad {
+        running_requests: samples.get("vllm:num_requests_running").map(|v| *v as u64),
+        waiting_requests: samples.get("vllm:num_requests_waiting").map(|v| *v as u64),
+        kv_cache_usage: samples
+            .get("vllm:kv_cache_usage_perc")
+            .or_else(|| samples.get("vllm:gpu_cache_usage_perc"))
+            .copied(),
+        ..empty_load()
+    }
+}
+
+fn llama_cpp_metrics_load(samples: &HashMap<&str, f64>) -> EndpointLoad {
+    EndpointLoad {
+        running_requests: samples
+            .get("llamacpp:requests_processing")
+            .map(|v| *v as u64),
+        waiting_requests: samples.get("llamacpp:requests_deferred").map(|v| *v as u64),
+        kv_cache_usage: samples.get("llamacpp:kv_cache_usage_ratio").copied(),
+        ..empty_load()
+    }
+}
+
+/// Derive load from llama.cpp's `GET /slots`.
+///
+/// Newer servers report `is_processing`; older ones use `state` (0 = idle).
+fn llama_cpp_slots_load(slots: &Value) -> EndpointLoad {
+    let Some(slots) = slots.as_array() else {
+        return empty_load();
+    };
+
+    let running = slots
+        .iter()
+        .filter(|slot| {
+            slot["is_processing"]
+                .as_bool()
+                .unwrap_or_else(|| slot["state"].as_u64().is_some_and(|s| s != 0))
+        })
+        .count();
+
+    EndpointLoad {
+        running_requests: Some(running as u64),
+        total_slots: Some(slots.len() as u64),
+        ..empty_load()
+    }
+}
+
+fn empty_load() -> EndpointLoad {
+    EndpointLoad {
+        running_requests: None,
+        waiting_requests: None,
+        kv_cache_usage: None,
+        total_slots: None,
+        scraped_at: Utc::now(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vllm_metrics_are_summed_across_models() {
+        let text = r#"
+# HELP vllm:num_requests_running Number of requests currently running on GPU.
+# TYPE vllm:num_requests_running gauge
+vllm:num_requests_running{model_name="a"} 2.0
+vllm:num_requests_running{model_name="b"} 1.0
+vllm:num_requests_waiting{model_name="a"} 4.0 1700000000000
+vllm:gpu_cache_usage_perc{model_name="a"} 0.5
+"#;
+        let load = vllm_load(&parse_samples(text));
+
+        assert_eq!(load.running_requests, Some(3));
+        assert_eq!(load.waiting_requests, Some(4));
+        assert_eq!(load.kv_cache_usage, Some(0.5));
+    }
+
+    #[test]
+    fn llama_cpp_metrics_and_slots() {
+        let text = "llamacpp:requests_processing 1\nllamacpp:requests_deferred 2\n\
+                    llamacpp:kv_cache_usage_ratio 0.125\n";
+        let load = llama_cpp_metrics_load(&parse_samples(text));
+        assert_eq!(load.running_requests, Some(1));
+        assert_eq!(load.waiting_requests, Some(2));
+        assert_eq!(load.kv_cache_usage, Some(0.125));
+
+        let slots: Value = serde_json::from_str(
+            r#"[{"id":0,"is_processing":true},{"id":1,"is_processing":false},
+                {"id":2,"state":1}]"#,
+        )
+        .unwrap();
+        let load = llama_cpp_slots_load(&slots);
+        assert_eq!(load.running_requests, Some(2));
+        assert_eq!(load.total_slots, Some(3));
+        assert_eq!(load.waiting_requests, None);
+    }
+
+    #[test]
+    fn parse_samples_skips_garbage() {
+        let samples = parse_samples("foo NaN\nbar{x=\"1\"\nbaz 1e3\n");
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples.get("baz"), Some(&1000.0));
+    }
+}
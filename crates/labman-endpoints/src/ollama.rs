@@ -0,0 +1,292 @@
+//! Native Ollama API support for model discovery.
+//!
+//! Ollama's OpenAI shim (`/v1/models`) only reports model IDs. The native API
+//! exposes much richer information:
+//!
+//! - `GET /api/tags` lists installed models with family, parameter size and
+//!   quantization level.
+//! - `POST /api/show` returns per-model details, including the architecture's
+//!   context length under `model_info["<arch>.context_length"]` and a
+//!   `capabilities` list (`vision`, `tools`, ...).
+//!
+//! Endpoints configured with `kind = "ollama"` are discovered through these
+//! routes instead of `/v1/models`. The resulting `ModelDescriptor`s still use
+//! the Ollama model name (e.g. `llama3.1:8b`) as their ID, so slug derivation
+//! and resolution are unchanged.
+//!
+//! Ollama endpoints configured as plain OpenAI are still detected by the
+//! fingerprinting pass, which uses [`enrich_models`] to add the `/api/show`
+//! details to the models found via `/v1/models`.
+
+use labman_core::{ModelCapability, ModelDescriptor};
+use serde::Deserialize;
+
+use crate::DiscoveryError;
+
+/// Response body of `GET /api/tags`.
+#[derive(Debug, Deserialize)]
+struct TagsResponse {
+    #[serde(default)]
+    models: Vec<TagModel>,
+}
+
+/// A single installed model as listed by `/api/tags`.
+#[derive(Debug, Deserialize)]
+struct TagModel {
+    name: String,
+    #[serde(default)]
+    details: Option<ModelDetails>,
+}
+
+/// The `details` object shared by `/api/tags` and `/api/show`.
+#[derive(Debug, Default, Deserialize)]
+struct ModelDetails {
+    #[serde(default)]
+    family: Option<String>,
+    #[serde(default)]
+    parameter_size: Option<String>,
+    #[serde(default)]
+    quantization_level: Option<String>,
+}
+
+/// Response body of `POST /api/show`; only the fields labman uses.
+#[derive(Debug, Deserialize)]
+struct ShowResponse {
+    #[serde(default)]
+    model_info: serde_json::Map<String, serde_json::Value>,
+    #[serde(default)]
+    capabilities: Vec<String>,
+    #[serde(default)]
+    details: Option<ModelDetails>,
+}
+
+/// Derive the root URL of Ollama's native API from an endpoint's HTTP base
+/// URL.
+///
+/// Operators usually configure the OpenAI shim (`http://host:11434/v1`); the
+/// native routes live one level up at `http://host:11434/api/...`.
+pub fn native_root(http_base_url: &str) -> String {
+    let base = http_base_url.trim_end_matches('/');
+    base.strip_suffix("/v1").unwrap_or(base).to_string()
+}
+
+/// Discover models from an Ollama endpoint via `/api/tags` and `/api/show`.
+///
+/// A failing `/api/show` call for an individual model is not fatal: the model
+/// is still reported, just without a context length or modalities.
+pub(crate) async fn discover_models(
+    client: &reqwest::Client,
+    http_base_url: &str,
+) -> Result<Vec<ModelDescriptor>, DiscoveryError> {
+    let root = native_root(http_base_url);
+
+    let resp = client
+        .get(format!("{}/api/tags", root))
+        .send()
+        .await
+        .map_err(DiscoveryError::Request)?;
+    if !resp.status().is_success() {
+        return Err(DiscoveryError::Status(resp.status()));
+    }
+    let tags: TagsResponse = resp.json().await.map_err(DiscoveryError::Parse)?;
+
+    let mut models: Vec<ModelDescriptor> = tags.models.iter().map(descriptor_from_tag).collect();
+    enrich_models(client, &root, &mut models).await;
+
+    Ok(models)
+}
+
+/// Add `/api/show` details to already discovered models.
+///
+/// `root` is the native API root as returned by [`native_root`]. Failures are
+/// logged and leave the affected model unchanged.
+pub(crate) async fn enrich_models(
+    client: &reqwest::Client,
+    root: &str,
+    models: &mut [ModelDescriptor],
+) {
+    for model in models.iter_mut() {
+        match show_model(client, root, &model.id).await {
+            Ok(show) => apply_show(model, &show),
+            Err(e) => {
+                tracing::debug!("ollama /api/show failed for model '{}': {}", model.id, e);
+            }
+        }
+    }
+}
+
+/// Build a descriptor from a `/api/tags` entry.
+fn descriptor_from_tag(tag: &TagModel) -> ModelDescriptor {
+    let mut model = ModelDescriptor::new(tag.name.clone());
+    if let Some(details) = &tag.details {
+        model.family = details.family.clone();
+        model.parameter_size = details.parameter_size.clone();
+        model.quantization = details.quantization_level.clone();
+    }
+    model
+}
+
+/// Query `/api/show` for a model.
+async fn show_model(
+    client: &reqwest::Client,
+    root: &str,
+    model: &str,
+) -> Result<ShowResponse, DiscoveryError> {
+    let resp = client
+        .post(format!("{}/api/show", root))
+        .json(&serde_json::json!({ "model": model }))
+        .send()
+        .await
+        .map_err(DiscoveryError::Request)?;
+    if !resp.status().is_success() {
+        return Err(DiscoveryError::Status(resp.status()));
+    }
+    resp.json().await.map_err(DiscoveryError::Parse)
+}
+
+/// Copy context length, modalities, capabilities and any missing details
+/// from an `/api/show` response onto a descriptor.
+fn apply_show(model: &mut ModelDescriptor, show: &ShowResponse) {
+    if let Some(len) = context_length_from_model_info(&show.model_info) {
+        model.context_length = Some(len);
+    }
+
+    if !show.capabilities.is_empty() {
+        let mut modalities = Vec::new();
+        if show.capabilities.iter().any(|c| c == "completion") {
+            modalities.push("text".to_string());
+        }
+        if show.capabilities.iter().any(|c| c == "vision") {
+            modalities.push("image".to_string());
+        }
+        model.modalities = modalities;
+
+        // Ollama's `format` parameter works with every completion model.
+        let capabilities = [
+            ("tools", ModelCapability::Tools),
+            ("vision", ModelCapability::Vision),
+            ("completion", ModelCapability::JsonMode),
+        ];
+        model.capabilities = Some(
+            capabilities
+                .iter()
+                .filter(|(name, _)| show.capabilities.iter().any(|c| c == name))
+                .map(|(_, capability)| *capability)
+                .collect(),
+        );
+    }
+
+    if let Some(details) = &show.details {
+        if model.family.is_none() {
+            model.family = details.family.clone();
+        }
+        if model.parameter_size.is_none() {
+            model.parameter_size = details.parameter_size.clone();
+        }
+        if model.quantization.is_none() {
+            model.quantization = details.quantization_level.clone();
+        }
+    }
+}
+
+/// Extract `<arch>.context_length` from an `/api/show` `model_info` object.
+///
+/// The architecture prefix is taken from `general.architecture` when present;
+/// otherwise any `*.context_length` key is accepted.
+fn context_length_from_model_info(
+    model_info: &serde_json::Map<String, serde_json::Value>,
+) -> Option<u64> {
+    if let Some(arch) = model_info
+        .get("general.architecture")
+        .and_then(|v| v.as_str())
+    {
+        if let Some(len) = model_info
+            .get(&format!("{}.context_length", arch))
+            .and_then(|v| v.as_u64())
+        {
+            return Some(len);
+        }
+    }
+
+    model_info
+        .iter()
+        .find(|(k, _)| k.ends_with(".context_length"))
+        .and_then(|(_, v)| v.as_u64())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn native_root_strips_v1_suffix() {
+        assert_eq!(
+            native_root("http://127.0.0.1:11434/v1"),
+            "http://127.0.0.1:11434"
+        );
+        assert_eq!(
+            native_root("http://127.0.0.1:11434/"),
+            "http://127.0.0.1:11434"
+        );
+        assert_eq!(native_root("http://localhost/v1"), "http://localhost");
+    }
+
+    #[test]
+    fn descriptor_from_tag_copies_details() {
+        let tags: TagsResponse = serde_json::from_str(
+            r#"{"models":[{"name":"llama3.1:8b","model":"llama3.1:8b","size":4920753328,
+                "details":{"format":"gguf","family":"llama","parameter_size":"8.0B",
+                "quantization_level":"Q4_K_M"}}]}"#,
+        )
+        .unwrap();
+
+        let model = descriptor_from_tag(&tags.models[0]);
+        assert_eq!(model.id, "llama3.1:8b");
+        assert_eq!(model.family.as_deref(), Some("llama"));
+        assert_eq!(model.parameter_size.as_deref(), Some("8.0B"));
+        assert_eq!(model.quantization.as_deref(), Some("Q4_K_M"));
+    }
+
+    #[test]
+    fn context_length_prefers_declared_architecture() {
+        let show: ShowResponse = serde_json::from_str(
+            r#"{"model_info":{"general.architecture":"llama",
+                "clip.context_length":77,"llama.context_length":131072}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            context_length_from_model_info(&show.model_info),
+            Some(131072)
+        );
+
+        let show: ShowResponse =
+            serde_json::from_str(r#"{"model_info":{"qwen2.context_length":32768}}"#).unwrap();
+        assert_eq!(
+            context_length_from_model_info(&show.model_info),
+            Some(32768)
+        );
+    }
+
+    #[test]
+    fn apply_show_sets_modalities_and_missing_details() {
+        let show: ShowResponse = serde_json::from_str(
+            r#"{"capabilities":["completion","vision"],
+                "details":{"family":"gemma3","parameter_size":"4.3B","quantization_level":"Q4_K_M"},
+                "model_info":{"general.architecture":"gemma3","gemma3.context_length":131072}}"#,
+        )
+        .unwrap();
+
+        let mut model = ModelDescriptor::new("gemma3:4b");
+        model.family = Some("gemma".to_string());
+        apply_show(&mut model, &show);
+
+        assert_eq!(model.context_length, Some(131072));
+        assert_eq!(model.modalities, vec!["text", "image"]);
+        assert_eq!(
+            model.capabilities,
+            Some(vec![ModelCapability::Vision, ModelCapability::JsonMode])
+        );
+        assert_eq!(model.family.as_deref(), Some("gemma"));
+        assert_eq!(model.quantization.as_deref(), Some("Q4_K_M"));
+    }
+}
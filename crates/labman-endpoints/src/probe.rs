@@ -0,0 +1,49 @@
+//! Synthetic inference probes.
+//!
+//! The regular health check only confirms that an endpoint's HTTP server
+//! answers; a runtime whose model failed to load, or crashed mid-way, often
+//! still does. A probe sends the smallest possible chat completion
+//! (`max_tokens: 1`) to a configured model and treats anything other than a
+//! 2xx response within the timeout as a failure of that model.
+
+use std::time::{Duration, Instant};
+
+use crate::DiscoveryError;
+
+/// Send a one-token chat completion to `model` and return how long it took.
+pub(crate) async fn run(
+    client: &reqwest::Client,
+    http_base_url: &str,
+    model: &str,
+    timeout: Duration,
+) -> Result<Duration, DiscoveryError> {
+    let url = if http_base_url.ends_with("/v1") {
+        format!("{}/chat/completions", http_base_url)
+    } else {
+        format!("{}/v1/chat/completions", http_base_url)
+    };
+
+    let body = serde_json::json!({
+        "model": model,
+        "messages": [{ "role": "user", "content": "ping" }],
+        "max_tokens": 1,
+        "stream": false,
+    });
+
+    let started = Instant::now();
+    let resp = client
+        .post(&url)
+        .timeout(timeout)
+        .json(&body)
+        .send()
+        .await
+        .map_err(DiscoveryError::Request)?;
+    if !resp.status().is_success() {
+        return Err(DiscoveryError::Status(resp.status()));
+    }
+
+    // Wait for the full body so the latency covers the actual generation.
+    resp.bytes().await.map_err(DiscoveryError::Request)?;
+
+    Ok(started.elapsed())
+}
@@ -0,0 +1,204 @@
+//! Endpoint availability windows.
+//!
+//! An endpoint with a `schedule` only serves the network inside its
+//! configured windows (e.g. a gaming PC overnight, an office machine on
+//! weekends). The registry re-evaluates schedules on every health check
+//! tick and moves each endpoint between three states:
+//!
+//! - `Open`: routed and reported like any other endpoint.
+//! - `Draining`: the window closes within `drain_secs`. The endpoint is
+//!   dropped from routing and capability reporting so that no new requests
+//!   arrive, while requests already in flight run to completion.
+//! - `Closed`: outside every window; treated like `Draining`.
+//!
+//! When a window opens again the endpoint's models reappear on the next
+//! index rebuild. Only the routable inventory is affected: health checks
+//! and discovery keep running so the endpoint is ready the moment its
+//! window opens.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, FixedOffset, Local, NaiveDateTime, Timelike, Utc};
+use labman_config::{ScheduleConfig, ScheduleTimezone, DEFAULT_SCHEDULE_DRAIN_SECS};
+use serde::{Deserialize, Serialize};
+
+/// Where an endpoint is within its schedule.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleState {
+    /// Inside a window (or no schedule configured).
+    #[default]
+    Open,
+
+    /// Inside a window that closes within the drain period.
+    Draining,
+
+    /// Outside every window.
+    Closed,
+}
+
+impl std::fmt::Display for ScheduleState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScheduleState::Open => write!(f, "open"),
+            ScheduleState::Draining => write!(f, "draining"),
+            ScheduleState::Closed => write!(f, "closed"),
+        }
+    }
+}
+
+/// A parsed endpoint schedule.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Schedule {
+    /// Fixed offset east of UTC in seconds, or `None` for the host's
+    /// timezone.
+    utc_offset_secs: Option<i32>,
+
+    drain: Duration,
+
+    windows: Vec<Window>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Window {
+    /// Days the window starts on, as days since Monday.
+    days: Vec<u32>,
+
+    /// Minutes after midnight.
+    start: u32,
+    end: u32,
+}
+
+impl Window {
+    fn contains(&self, day: u32, minute: u32) -> bool {
+        let starts_on = |d: u32| self.days.contains(&d);
+        if self.start < self.end {
+            starts_on(day) && (self.start..self.end).contains(&minute)
+        } else {
+            // Runs past midnight into the next day.
+            (starts_on(day) && minute >= self.start)
+                || (starts_on((day + 6) % 7) && minute < self.end)
+        }
+    }
+}
+
+impl Schedule {
+    /// Parse a `[endpoints.schedule]` table.
+    pub fn from_config(config: &ScheduleConfig) -> Result<Schedule, String> {
+        let utc_offset_secs = match config.parsed_timezone()? {
+            ScheduleTimezone::Utc => Some(0),
+            ScheduleTimezone::Local => None,
+            ScheduleTimezone::Offset(secs) => Some(secs),
+        };
+
+        let windows = config
+            .windows
+            .iter()
+            .map(|w| {
+                let (start, end) = w.parsed_times()?;
+                Ok(Window {
+                    days: w.parsed_days()?,
+                    start,
+                    end,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(Schedule {
+            utc_offset_secs,
+            drain: Duration::from_secs(config.drain_secs.unwrap_or(DEFAULT_SCHEDULE_DRAIN_SECS)),
+            windows,
+        })
+    }
+
+    /// State of the schedule at `now`.
+    pub fn state_at(&self, now: DateTime<Utc>) -> ScheduleState {
+        if !self.is_open_at(now) {
+            return ScheduleState::Closed;
+        }
+        let drain = chrono::Duration::from_std(self.drain).unwrap_or(chrono::Duration::zero());
+        if !self.is_open_at(now + drain) {
+            ScheduleState::Draining
+        } else {
+            ScheduleState::Open
+        }
+    }
+
+    fn is_open_at(&self, time: DateTime<Utc>) -> bool {
+        let local = self.local_time(time);
+        let day = local.weekday().num_days_from_monday();
+        let minute = local.hour() * 60 + local.minute();
+        self.windows.iter().any(|w| w.contains(day, minute))
+    }
+
+    fn local_time(&self, time: DateTime<Utc>) -> NaiveDateTime {
+        match self.utc_offset_secs.and_then(FixedOffset::east_opt) {
+            Some(offset) => time.with_timezone(&offset).naive_local(),
+            None => time.with_timezone(&Local).naive_local(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use labman_config::ScheduleWindowConfig;
+
+    fn schedule(timezone: &str, windows: &[(&[&str], &str, &str)]) -> Schedule {
+        Schedule::from_config(&ScheduleConfig {
+            timezone: Some(timezone.to_string()),
+            drain_secs: Some(600),
+            windows: windows
+                .iter()
+                .map(|(days, start, end)| ScheduleWindowConfig {
+                    days: Some(days.iter().map(|d| d.to_string()).collect()),
+                    start: Some(start.to_string()),
+                    end: Some(end.to_string()),
+                })
+                .collect(),
+        })
+        .unwrap()
+    }
+
+    /// 2026-06-05 is a Friday.
+    fn friday(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 6, 5, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn overnight_window_runs_into_the_next_day() {
+        let overnight = schedule("UTC", &[(&["fri"], "22:00", "07:00")]);
+
+        assert_eq!(overnight.state_at(friday(21, 59)), ScheduleState::Closed);
+        assert_eq!(overnight.state_at(friday(22, 0)), ScheduleState::Open);
+        // Friday morning belongs to Thursday, which has no window.
+        assert_eq!(overnight.state_at(friday(3, 0)), ScheduleState::Closed);
+
+        // Saturday morning is still inside Friday's window.
+        let saturday = friday(0, 0) + chrono::Duration::days(1);
+        assert_eq!(
+            overnight.state_at(saturday + chrono::Duration::hours(6)),
+            ScheduleState::Open
+        );
+        assert_eq!(
+            overnight.state_at(saturday + chrono::Duration::minutes(6 * 60 + 51)),
+            ScheduleState::Draining
+        );
+        assert_eq!(
+            overnight.state_at(saturday + chrono::Duration::hours(7)),
+            ScheduleState::Closed
+        );
+    }
+
+    #[test]
+    fn windows_use_the_configured_offset() {
+        let office = schedule("+02:00", &[(&["fri"], "09:00", "17:00")]);
+
+        // 07:00 UTC is 09:00 at +02:00.
+        assert_eq!(office.state_at(friday(6, 59)), ScheduleState::Closed);
+        assert_eq!(office.state_at(friday(7, 0)), ScheduleState::Open);
+        assert_eq!(office.state_at(friday(14, 55)), ScheduleState::Draining);
+        assert_eq!(office.state_at(friday(15, 0)), ScheduleState::Closed);
+    }
+}
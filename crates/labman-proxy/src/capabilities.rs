@@ -0,0 +1,170 @@
+//! Capability checks for chat completion requests.
+//!
+//! Some request features only work if the model behind a slug supports
+//! them: tool calling (`tools` / `functions`), image inputs (`image_url`
+//! content parts) and JSON mode (`response_format`). Upstreams handle
+//! unsupported features inconsistently (silently ignoring them, returning
+//! garbage or failing with a runtime-specific 500), so requests that need a
+//! capability the model is known to lack are rejected up front with an
+//! OpenAI-style `400`.
+//!
+//! A model's capabilities come from discovery (Ollama `/api/show`, llama.cpp
+//! `/props`) or from `capabilities` on a matching `[[models]]` entry. When
+//! nothing is known about a capability the request is forwarded unchanged.
+
+use labman_core::{ModelCapability, ModelDescriptor};
+use serde_json::{json, Value};
+
+use crate::ChatCompletionRequest;
+
+/// Content part types that carry an image.
+const IMAGE_PART_TYPES: &[&str] = &["image_url", "input_image", "image"];
+
+/// A request needing a capability the model does not have.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct UnsupportedCapability {
+    /// The missing capability.
+    pub capability: ModelCapability,
+
+    /// The request parameter that needs it.
+    pub param: &'static str,
+
+    /// Model ID the request was resolved to.
+    pub model_id: String,
+}
+
+impl UnsupportedCapability {
+    /// Human-readable explanation.
+    pub(crate) fn message(&self) -> String {
+        format!(
+            "model '{}' does not support {} (requested via '{}')",
+            self.model_id, self.capability, self.param
+        )
+    }
+
+    /// OpenAI-style error body returned with `400 Bad Request`.
+    pub(crate) fn to_json(&self) -> Value {
+        json!({
+            "error": {
+                "message": self.message(),
+                "type": "invalid_request_error",
+                "param": self.param,
+                "code": "unsupported_capability",
+            }
+        })
+    }
+}
+
+/// Capabilities `req` needs, each with the parameter that needs it.
+pub(crate) fn required(req: &ChatCompletionRequest) -> Vec<(ModelCapability, &'static str)> {
+    let mut required = Vec::new();
+    let non_empty = |key: &str| {
+        req.extra
+            .get(key)
+            .is_some_and(|v| v.as_array().is_some_and(|a| !a.is_empty()))
+    };
+
+    if non_empty("tools") {
+        required.push((ModelCapability::Tools, "tools"));
+    } else if non_empty("functions") {
+        required.push((ModelCapability::Tools, "functions"));
+    }
+
+    let has_image = req.messages.iter().any(|message| {
+        message.content.as_array().is_some_and(|parts| {
+            parts.iter().any(|part| {
+                part["type"]
+                    .as_str()
+                    .is_some_and(|t| IMAGE_PART_TYPES.contains(&t))
+            })
+        })
+    });
+    if has_image {
+        required.push((ModelCapability::Vision, "messages"));
+    }
+
+    let response_format = req.extra["response_format"]["type"].as_str();
+    if matches!(response_format, Some("json_object" | "json_schema")) {
+        required.push((ModelCapability::JsonMode, "response_format"));
+    }
+
+    required
+}
+
+/// Reject `req` if it needs a capability `model` is known to lack.
+pub(crate) fn check(
+    model: &ModelDescriptor,
+    req: &ChatCompletionRequest,
+) -> Result<(), UnsupportedCapability> {
+    for (capability, param) in required(req) {
+        if model.supports(capability) == Some(false) {
+            return Err(UnsupportedCapability {
+                capability,
+                param,
+                model_id: model.id.clone(),
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(body: Value) -> ChatCompletionRequest {
+        serde_json::from_value(body).unwrap()
+    }
+
+    #[test]
+    fn required_capabilities_are_detected() {
+        let req = request(json!({
+            "model": "slug",
+            "messages": [{"role": "user", "content": [
+                {"type": "text", "text": "what is this?"},
+                {"type": "image_url", "image_url": {"url": "data:image/png;base64,AAAA"}}
+            ]}],
+            "tools": [{"type": "function", "function": {"name": "f"}}],
+            "response_format": {"type": "json_object"}
+        }));
+        let caps: Vec<_> = required(&req).into_iter().map(|(c, _)| c).collect();
+        assert_eq!(
+            caps,
+            vec![
+                ModelCapability::Tools,
+                ModelCapability::Vision,
+                ModelCapability::JsonMode
+            ]
+        );
+
+        let plain = request(json!({
+            "model": "slug",
+            "messages": [{"role": "user", "content": "hi"}],
+            "tools": [],
+            "response_format": {"type": "text"}
+        }));
+        assert!(required(&plain).is_empty());
+    }
+
+    #[test]
+    fn only_known_missing_capabilities_are_rejected() {
+        let req = request(json!({
+            "model": "slug",
+            "messages": [],
+            "tools": [{"type": "function", "function": {"name": "f"}}]
+        }));
+
+        // Nothing known: forwarded.
+        let mut model = ModelDescriptor::new("llama3");
+        assert!(check(&model, &req).is_ok());
+
+        model.capabilities = Some(vec![ModelCapability::JsonMode]);
+        let err = check(&model, &req).unwrap_err();
+        assert_eq!(err.capability, ModelCapability::Tools);
+        assert_eq!(err.to_json()["error"]["code"], "unsupported_capability");
+        assert_eq!(err.to_json()["error"]["param"], "tools");
+
+        model.capabilities = Some(vec![ModelCapability::Tools]);
+        assert!(check(&model, &req).is_ok());
+    }
+}
@@ -6,6 +6,10 @@
 //!
 //! For now, this module provides a minimal HTTP server skeleton with a single
 //! `GET /v1/models` route backed by `EndpointRegistry::to_node_capabilities()`.
+//!
+//! When a catalog token is configured, `GET /labman/v1/catalog` additionally
+//! publishes the slug catalog (`labman_core::SlugCatalog`) to callers that
+//! present it as a bearer token.
 
 use std::net::SocketAddr;
 use std::sync::Arc;
@@ -13,15 +17,17 @@ use std::sync::Arc;
 use axum::extract::State;
 use axum::routing::{get, post};
 use axum::{Json, Router};
-use hyper_util::rt::TokioIo;
-use labman_core::ModelDescriptor;
-use labman_endpoints::EndpointRegistry;
+use labman_core::{ModelDescriptor, SlugCatalog};
+use labman_endpoints::{ConcurrencyPermit, EndpointRegistry, SlugResolution};
 use labman_telemetry::MetricsRecorder;
 use serde::{Deserialize, Serialize};
 use tokio::task::JoinHandle;
-use tracing;
 use tracing::{error, info};
 
+mod capabilities;
+mod ollama;
+mod policy;
+
 /// Error type for the proxy server.
 #[derive(Debug)]
 pub enum ProxyError {
@@ -41,12 +47,18 @@ impl std::error::Error for ProxyError {}
 
 /// Minimal representation of an OpenAI-style chat completion message.
 ///
-/// This is intentionally minimal and currently only used for deserializing and
-/// forwarding requests; additional fields can be added later as needed.
+/// `content` is kept as raw JSON since it may be a string, an array of
+/// content parts (text and images) or `null` for assistant tool calls. Any
+/// other fields (`tool_calls`, `tool_call_id`, `name`, ...) are captured in
+/// `extra` and forwarded unchanged.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatMessage {
     pub role: String,
-    pub content: String,
+    #[serde(default)]
+    pub content: serde_json::Value,
+
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 /// Minimal representation of an OpenAI-style chat completion request.
@@ -85,6 +97,11 @@ pub struct ProxyState {
 pub struct ProxyConfig {
     /// Address to bind the proxy on, typically the WireGuard IP + proxy port.
     pub listen_addr: SocketAddr,
+
+    /// Bearer token required for `GET /labman/v1/catalog`.
+    ///
+    /// The route is not served at all when this is `None`.
+    pub catalog_token: Option<String>,
 }
 
 /// Handle to a running proxy server.
@@ -134,10 +151,19 @@ impl ProxyServer {
 
     /// Build the Axum `Router` for this proxy.
     fn router(&self) -> Router {
-        Router::new()
+        let mut router = Router::new()
             .route("/v1/models", get(get_models))
-            .route("/v1/chat/completions", post(post_chat_completions))
-            .with_state(self.state.clone())
+            .route("/v1/chat/completions", post(post_chat_completions));
+
+        if let Some(token) = &self.cfg.catalog_token {
+            let token: Arc<str> = Arc::from(token.as_str());
+            router = router.route(
+                "/labman/v1/catalog",
+                get(move |state, headers| get_catalog(state, headers, token)),
+            );
+        }
+
+        router.with_state(self.state.clone())
     }
 
     /// Spawn the HTTP server on the current Tokio runtime and return a handle.
@@ -227,37 +253,105 @@ async fn get_models(State(state): State<ProxyState>) -> Json<ModelsResponse> {
     })
 }
 
+/// Handler for `GET /labman/v1/catalog`.
+///
+/// Returns the slug catalog (every slug this node accepts, with its tenant,
+/// endpoint, model, health and capacity hints) so the control plane can
+/// schedule by slug without reproducing the slug hashes. Callers must send
+/// `Authorization: Bearer <token>`; anything else gets `401 Unauthorized`.
+async fn get_catalog(
+    State(state): State<ProxyState>,
+    headers: axum::http::HeaderMap,
+    token: Arc<str>,
+) -> Result<Json<SlugCatalog>, axum::http::StatusCode> {
+    let presented = headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    if !presented.is_some_and(|p| constant_time_eq(p.as_bytes(), token.as_bytes())) {
+        state.metrics.record_error(None, "catalog_unauthorized");
+        return Err(axum::http::StatusCode::UNAUTHORIZED);
+    }
+
+    let catalog = state.registry.lock().await.slug_catalog();
+
+    state
+        .metrics
+        .record_request_end(Some("proxy"), Some("slug_catalog"), true, None);
+
+    Ok(Json(catalog))
+}
+
+/// Compare two byte strings without short-circuiting on the first mismatch.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Upstream endpoint and model resolved from an incoming model slug.
+struct UpstreamTarget {
+    endpoint_name: String,
+    base_url: String,
+    model_id: String,
+    upstream_model_id: String,
+    policy: Option<labman_config::RequestPolicyConfig>,
+    model: Option<ModelDescriptor>,
+    client: reqwest::Client,
+    ollama_native_chat: bool,
+    ollama_keep_alive: Option<String>,
+}
+
 /// Handler for `POST /v1/chat/completions`.
 ///
 /// This:
 /// - Parses the incoming request as `ChatCompletionRequest`.
 /// - Treats the incoming `model` field as an opaque, control‑plane provided
 ///   slug encoding `(tenant, endpoint_slug, model_id)`.
-/// - Uses `EndpointRegistry::lookup_hashed_model` to resolve the slug to a
-///   concrete endpoint and model.
+/// - Uses `EndpointRegistry::resolve_slug` to resolve the slug to a
+///   concrete endpoint and model. Unknown slugs get `404 Not Found`; slugs
+///   whose endpoint or model is temporarily down get `503 Service
+///   Unavailable` so the control plane can retry elsewhere.
+/// - Applies the endpoint/model request policy (see the `policy` module),
+///   answering violations with `400 Bad Request` and an OpenAI-style error.
+/// - Rejects requests needing tools, vision or JSON mode when the model is
+///   known not to support them (see the `capabilities` module), again with
+///   `400 Bad Request`.
+/// - Admits the request against the endpoint's `max_concurrent` and the
+///   model's `model_limits`, answering `429 Too Many Requests` when full.
 /// - Rewrites the upstream request so that the selected endpoint sees the
 ///   original model identifier it understands.
-/// - Proxies the request body to the selected endpoint's `/chat/completions`.
+/// - Proxies the request body to the selected endpoint's `/chat/completions`,
+///   or translates it to `/api/chat` for Ollama endpoints with native chat
+///   enabled.
 /// - Streams or buffers the response back to the caller, depending on `stream`.
 async fn post_chat_completions(
     State(state): State<ProxyState>,
-    axum::Json(req_body): axum::Json<ChatCompletionRequest>,
+    axum::Json(mut req_body): axum::Json<ChatCompletionRequest>,
 ) -> Result<axum::response::Response, axum::http::StatusCode> {
     // The incoming `model` field is an opaque slug chosen by the control
     // plane. Resolve it to a concrete endpoint/model pair using the registry's
     // slug index.
     let model_slug = req_body.model.clone();
 
-    let (endpoint_name, endpoint_base_url, real_model_id) = {
+    let target = {
         let registry = state.registry.lock().await;
-        match registry.lookup_hashed_model(&model_slug) {
-            Some(mapping) => {
+        match registry.resolve_slug(&model_slug) {
+            SlugResolution::Available(mapping) => {
                 if let Some(entry) = registry.get(&mapping.endpoint_name) {
-                    (
-                        mapping.endpoint_name.clone(),
-                        entry.endpoint.base_url.clone(),
-                        mapping.model_id.clone(),
-                    )
+                    UpstreamTarget {
+                        endpoint_name: mapping.endpoint_name.clone(),
+                        base_url: entry.endpoint.http_base_url(),
+                        model_id: mapping.model_id.clone(),
+                        upstream_model_id: mapping.upstream_model_id.clone(),
+                        policy: entry.meta.request_policy(&mapping.model_id),
+                        model: entry.model(&mapping.model_id).cloned(),
+                        client: entry.http_client().clone(),
+                        ollama_native_chat: entry.meta.ollama_native_chat(),
+                        ollama_keep_alive: entry
+                            .meta
+                            .ollama
+                            .as_ref()
+                            .and_then(|o| o.keep_alive.clone()),
+                    }
                 } else {
                     // Inconsistent registry state: mapping refers to a missing endpoint.
                     state
@@ -266,23 +360,97 @@ async fn post_chat_completions(
                     return Err(axum::http::StatusCode::BAD_REQUEST);
                 }
             }
-            None => {
+            SlugResolution::Unavailable(mapping) => {
+                // Known slug whose endpoint (or model) is currently down. This
+                // is retryable and must not look like a routing error.
+                state.metrics.record_error(
+                    Some(mapping.endpoint_name.as_str()),
+                    "hashed_model_unavailable",
+                );
+                return Err(axum::http::StatusCode::SERVICE_UNAVAILABLE);
+            }
+            SlugResolution::Unknown => {
                 // No mapping for this slug; treat as unknown model.
                 state.metrics.record_error(None, "hashed_model_not_found");
-                return Err(axum::http::StatusCode::BAD_REQUEST);
+                return Err(axum::http::StatusCode::NOT_FOUND);
             }
         }
     };
 
-    let base = endpoint_base_url.trim_end_matches('/');
-    let upstream_url = format!("{}/chat/completions", base);
+    if let Some(policy) = &target.policy {
+        if let Err(violation) = policy::apply(policy, &mut req_body) {
+            tracing::info!(
+                "rejecting request for model '{}' on endpoint '{}': {}",
+                target.model_id,
+                target.endpoint_name,
+                violation.message
+            );
+            state
+                .metrics
+                .record_error(Some(target.endpoint_name.as_str()), "policy_rejected");
+            return Ok(axum::response::IntoResponse::into_response((
+                axum::http::StatusCode::BAD_REQUEST,
+                Json(violation.to_json()),
+            )));
+        }
+    }
+
+    if let Some(model) = &target.model {
+        if let Err(unsupported) = capabilities::check(model, &req_body) {
+            tracing::info!(
+                "rejecting request on endpoint '{}': {}",
+                target.endpoint_name,
+                unsupported.message()
+            );
+            state
+                .metrics
+                .record_error(Some(target.endpoint_name.as_str()), "capability_rejected");
+            return Ok(axum::response::IntoResponse::into_response((
+                axum::http::StatusCode::BAD_REQUEST,
+                Json(unsupported.to_json()),
+            )));
+        }
+    }
+
+    // Hold a concurrency slot on the endpoint and model until the response
+    // has been fully sent.
+    let admitted = state
+        .registry
+        .lock()
+        .await
+        .try_admit(&target.endpoint_name, &target.model_id);
+    let permit = match admitted {
+        Ok(permit) => permit,
+        Err(err) => {
+            tracing::debug!("proxy: not admitting request: {}", err);
+            state
+                .metrics
+                .record_error(Some(target.endpoint_name.as_str()), err.metric_kind());
+            return Err(axum::http::StatusCode::TOO_MANY_REQUESTS);
+        }
+    };
+
+    if target.ollama_native_chat {
+        return forward_ollama_native_chat(&state, target, &model_slug, req_body, permit).await;
+    }
+
+    let UpstreamTarget {
+        endpoint_name,
+        base_url: endpoint_base_url,
+        model_id: real_model_id,
+        upstream_model_id,
+        client,
+        ..
+    } = target;
+
+    let upstream_url = format!("{}/chat/completions", endpoint_base_url);
 
-    // Forward the request to the selected upstream using reqwest. Rewrite the
-    // `model` field so that the upstream sees the concrete model identifier it
-    // expects rather than the opaque slug.
-    let client = reqwest::Client::new();
+    // Forward the request to the selected upstream using the endpoint's own
+    // client (which may be bound to a Unix socket). Rewrite the `model` field
+    // so that the upstream sees the concrete model identifier it expects
+    // rather than the opaque slug (or the operator's alias for it).
     let mut upstream_body = req_body;
-    upstream_body.model = real_model_id.clone();
+    upstream_body.model = upstream_model_id;
 
     let started = std::time::Instant::now();
     let upstream_resp = match client.post(&upstream_url).json(&upstream_body).send().await {
@@ -296,6 +464,8 @@ async fn post_chat_completions(
             state
                 .metrics
                 .record_error(Some(endpoint_name.as_str()), "upstream_request_error");
+            record_model_outcome(&state, &endpoint_name, &real_model_id, Err(err.to_string()))
+                .await;
             return Err(axum::http::StatusCode::BAD_GATEWAY);
         }
     };
@@ -308,7 +478,7 @@ async fn post_chat_completions(
 
     if is_streaming {
         // Streaming: pipe the bytes stream from upstream to the client.
-        let stream = upstream_resp.bytes_stream();
+        let stream = hold_until_finished(upstream_resp.bytes_stream(), permit);
         let body = axum::body::Body::from_stream(stream);
 
         let mut response = axum::response::Response::new(body);
@@ -330,6 +500,7 @@ async fn post_chat_completions(
             status.is_success(),
             Some(latency),
         );
+        record_model_outcome(&state, &endpoint_name, &real_model_id, Ok(status)).await;
 
         Ok(response)
     } else {
@@ -345,6 +516,8 @@ async fn post_chat_completions(
                 state
                     .metrics
                     .record_error(Some(endpoint_name.as_str()), "upstream_body_read_error");
+                record_model_outcome(&state, &endpoint_name, &real_model_id, Err(err.to_string()))
+                    .await;
                 return Err(axum::http::StatusCode::BAD_GATEWAY);
             }
         };
@@ -356,6 +529,7 @@ async fn post_chat_completions(
             status.is_success(),
             Some(latency),
         );
+        record_model_outcome(&state, &endpoint_name, &real_model_id, Ok(status)).await;
 
         let mut response = axum::response::Response::new(axum::body::Body::from(bytes));
         *response.status_mut() = status;
@@ -372,6 +546,188 @@ async fn post_chat_completions(
     }
 }
 
+/// Forward a chat completion to Ollama's native `/api/chat`.
+///
+/// The request is translated with [`ollama::chat_request`] and the response
+/// converted back into the OpenAI shape, as SSE when streaming. Requests the
+/// native API cannot express are answered with `400 Bad Request`, and
+/// upstream errors keep their status but get an OpenAI error body.
+async fn forward_ollama_native_chat(
+    state: &ProxyState,
+    target: UpstreamTarget,
+    model_slug: &str,
+    req_body: ChatCompletionRequest,
+    permit: ConcurrencyPermit,
+) -> Result<axum::response::Response, axum::http::StatusCode> {
+    let endpoint_name = target.endpoint_name.as_str();
+    let upstream_url = format!(
+        "{}/api/chat",
+        labman_endpoints::ollama::native_root(&target.base_url)
+    );
+    let upstream_body = match ollama::chat_request(
+        &req_body,
+        &target.upstream_model_id,
+        target.ollama_keep_alive.as_deref(),
+    ) {
+        Ok(body) => body,
+        Err(untranslatable) => {
+            tracing::info!(
+                "rejecting request on endpoint '{}': {}",
+                endpoint_name,
+                untranslatable.message
+            );
+            state
+                .metrics
+                .record_error(Some(endpoint_name), "native_chat_rejected");
+            return Ok(axum::response::IntoResponse::into_response((
+                axum::http::StatusCode::BAD_REQUEST,
+                Json(untranslatable.to_json()),
+            )));
+        }
+    };
+    let is_streaming = req_body.stream.unwrap_or(false);
+
+    let started = std::time::Instant::now();
+    let upstream_resp = match target
+        .client
+        .post(&upstream_url)
+        .json(&upstream_body)
+        .send()
+        .await
+    {
+        Ok(resp) => resp,
+        Err(err) => {
+            tracing::warn!(
+                "proxy: error forwarding native Ollama chat to endpoint '{}': {}",
+                endpoint_name,
+                err
+            );
+            state
+                .metrics
+                .record_error(Some(endpoint_name), "upstream_request_error");
+            record_model_outcome(state, endpoint_name, &target.model_id, Err(err.to_string()))
+                .await;
+            return Err(axum::http::StatusCode::BAD_GATEWAY);
+        }
+    };
+
+    let status = upstream_resp.status();
+
+    let mut response = if status.is_success() && is_streaming {
+        let stream = hold_until_finished(
+            ollama::sse_stream(upstream_resp.bytes_stream(), target.model_id.clone()),
+            permit,
+        );
+        let mut response = axum::response::Response::new(axum::body::Body::from_stream(stream));
+        response.headers_mut().insert(
+            axum::http::header::CONTENT_TYPE,
+            axum::http::HeaderValue::from_static("text/event-stream"),
+        );
+        response
+    } else {
+        let bytes = match upstream_resp.bytes().await {
+            Ok(b) => b,
+            Err(err) => {
+                tracing::warn!(
+                    "proxy: error reading native Ollama chat body from '{}': {}",
+                    endpoint_name,
+                    err
+                );
+                state
+                    .metrics
+                    .record_error(Some(endpoint_name), "upstream_body_read_error");
+                record_model_outcome(state, endpoint_name, &target.model_id, Err(err.to_string()))
+                    .await;
+                return Err(axum::http::StatusCode::BAD_GATEWAY);
+            }
+        };
+
+        let body = if status.is_success() {
+            match serde_json::from_slice::<serde_json::Value>(&bytes) {
+                Ok(json) => axum::body::Body::from(
+                    ollama::chat_response(&json, &target.model_id).to_string(),
+                ),
+                Err(err) => {
+                    tracing::warn!(
+                        "proxy: invalid native Ollama chat response from '{}': {}",
+                        endpoint_name,
+                        err
+                    );
+                    state
+                        .metrics
+                        .record_error(Some(endpoint_name), "upstream_invalid_response");
+                    record_model_outcome(
+                        state,
+                        endpoint_name,
+                        &target.model_id,
+                        Err(format!("invalid response: {}", err)),
+                    )
+                    .await;
+                    return Err(axum::http::StatusCode::BAD_GATEWAY);
+                }
+            }
+        } else {
+            axum::body::Body::from(ollama::error_response(status, &bytes).to_string())
+        };
+
+        let mut response = axum::response::Response::new(body);
+        response.headers_mut().insert(
+            axum::http::header::CONTENT_TYPE,
+            axum::http::HeaderValue::from_static("application/json"),
+        );
+        response
+    };
+
+    let latency = started.elapsed().as_secs_f64();
+    state.metrics.record_request_end(
+        Some(endpoint_name),
+        Some(model_slug),
+        status.is_success(),
+        Some(latency),
+    );
+    record_model_outcome(state, endpoint_name, &target.model_id, Ok(status)).await;
+
+    *response.status_mut() = status;
+    Ok(response)
+}
+
+/// Keep `permit` alive until `stream` has been consumed or dropped, so that
+/// streamed responses count against concurrency limits until they end.
+fn hold_until_finished<S: futures::Stream>(
+    stream: S,
+    permit: ConcurrencyPermit,
+) -> impl futures::Stream<Item = S::Item> {
+    use futures::StreamExt;
+
+    stream.map(move |item| {
+        let _held = &permit;
+        item
+    })
+}
+
+/// Feed the outcome of a proxied request into per-model health tracking.
+///
+/// `outcome` is the upstream status, or a description of a transport error.
+/// Only failures that point at the model count against it: 5xx responses,
+/// 404 (model not loaded / unknown to the runtime) and transport errors.
+/// Other client errors say nothing about the model and are ignored.
+async fn record_model_outcome(
+    state: &ProxyState,
+    endpoint_name: &str,
+    model_id: &str,
+    outcome: std::result::Result<axum::http::StatusCode, String>,
+) {
+    let mut registry = state.registry.lock().await;
+    match outcome {
+        Ok(status) if status.is_success() => registry.record_model_success(endpoint_name, model_id),
+        Ok(status) if status.is_server_error() || status == axum::http::StatusCode::NOT_FOUND => {
+            registry.record_model_failure(endpoint_name, model_id, format!("HTTP {}", status))
+        }
+        Ok(_) => {}
+        Err(error) => registry.record_model_failure(endpoint_name, model_id, error),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -393,9 +749,13 @@ mod tests {
             labman_config::LabmanConfig {
                 control_plane: ControlPlaneConfig {
                     base_url: "https://control.local/api/v1".to_string(),
-                    node_token: "test-token".to_string(),
+                    node_token: "test-token".into(),
+                    node_token_file: None,
                     region: None,
                     description: None,
+                    heartbeat_interval_secs: 30,
+                    state_file: None,
+                    slug_scheme: Default::default(),
                 },
                 wireguard: WireGuardConfig {
                     interface_name: "labman0".to_string(),
@@ -410,13 +770,21 @@ mod tests {
                     listen_port: 8080,
                     listen_addr: None,
                 },
+                portman: Default::default(),
                 telemetry: Some(TelemetryConfig {
                     log_level: Some("info".to_string()),
                     log_format: Some("text".to_string()),
+                    log_output: None,
+                    log_file: None,
+                    log_file_max_size_mb: 10,
+                    log_file_max_files: 5,
                     disable_metrics: false,
                     metrics_port: 9090,
+                    metrics_addr: None,
                 }),
+                health: Default::default(),
                 endpoints: Vec::new(),
+                models: Vec::new(),
             }
         }
     }
@@ -462,4 +830,326 @@ mod tests {
 
         assert_eq!(response.status(), axum::http::StatusCode::OK);
     }
+
+    #[tokio::test]
+    async fn catalog_route_requires_the_bearer_token() {
+        let server = ProxyServer::new(
+            super::ProxyConfig {
+                listen_addr: SocketAddr::from(([127, 0, 0, 1], 0)),
+                catalog_token: Some("secret".to_string()),
+            },
+            empty_registry(),
+            Arc::new(NoopMetrics),
+        );
+
+        let get_catalog = |auth: Option<&str>| {
+            let mut req = Request::builder().uri("/labman/v1/catalog");
+            if let Some(auth) = auth {
+                req = req.header("authorization", auth);
+            }
+            server
+                .router()
+                .oneshot(req.body(axum::body::Body::empty()).unwrap())
+        };
+
+        for auth in [None, Some("Bearer wrong"), Some("secret")] {
+            let response = get_catalog(auth).await.unwrap();
+            assert_eq!(response.status(), axum::http::StatusCode::UNAUTHORIZED);
+        }
+
+        let response = get_catalog(Some("Bearer secret")).await.unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let catalog: SlugCatalog = serde_json::from_slice(&body).unwrap();
+        assert!(catalog.entries.is_empty());
+
+        // Without a token the route does not exist.
+        let server = ProxyServer::new(
+            super::ProxyConfig {
+                listen_addr: SocketAddr::from(([127, 0, 0, 1], 0)),
+                catalog_token: None,
+            },
+            empty_registry(),
+            Arc::new(NoopMetrics),
+        );
+        let response = server
+            .router()
+            .oneshot(
+                Request::builder()
+                    .uri("/labman/v1/catalog")
+                    .header("authorization", "Bearer secret")
+                    .body(axum::body::Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::NOT_FOUND);
+    }
+
+    async fn post_chat(state: ProxyState, model: &str) -> axum::http::StatusCode {
+        let app = Router::new()
+            .route("/v1/chat/completions", post(post_chat_completions))
+            .with_state(state);
+
+        app.oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/v1/chat/completions")
+                .header("content-type", "application/json")
+                .body(axum::body::Body::from(
+                    serde_json::json!({"model": model, "messages": []}).to_string(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+        .status()
+    }
+
+    #[tokio::test]
+    async fn chat_completions_distinguishes_unknown_and_unavailable_slugs() {
+        // A throwaway upstream that serves the model list and fails its
+        // health check once `down` is set.
+        let down = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let upstream = Router::new()
+            .route(
+                "/v1",
+                get({
+                    let down = down.clone();
+                    move || async move {
+                        if down.load(std::sync::atomic::Ordering::SeqCst) {
+                            axum::http::StatusCode::SERVICE_UNAVAILABLE
+                        } else {
+                            axum::http::StatusCode::OK
+                        }
+                    }
+                }),
+            )
+            .route(
+                "/v1/models",
+                get(|| async {
+                    Json(serde_json::json!({"object": "list", "data": [{"id": "m"}]}))
+                }),
+            );
+        tokio::spawn(async move {
+            axum::serve(listener, upstream).await.unwrap();
+        });
+
+        let mut cfg = LabmanConfigBuilder::empty();
+        cfg.endpoints = vec![labman_config::EndpointConfig {
+            name: "ep".to_string(),
+            base_url: format!("http://{}/v1", addr),
+            ..Default::default()
+        }];
+        let state = ProxyState {
+            registry: Arc::new(tokio::sync::Mutex::new(
+                EndpointRegistry::from_config(&cfg).unwrap(),
+            )),
+            metrics: Arc::new(NoopMetrics),
+        };
+        EndpointRegistry::health_check_all_http(&state.registry)
+            .await
+            .unwrap();
+        EndpointRegistry::discover_models_all_http(&state.registry)
+            .await
+            .unwrap();
+        let slug = labman_core::slug::encode_model_slug("", &format!("{}/v1", addr), "m");
+        assert!(state
+            .registry
+            .lock()
+            .await
+            .lookup_hashed_model(&slug)
+            .is_some());
+
+        assert_eq!(
+            post_chat(state.clone(), "unknown-slug").await,
+            axum::http::StatusCode::NOT_FOUND
+        );
+
+        // Take the upstream down and let the registry notice.
+        down.store(true, std::sync::atomic::Ordering::SeqCst);
+        EndpointRegistry::health_check_all_http(&state.registry)
+            .await
+            .unwrap();
+        EndpointRegistry::discover_models_all_http(&state.registry)
+            .await
+            .unwrap();
+        assert!(state
+            .registry
+            .lock()
+            .await
+            .lookup_hashed_model(&slug)
+            .is_none());
+        assert_eq!(
+            post_chat(state, &slug).await,
+            axum::http::StatusCode::SERVICE_UNAVAILABLE
+        );
+    }
+
+    #[tokio::test]
+    async fn native_chat_applies_policy_token_cap_and_wraps_errors() {
+        // A throwaway Ollama that records the `/api/chat` body it receives
+        // and fails requests whose message says "fail".
+        let seen = Arc::new(std::sync::Mutex::new(None::<serde_json::Value>));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let upstream = Router::new()
+            .route("/v1", get(|| async { axum::http::StatusCode::OK }))
+            .route(
+                "/api/tags",
+                get(|| async { Json(serde_json::json!({"models": [{"name": "m"}]})) }),
+            )
+            .route(
+                "/api/chat",
+                post({
+                    let seen = seen.clone();
+                    move |Json(body): Json<serde_json::Value>| async move {
+                        let fail = body["messages"][0]["content"] == "fail";
+                        *seen.lock().unwrap() = Some(body);
+                        if fail {
+                            return (
+                                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                                Json(serde_json::json!({"error": "boom"})),
+                            );
+                        }
+                        (
+                            axum::http::StatusCode::OK,
+                            Json(serde_json::json!({
+                                "model": "m",
+                                "message": {"role": "assistant", "content": "ok"},
+                                "done": true,
+                                "done_reason": "stop",
+                            })),
+                        )
+                    }
+                }),
+            );
+        tokio::spawn(async move {
+            axum::serve(listener, upstream).await.unwrap();
+        });
+
+        let mut cfg = LabmanConfigBuilder::empty();
+        cfg.endpoints = vec![labman_config::EndpointConfig {
+            name: "ollama".to_string(),
+            base_url: format!("http://{}/v1", addr),
+            kind: labman_config::EndpointKind::Ollama,
+            ollama: Some(labman_config::OllamaConfig {
+                native_chat: true,
+                keep_alive: None,
+            }),
+            policy: Some(labman_config::RequestPolicyConfig {
+                max_tokens: Some(256),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }];
+        let state = ProxyState {
+            registry: Arc::new(tokio::sync::Mutex::new(
+                EndpointRegistry::from_config(&cfg).unwrap(),
+            )),
+            metrics: Arc::new(NoopMetrics),
+        };
+        EndpointRegistry::health_check_all_http(&state.registry)
+            .await
+            .unwrap();
+        EndpointRegistry::discover_models_all_http(&state.registry)
+            .await
+            .unwrap();
+        let slug = labman_core::slug::encode_model_slug("", &format!("{}/v1", addr), "m");
+
+        let chat = |content: &str| {
+            Router::new()
+                .route("/v1/chat/completions", post(post_chat_completions))
+                .with_state(state.clone())
+                .oneshot(
+                    Request::builder()
+                        .method("POST")
+                        .uri("/v1/chat/completions")
+                        .header("content-type", "application/json")
+                        .body(axum::body::Body::from(
+                            serde_json::json!({
+                                "model": slug,
+                                "messages": [{"role": "user", "content": content}],
+                                "max_completion_tokens": 4096,
+                            })
+                            .to_string(),
+                        ))
+                        .unwrap(),
+                )
+        };
+
+        let response = chat("hi").await.unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::OK);
+        let upstream_body = seen.lock().unwrap().take().expect("request reached Ollama");
+        assert_eq!(upstream_body["options"]["num_predict"], 256);
+
+        let response = chat("fail").await.unwrap();
+        assert_eq!(
+            response.status(),
+            axum::http::StatusCode::INTERNAL_SERVER_ERROR
+        );
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let error: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(error["error"]["message"], "boom");
+        assert_eq!(error["error"]["type"], "server_error");
+    }
+
+    #[tokio::test]
+    async fn record_model_outcome_only_counts_model_failures() {
+        let mut cfg = LabmanConfigBuilder::empty();
+        cfg.endpoints = vec![labman_config::EndpointConfig {
+            name: "vllm".to_string(),
+            base_url: "http://127.0.0.1:8000/v1".to_string(),
+            ..Default::default()
+        }];
+        let state = ProxyState {
+            registry: Arc::new(tokio::sync::Mutex::new(
+                EndpointRegistry::from_config(&cfg).unwrap(),
+            )),
+            metrics: Arc::new(NoopMetrics),
+        };
+
+        // Client errors say nothing about the model.
+        for _ in 0..5 {
+            record_model_outcome(&state, "vllm", "m", Ok(axum::http::StatusCode::BAD_REQUEST))
+                .await;
+        }
+        assert!(state
+            .registry
+            .lock()
+            .await
+            .get("vllm")
+            .unwrap()
+            .is_model_healthy("m"));
+
+        record_model_outcome(&state, "vllm", "m", Err("connection reset".to_string())).await;
+        for _ in 1..labman_endpoints::MODEL_FAILURE_THRESHOLD {
+            record_model_outcome(
+                &state,
+                "vllm",
+                "m",
+                Ok(axum::http::StatusCode::SERVICE_UNAVAILABLE),
+            )
+            .await;
+        }
+        assert!(!state
+            .registry
+            .lock()
+            .await
+            .get("vllm")
+            .unwrap()
+            .is_model_healthy("m"));
+
+        record_model_outcome(&state, "vllm", "m", Ok(axum::http::StatusCode::OK)).await;
+        let registry = state.registry.lock().await;
+        let health = registry.get("vllm").unwrap().model_health("m").unwrap();
+        assert!(health.is_healthy());
+        assert!(health.last_served.is_some());
+    }
 }
@@ -0,0 +1,671 @@
+//! Translation between OpenAI chat completions and Ollama's native `/api/chat`.
+//!
+//! Used for endpoints configured with `kind = "ollama"` and
+//! `ollama.native_chat = true`. The control plane keeps speaking the OpenAI
+//! API (including slug resolution); only the hop from labman to the upstream
+//! uses the native protocol, which gives access to `keep_alive` and options
+//! the OpenAI shim doesn't support.
+//!
+//! Differences handled here:
+//! - Sampling parameters move into an `options` object. `max_tokens` and
+//!   `max_completion_tokens` become `num_predict` (the latter wins when both
+//!   are set), and a single `stop` string becomes a one-element array.
+//! - Content-part arrays are flattened: text parts are joined into
+//!   `content` and base64 `data:` image parts move into the message's
+//!   `images`. Requests with parts the native API cannot express (remote
+//!   image URLs, audio, ...) are rejected with `400 Bad Request`.
+//! - `tools` is forwarded as-is; tool call arguments are JSON strings in the
+//!   OpenAI API and objects in the native one, and are converted both ways.
+//! - Non-streaming responses are a single JSON object with `message` and
+//!   token counts, converted into a `chat.completion` object.
+//! - Streaming responses are newline-delimited JSON rather than SSE; each
+//!   line is converted into a `chat.completion.chunk` SSE event, followed by
+//!   the usual `data: [DONE]` terminator.
+//! - Error responses (`{"error": "..."}`) are wrapped in the OpenAI error
+//!   object.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use axum::body::Bytes;
+use axum::http::StatusCode;
+use futures::{Stream, StreamExt};
+use serde_json::{json, Map, Value};
+
+use crate::{ChatCompletionRequest, ChatMessage};
+
+/// OpenAI request parameters that map onto Ollama `options` keys.
+const OPTION_KEYS: &[(&str, &str)] = &[
+    ("temperature", "temperature"),
+    ("top_p", "top_p"),
+    ("seed", "seed"),
+    ("stop", "stop"),
+    ("presence_penalty", "presence_penalty"),
+    ("frequency_penalty", "frequency_penalty"),
+];
+
+/// OpenAI output token limits that map onto `num_predict`, in order of
+/// precedence.
+const TOKEN_LIMIT_KEYS: &[&str] = &["max_completion_tokens", "max_tokens"];
+
+/// A request the native `/api/chat` API cannot express.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Untranslatable {
+    /// The offending request parameter, e.g. `messages[0].content[1]`.
+    pub param: String,
+
+    /// Human-readable explanation.
+    pub message: String,
+}
+
+impl Untranslatable {
+    fn new(param: String, message: impl Into<String>) -> Self {
+        Self {
+            param,
+            message: message.into(),
+        }
+    }
+
+    /// OpenAI-style error body returned with `400 Bad Request`.
+    pub(crate) fn to_json(&self) -> Value {
+        json!({
+            "error": {
+                "message": self.message,
+                "type": "invalid_request_error",
+                "param": self.param,
+                "code": "unsupported_by_endpoint",
+            }
+        })
+    }
+}
+
+/// Build an `/api/chat` request body from an OpenAI chat completion request.
+pub(crate) fn chat_request(
+    req: &ChatCompletionRequest,
+    model_id: &str,
+    keep_alive: Option<&str>,
+) -> Result<Value, Untranslatable> {
+    let mut options = Map::new();
+    if let Some(extra) = req.extra.as_object() {
+        for (openai_key, ollama_key) in OPTION_KEYS {
+            if let Some(value) = extra.get(*openai_key) {
+                if !value.is_null() {
+                    options.insert((*ollama_key).to_string(), value.clone());
+                }
+            }
+        }
+        if let Some(limit) = TOKEN_LIMIT_KEYS
+            .iter()
+            .filter_map(|key| extra.get(*key))
+            .find(|value| !value.is_null())
+        {
+            options.insert("num_predict".to_string(), limit.clone());
+        }
+        // OpenAI accepts a single stop sequence; Ollama wants a list.
+        if let Some(Value::String(stop)) = options.get("stop").cloned() {
+            options.insert("stop".to_string(), json!([stop]));
+        }
+    }
+
+    let messages = req
+        .messages
+        .iter()
+        .enumerate()
+        .map(|(i, message)| chat_message(i, message))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut body = json!({
+        "model": model_id,
+        "messages": messages,
+        "stream": req.stream.unwrap_or(false),
+    });
+
+    if !options.is_empty() {
+        body["options"] = Value::Object(options);
+    }
+    if let Some(keep_alive) = keep_alive {
+        body["keep_alive"] = json!(keep_alive);
+    }
+    if let Some(tools) = req.extra.get("tools").filter(|t| !t.is_null()) {
+        body["tools"] = tools.clone();
+    }
+    if let Some(format) = req
+        .extra
+        .get("response_format")
+        .and_then(|f| f.get("type"))
+        .and_then(|t| t.as_str())
+    {
+        if format == "json_object" {
+            body["format"] = json!("json");
+        }
+    }
+
+    Ok(body)
+}
+
+/// Translate one OpenAI chat message into a native one.
+fn chat_message(index: usize, message: &ChatMessage) -> Result<Value, Untranslatable> {
+    let mut out = message.extra.clone();
+    out.insert("role".to_string(), json!(message.role));
+
+    let mut images = Vec::new();
+    let content =
+        match &message.content {
+            Value::Null => String::new(),
+            Value::String(text) => text.clone(),
+            Value::Array(parts) => {
+                let mut texts = Vec::new();
+                for (j, part) in parts.iter().enumerate() {
+                    let param = || format!("messages[{}].content[{}]", index, j);
+                    match part["type"].as_str() {
+                        Some("text") => texts.push(part["text"].as_str().unwrap_or_default()),
+                        Some("image_url") => {
+                            let url = part["image_url"]["url"]
+                                .as_str()
+                                .or_else(|| part["image_url"].as_str())
+                                .unwrap_or_default();
+                            match base64_data_url(url) {
+                                Some(data) => images.push(json!(data)),
+                                None => return Err(Untranslatable::new(
+                                    param(),
+                                    "only base64 data: image URLs are supported by this endpoint",
+                                )),
+                            }
+                        }
+                        other => {
+                            return Err(Untranslatable::new(
+                                param(),
+                                format!(
+                                    "content part type '{}' is not supported by this endpoint",
+                                    other.unwrap_or("")
+                                ),
+                            ))
+                        }
+                    }
+                }
+                texts.join("\n")
+            }
+            _ => {
+                return Err(Untranslatable::new(
+                    format!("messages[{}].content", index),
+                    "content must be a string or an array of content parts",
+                ))
+            }
+        };
+    out.insert("content".to_string(), json!(content));
+    if !images.is_empty() {
+        out.insert("images".to_string(), Value::Array(images));
+    }
+
+    if let Some(calls) = message.extra.get("tool_calls").and_then(Value::as_array) {
+        let calls = calls
+            .iter()
+            .map(|call| {
+                let arguments = match &call["function"]["arguments"] {
+                    Value::String(args) => serde_json::from_str(args).map_err(|_| {
+                        Untranslatable::new(
+                            format!("messages[{}].tool_calls", index),
+                            "tool call arguments must be a JSON object",
+                        )
+                    })?,
+                    args => args.clone(),
+                };
+                Ok(json!({
+                    "function": {
+                        "name": call["function"]["name"],
+                        "arguments": arguments,
+                    }
+                }))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        out.insert("tool_calls".to_string(), Value::Array(calls));
+    }
+
+    Ok(Value::Object(out))
+}
+
+/// The payload of a `data:<mime>;base64,<payload>` URL.
+fn base64_data_url(url: &str) -> Option<&str> {
+    let (header, data) = url.strip_prefix("data:")?.split_once(',')?;
+    header.ends_with(";base64").then_some(data)
+}
+
+/// Native tool calls in OpenAI form: with ids and JSON-string arguments.
+fn openai_tool_calls(message: &Value) -> Option<Vec<Value>> {
+    let calls = message["tool_calls"].as_array().filter(|c| !c.is_empty())?;
+    Some(
+        calls
+            .iter()
+            .enumerate()
+            .map(|(i, call)| {
+                let arguments = match &call["function"]["arguments"] {
+                    Value::String(args) => args.clone(),
+                    args => args.to_string(),
+                };
+                json!({
+                    "index": i,
+                    "id": call["id"].as_str().map_or_else(|| format!("call_{}", i), str::to_string),
+                    "type": "function",
+                    "function": {
+                        "name": call["function"]["name"],
+                        "arguments": arguments,
+                    },
+                })
+            })
+            .collect(),
+    )
+}
+
+/// Wrap a non-2xx `/api/chat` response body in an OpenAI error object.
+///
+/// Ollama reports errors as `{"error": "..."}`; any other body is used as
+/// the message as-is.
+pub(crate) fn error_response(status: StatusCode, body: &[u8]) -> Value {
+    let message = serde_json::from_slice::<Value>(body)
+        .ok()
+        .and_then(|json| json.get("error")?.as_str().map(str::to_string))
+        .unwrap_or_else(|| String::from_utf8_lossy(body).trim().to_string());
+    let message = if message.is_empty() {
+        status
+            .canonical_reason()
+            .unwrap_or("upstream error")
+            .to_string()
+    } else {
+        message
+    };
+    let error_type = if status.is_client_error() {
+        "invalid_request_error"
+    } else {
+        "server_error"
+    };
+
+    json!({
+        "error": {
+            "message": message,
+            "type": error_type,
+            "param": null,
+            "code": "upstream_error",
+        }
+    })
+}
+
+/// Convert a non-streaming `/api/chat` response into a `chat.completion`.
+pub(crate) fn chat_response(body: &Value, model: &str) -> Value {
+    let prompt_tokens = body["prompt_eval_count"].as_u64().unwrap_or(0);
+    let completion_tokens = body["eval_count"].as_u64().unwrap_or(0);
+
+    let mut message = json!({
+        "role": body["message"]["role"].as_str().unwrap_or("assistant"),
+        "content": body["message"]["content"].as_str().unwrap_or(""),
+    });
+    let mut finish_reason = finish_reason(body);
+    if let Some(calls) = openai_tool_calls(&body["message"]) {
+        message["tool_calls"] = Value::Array(calls);
+        finish_reason = "tool_calls";
+    }
+
+    json!({
+        "id": completion_id(),
+        "object": "chat.completion",
+        "created": unix_now(),
+        "model": model,
+        "choices": [{
+            "index": 0,
+            "message": message,
+            "finish_reason": finish_reason,
+        }],
+        "usage": {
+            "prompt_tokens": prompt_tokens,
+            "completion_tokens": completion_tokens,
+            "total_tokens": prompt_tokens + completion_tokens,
+        },
+    })
+}
+
+/// Convert one NDJSON line of a streaming `/api/chat` response into a
+/// `chat.completion.chunk` object.
+///
+/// Returns `None` for blank or unparseable lines. `tool_calls` records
+/// whether an earlier chunk carried tool calls, so the final chunk can report
+/// `finish_reason: "tool_calls"`.
+fn chat_chunk(
+    line: &[u8],
+    id: &str,
+    created: u64,
+    model: &str,
+    tool_calls: &mut bool,
+) -> Option<Value> {
+    let line = std::str::from_utf8(line).ok()?.trim();
+    if line.is_empty() {
+        return None;
+    }
+    let body: Value = serde_json::from_str(line).ok()?;
+
+    let done = body["done"].as_bool().unwrap_or(false);
+    let delta = if done {
+        json!({})
+    } else {
+        let mut delta = json!({
+            "role": body["message"]["role"].as_str().unwrap_or("assistant"),
+            "content": body["message"]["content"].as_str().unwrap_or(""),
+        });
+        if let Some(calls) = openai_tool_calls(&body["message"]) {
+            delta["tool_calls"] = Value::Array(calls);
+            *tool_calls = true;
+        }
+        delta
+    };
+    let finish_reason = match (done, *tool_calls) {
+        (false, _) => Value::Null,
+        (true, true) => json!("tool_calls"),
+        (true, false) => json!(finish_reason(&body)),
+    };
+
+    let mut chunk = json!({
+        "id": id,
+        "object": "chat.completion.chunk",
+        "created": created,
+        "model": model,
+        "choices": [{
+            "index": 0,
+            "delta": delta,
+            "finish_reason": finish_reason,
+        }],
+    });
+
+    if done {
+        let prompt_tokens = body["prompt_eval_count"].as_u64().unwrap_or(0);
+        let completion_tokens = body["eval_count"].as_u64().unwrap_or(0);
+        chunk["usage"] = json!({
+            "prompt_tokens": prompt_tokens,
+            "completion_tokens": completion_tokens,
+            "total_tokens": prompt_tokens + completion_tokens,
+        });
+    }
+
+    Some(chunk)
+}
+
+/// Turn a streaming `/api/chat` body (NDJSON) into an OpenAI-style SSE stream.
+pub(crate) fn sse_stream<S>(
+    upstream: S,
+    model: String,
+) -> impl Stream<Item = Result<Bytes, std::io::Error>> + Send
+where
+    S: Stream<Item = reqwest::Result<Bytes>> + Send + Unpin + 'static,
+{
+    struct State<S> {
+        upstream: S,
+        buffer: Vec<u8>,
+        id: String,
+        created: u64,
+        model: String,
+        tool_calls: bool,
+        finished: bool,
+    }
+
+    let state = State {
+        upstream,
+        buffer: Vec::new(),
+        id: completion_id(),
+        created: unix_now(),
+        model,
+        tool_calls: false,
+        finished: false,
+    };
+
+    futures::stream::unfold(state, |mut st| async move {
+        loop {
+            if let Some(pos) = st.buffer.iter().position(|b| *b == b'\n') {
+                let line: Vec<u8> = st.buffer.drain(..=pos).collect();
+                if let Some(chunk) =
+                    chat_chunk(&line, &st.id, st.created, &st.model, &mut st.tool_calls)
+                {
+                    let event = format!("data: {}\n\n", chunk);
+                    return Some((Ok(Bytes::from(event)), st));
+                }
+                continue;
+            }
+
+            if st.finished {
+                return None;
+            }
+
+            match st.upstream.next().await {
+                Some(Ok(bytes)) => st.buffer.extend_from_slice(&bytes),
+                Some(Err(e)) => {
+                    st.finished = true;
+                    return Some((Err(std::io::Error::other(e)), st));
+                }
+                None => {
+                    // Flush a trailing line without a newline, then terminate
+                    // the SSE stream the way OpenAI clients expect.
+                    st.finished = true;
+                    let rest = std::mem::take(&mut st.buffer);
+                    let mut out = String::new();
+                    if let Some(chunk) =
+                        chat_chunk(&rest, &st.id, st.created, &st.model, &mut st.tool_calls)
+                    {
+                        out.push_str(&format!("data: {}\n\n", chunk));
+                    }
+                    out.push_str("data: [DONE]\n\n");
+                    return Some((Ok(Bytes::from(out)), st));
+                }
+            }
+        }
+    })
+}
+
+fn finish_reason(body: &Value) -> &str {
+    match body["done_reason"].as_str() {
+        Some("length") => "length",
+        _ => "stop",
+    }
+}
+
+fn completion_id() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    format!("chatcmpl-{:x}", nanos)
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(extra: Value, stream: bool) -> ChatCompletionRequest {
+        ChatCompletionRequest {
+            model: "slug".to_string(),
+            messages: vec![crate::ChatMessage {
+                role: "user".to_string(),
+                content: json!("hi"),
+                extra: Map::new(),
+            }],
+            stream: Some(stream),
+            extra,
+        }
+    }
+
+    #[test]
+    fn chat_request_maps_options_and_keep_alive() {
+        let req = request(
+            json!({"max_tokens": 16, "temperature": 0.2, "stop": "\n\n"}),
+            false,
+        );
+        let body = chat_request(&req, "llama3.1:8b", Some("10m")).unwrap();
+
+        assert_eq!(body["model"], "llama3.1:8b");
+        assert_eq!(body["stream"], false);
+        assert_eq!(body["keep_alive"], "10m");
+        assert_eq!(body["options"]["num_predict"], 16);
+        assert_eq!(body["options"]["temperature"], 0.2);
+        assert_eq!(body["options"]["stop"], json!(["\n\n"]));
+        assert_eq!(body["messages"][0]["content"], "hi");
+
+        // Lists of stop sequences are passed through.
+        let req = request(json!({"stop": ["a", "b"]}), false);
+        let body = chat_request(&req, "llama3.1:8b", None).unwrap();
+        assert_eq!(body["options"]["stop"], json!(["a", "b"]));
+    }
+
+    #[test]
+    fn chat_request_maps_max_completion_tokens_to_num_predict() {
+        let req = request(json!({"max_completion_tokens": 64}), false);
+        let body = chat_request(&req, "llama3.1:8b", None).unwrap();
+        assert_eq!(body["options"]["num_predict"], 64);
+
+        let req = request(
+            json!({"max_tokens": 512, "max_completion_tokens": 64}),
+            false,
+        );
+        let body = chat_request(&req, "llama3.1:8b", None).unwrap();
+        assert_eq!(body["options"]["num_predict"], 64);
+    }
+
+    #[test]
+    fn error_response_wraps_ollama_errors() {
+        let err = error_response(
+            StatusCode::NOT_FOUND,
+            br#"{"error":"model 'llama3.1:8b' not found"}"#,
+        );
+        assert_eq!(err["error"]["message"], "model 'llama3.1:8b' not found");
+        assert_eq!(err["error"]["type"], "invalid_request_error");
+
+        let err = error_response(StatusCode::INTERNAL_SERVER_ERROR, b"");
+        assert_eq!(err["error"]["message"], "Internal Server Error");
+        assert_eq!(err["error"]["type"], "server_error");
+    }
+
+    #[test]
+    fn chat_request_flattens_content_parts_and_forwards_tools() {
+        let tools = json!([{
+            "type": "function",
+            "function": {"name": "get_weather", "parameters": {"type": "object"}}
+        }]);
+        let mut req = request(json!({ "tools": tools }), false);
+        req.messages = vec![
+            crate::ChatMessage {
+                role: "user".to_string(),
+                content: json!([
+                    {"type": "text", "text": "What is in this picture?"},
+                    {"type": "image_url", "image_url": {"url": "data:image/png;base64,iVBORw0KGgo="}},
+                    {"type": "text", "text": "And where?"}
+                ]),
+                extra: Map::new(),
+            },
+            crate::ChatMessage {
+                role: "assistant".to_string(),
+                content: Value::Null,
+                extra: json!({"tool_calls": [{
+                    "id": "call_0",
+                    "type": "function",
+                    "function": {"name": "get_weather", "arguments": "{\"city\":\"Oslo\"}"}
+                }]})
+                .as_object()
+                .unwrap()
+                .clone(),
+            },
+        ];
+        let body = chat_request(&req, "llava", None).unwrap();
+
+        assert_eq!(
+            body["messages"][0]["content"],
+            "What is in this picture?\nAnd where?"
+        );
+        assert_eq!(body["messages"][0]["images"], json!(["iVBORw0KGgo="]));
+        assert_eq!(body["messages"][1]["content"], "");
+        assert_eq!(
+            body["messages"][1]["tool_calls"][0]["function"]["arguments"],
+            json!({"city": "Oslo"})
+        );
+        assert_eq!(body["tools"], tools);
+    }
+
+    #[test]
+    fn chat_request_rejects_parts_the_native_api_cannot_express() {
+        let mut req = request(json!({}), false);
+        req.messages[0].content = json!([
+            {"type": "text", "text": "Describe"},
+            {"type": "image_url", "image_url": {"url": "https://example.com/cat.png"}}
+        ]);
+        let err = chat_request(&req, "llava", None).unwrap_err();
+        assert_eq!(err.param, "messages[0].content[1]");
+        assert_eq!(err.to_json()["error"]["code"], "unsupported_by_endpoint");
+
+        req.messages[0].content = json!([{"type": "input_audio", "input_audio": {}}]);
+        assert!(chat_request(&req, "llava", None).is_err());
+    }
+
+    #[test]
+    fn tool_calls_are_converted_back_to_openai_shape() {
+        let upstream = json!({
+            "message": {
+                "role": "assistant",
+                "content": "",
+                "tool_calls": [{"function": {"name": "get_weather", "arguments": {"city": "Oslo"}}}]
+            },
+            "done": true,
+            "done_reason": "stop"
+        });
+        let resp = chat_response(&upstream, "llama3.1:8b");
+        let call = &resp["choices"][0]["message"]["tool_calls"][0];
+
+        assert_eq!(resp["choices"][0]["finish_reason"], "tool_calls");
+        assert_eq!(call["type"], "function");
+        assert_eq!(call["id"], "call_0");
+        assert_eq!(call["function"]["arguments"], "{\"city\":\"Oslo\"}");
+    }
+
+    #[test]
+    fn chat_response_converts_to_openai_shape() {
+        let upstream = json!({
+            "model": "llama3.1:8b",
+            "message": {"role": "assistant", "content": "hello"},
+            "done": true,
+            "done_reason": "stop",
+            "prompt_eval_count": 5,
+            "eval_count": 2
+        });
+        let resp = chat_response(&upstream, "llama3.1:8b");
+
+        assert_eq!(resp["object"], "chat.completion");
+        assert_eq!(resp["choices"][0]["message"]["content"], "hello");
+        assert_eq!(resp["choices"][0]["finish_reason"], "stop");
+        assert_eq!(resp["usage"]["total_tokens"], 7);
+    }
+
+    #[tokio::test]
+    async fn sse_stream_converts_ndjson_lines() {
+        let lines: Vec<reqwest::Result<Bytes>> = vec![
+            Ok(Bytes::from_static(
+                b"{\"message\":{\"role\":\"assistant\",\"content\":\"He\"},\"done\":false}\n{\"mess",
+            )),
+            Ok(Bytes::from_static(
+                b"age\":{\"role\":\"assistant\",\"content\":\"y\"},\"done\":false}\n",
+            )),
+            Ok(Bytes::from_static(
+                b"{\"done\":true,\"done_reason\":\"stop\",\"eval_count\":2}",
+            )),
+        ];
+        let stream = sse_stream(futures::stream::iter(lines), "m".to_string());
+        let events: Vec<_> = stream.map(|r| r.unwrap()).collect().await;
+        let text: String = events
+            .iter()
+            .map(|b| String::from_utf8_lossy(b).into_owned())
+            .collect();
+
+        assert_eq!(text.matches("chat.completion.chunk").count(), 3);
+        assert!(text.contains("\"content\":\"He\""));
+        assert!(text.contains("\"content\":\"y\""));
+        assert!(text.contains("\"finish_reason\":\"stop\""));
+        assert!(text.ends_with("data: [DONE]\n\n"));
+    }
+}
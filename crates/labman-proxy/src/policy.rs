@@ -0,0 +1,221 @@
+//! Enforcement of per-endpoint / per-model request policies.
+//!
+//! Policies are configured on endpoints and `[[models]]` entries (see
+//! `labman_config::RequestPolicyConfig`) and applied to chat completion
+//! requests after slug resolution, before anything is sent upstream:
+//!
+//! - Forbidden parameters reject the request. `stream: false` does not count
+//!   as using `stream`.
+//! - `max_tokens` / `max_completion_tokens` and `n` above the limits are
+//!   lowered to the limit, or rejected with `reject_over_limit`. Requests
+//!   without a token limit get the policy's one. Values that are not
+//!   non-negative integers are rejected when a limit applies, so they can't
+//!   slip past the clamp.
+//! - A missing `temperature` is filled in from `default_temperature`.
+//!
+//! Rejections are reported to the caller as an OpenAI-style error object.
+
+use labman_config::RequestPolicyConfig;
+use serde_json::{json, Value};
+
+use crate::ChatCompletionRequest;
+
+/// Request parameters that bound the number of generated tokens.
+const TOKEN_LIMIT_PARAMS: &[&str] = &["max_tokens", "max_completion_tokens"];
+
+/// A request rejected by policy.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PolicyViolation {
+    /// The offending request parameter.
+    pub param: String,
+
+    /// Human-readable explanation.
+    pub message: String,
+}
+
+impl PolicyViolation {
+    /// OpenAI-style error body returned with `400 Bad Request`.
+    pub(crate) fn to_json(&self) -> Value {
+        json!({
+            "error": {
+                "message": self.message,
+                "type": "invalid_request_error",
+                "param": self.param,
+                "code": "policy_violation",
+            }
+        })
+    }
+}
+
+/// Apply `policy` to `req` in place, or explain why it must be rejected.
+pub(crate) fn apply(
+    policy: &RequestPolicyConfig,
+    req: &mut ChatCompletionRequest,
+) -> Result<(), PolicyViolation> {
+    if !req.extra.is_object() {
+        req.extra = json!({});
+    }
+    let reject_over_limit = policy.reject_over_limit.unwrap_or(false);
+    let params = req.extra.as_object_mut().expect("extra is an object");
+
+    for param in policy.forbidden_params.iter().flatten() {
+        let present = match param.as_str() {
+            "stream" => req.stream == Some(true),
+            _ => params.get(param).is_some_and(|v| !v.is_null()),
+        };
+        if present {
+            return Err(PolicyViolation {
+                param: param.clone(),
+                message: format!("parameter '{}' is not allowed for this model", param),
+            });
+        }
+    }
+
+    if let Some(limit) = policy.max_tokens {
+        let mut has_limit = false;
+        for param in TOKEN_LIMIT_PARAMS {
+            let Some(value) = integer_param(params, param)? else {
+                continue;
+            };
+            has_limit = true;
+            if value > limit {
+                if reject_over_limit {
+                    return Err(PolicyViolation {
+                        param: param.to_string(),
+                        message: format!(
+                            "'{}' must not exceed {} for this model (got {})",
+                            param, limit, value
+                        ),
+                    });
+                }
+                tracing::debug!("policy: lowering {} from {} to {}", param, value, limit);
+                params.insert(param.to_string(), json!(limit));
+            }
+        }
+        if !has_limit {
+            params.insert("max_tokens".to_string(), json!(limit));
+        }
+    }
+
+    if let Some(limit) = policy.max_n {
+        if let Some(n) = integer_param(params, "n")?.filter(|n| *n > limit) {
+            if reject_over_limit {
+                return Err(PolicyViolation {
+                    param: "n".to_string(),
+                    message: format!("'n' must not exceed {} for this model (got {})", limit, n),
+                });
+            }
+            tracing::debug!("policy: lowering n from {} to {}", n, limit);
+            params.insert("n".to_string(), json!(limit));
+        }
+    }
+
+    if let Some(temperature) = policy.default_temperature {
+        if params.get("temperature").is_none_or(Value::is_null) {
+            params.insert("temperature".to_string(), json!(temperature));
+        }
+    }
+
+    Ok(())
+}
+
+/// The value of a count parameter, or a violation if it is set to anything
+/// but a non-negative integer.
+fn integer_param(
+    params: &serde_json::Map<String, Value>,
+    param: &str,
+) -> Result<Option<u64>, PolicyViolation> {
+    match params.get(param) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => value.as_u64().map(Some).ok_or_else(|| PolicyViolation {
+            param: param.to_string(),
+            message: format!("'{}' must be a non-negative integer (got {})", param, value),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(extra: Value) -> ChatCompletionRequest {
+        let mut body = json!({"model": "slug", "messages": []});
+        body.as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        serde_json::from_value(body).unwrap()
+    }
+
+    #[test]
+    fn limits_are_clamped_and_defaults_filled() {
+        let policy = RequestPolicyConfig {
+            max_tokens: Some(256),
+            max_n: Some(1),
+            default_temperature: Some(0.3),
+            ..Default::default()
+        };
+
+        let mut req = request(json!({"max_tokens": 4096, "n": 4}));
+        apply(&policy, &mut req).unwrap();
+        assert_eq!(req.extra["max_tokens"], 256);
+        assert_eq!(req.extra["n"], 1);
+        assert_eq!(req.extra["temperature"], 0.3);
+
+        // No limit requested: the policy's one is added; explicit
+        // temperature is kept.
+        let mut req = request(json!({"temperature": 1.0}));
+        apply(&policy, &mut req).unwrap();
+        assert_eq!(req.extra["max_tokens"], 256);
+        assert_eq!(req.extra["temperature"], 1.0);
+
+        // `max_completion_tokens` counts as a limit as well.
+        let mut req = request(json!({"max_completion_tokens": 100}));
+        apply(&policy, &mut req).unwrap();
+        assert_eq!(req.extra["max_completion_tokens"], 100);
+        assert!(req.extra.get("max_tokens").is_none());
+    }
+
+    #[test]
+    fn violations_are_rejected() {
+        let policy = RequestPolicyConfig {
+            max_tokens: Some(256),
+            reject_over_limit: Some(true),
+            forbidden_params: Some(vec!["logprobs".to_string()]),
+            ..Default::default()
+        };
+
+        let err = apply(&policy, &mut request(json!({"logprobs": true}))).unwrap_err();
+        assert_eq!(err.param, "logprobs");
+        assert_eq!(err.to_json()["error"]["code"], "policy_violation");
+
+        let err = apply(&policy, &mut request(json!({"max_tokens": 1000}))).unwrap_err();
+        assert_eq!(err.param, "max_tokens");
+
+        assert!(apply(&policy, &mut request(json!({"max_tokens": 10}))).is_ok());
+    }
+
+    #[test]
+    fn stream_false_and_non_integer_limits() {
+        let policy = RequestPolicyConfig {
+            max_tokens: Some(256),
+            max_n: Some(1),
+            forbidden_params: Some(vec!["stream".to_string()]),
+            ..Default::default()
+        };
+
+        assert!(apply(&policy, &mut request(json!({"stream": false}))).is_ok());
+        let err = apply(&policy, &mut request(json!({"stream": true}))).unwrap_err();
+        assert_eq!(err.param, "stream");
+
+        // Floats and negative numbers can't be clamped; they are rejected
+        // even without `reject_over_limit`.
+        for (param, value) in [
+            ("max_tokens", json!(1e9)),
+            ("max_completion_tokens", json!(-1)),
+            ("n", json!(2.5)),
+        ] {
+            let err = apply(&policy, &mut request(json!({ param: value }))).unwrap_err();
+            assert_eq!(err.param, param);
+        }
+    }
+}
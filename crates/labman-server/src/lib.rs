@@ -8,8 +8,7 @@ use hyper::server::conn::http1;
 use hyper_util::rt::TokioIo;
 use hyper_util::service::TowerToHyperService;
 use labman_telemetry::{
-    prometheus_impl::prometheus_http_response, MetricsRecorder, NoopMetricsRecorder,
-    PrometheusMetricsRecorder,
+    prometheus_impl::prometheus_http_response, MetricsRecorder, PrometheusMetricsRecorder,
 };
 use tokio::net::TcpListener;
 use tokio::task::JoinHandle;
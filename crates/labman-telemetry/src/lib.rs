@@ -1,33 +1,48 @@
 use std::env;
+use std::path::Path;
 use std::str::FromStr;
+use std::sync::Mutex;
 
+use labman_config::TelemetryConfig;
 use time::{format_description, UtcOffset};
 use tracing::Level;
 use tracing_subscriber::filter::EnvFilter;
 use tracing_subscriber::fmt;
 use tracing_subscriber::fmt::time::OffsetTime;
+use tracing_subscriber::fmt::writer::MakeWriter;
 use tracing_subscriber::prelude::*;
+use tracing_subscriber::Layer;
+
+use crate::log_file::RotatingFile;
+
+mod log_file;
 
 /// Re-export the Prometheus-backed metrics recorder so that other crates can
 /// depend on a concrete type without any feature gating.
 pub use crate::prometheus_impl::PrometheusMetricsRecorder;
 
 use prometheus::{
-    Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, Opts, Registry, TextEncoder,
+    Encoder, GaugeVec, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, Opts,
+    Registry, TextEncoder,
 };
 
 use hyper::{body::Bytes, Response};
 
 /// Error type for telemetry initialisation failures.
 ///
-/// This is intentionally lightweight so `labman-telemetry` can be used
-/// without depending on `labman-core`. Callers can map this into their own
+/// This is intentionally lightweight; callers can map it into their own
 /// error types as needed.
 #[derive(Debug)]
 pub enum TelemetryError {
     /// Provided log level string could not be parsed.
     InvalidLevel(String),
 
+    /// The `[telemetry]` logging settings are inconsistent.
+    InvalidConfig(String),
+
+    /// The configured log output (file or journald) could not be opened.
+    LogOutput(String),
+
     /// Failed to configure the subscriber (should be rare).
     SubscriberInit(String),
 }
@@ -38,6 +53,10 @@ impl std::fmt::Display for TelemetryError {
             TelemetryError::InvalidLevel(level) => {
                 write!(f, "invalid log level: {}", level)
             }
+            TelemetryError::InvalidConfig(msg) => {
+                write!(f, "invalid telemetry configuration: {}", msg)
+            }
+            TelemetryError::LogOutput(msg) => write!(f, "failed to open log output: {}", msg),
             TelemetryError::SubscriberInit(msg) => write!(f, "failed to init telemetry: {}", msg),
         }
     }
@@ -120,6 +139,36 @@ pub trait MetricsRecorder: Send + Sync + 'static {
     ///
     /// This is typically mirrored by a gauge in the concrete implementation.
     fn set_active_requests(&self, count: u64);
+
+    /// Record the load an endpoint's runtime reports about itself.
+    ///
+    /// Called after each successful load scrape. `None` values are not
+    /// exported. The default implementation does nothing.
+    fn record_endpoint_load(
+        &self,
+        _endpoint: &str,
+        _running: Option<u64>,
+        _waiting: Option<u64>,
+        _kv_cache_usage: Option<f64>,
+    ) {
+    }
+
+    /// Record the outcome of a synthetic inference probe.
+    ///
+    /// `latency_secs` is only present for successful probes. The default
+    /// implementation does nothing.
+    fn record_probe(
+        &self,
+        _endpoint: &str,
+        _model: &str,
+        _success: bool,
+        _latency_secs: Option<f64>,
+    ) {
+    }
+
+    /// Count an inventory change event (e.g. "model_added",
+    /// "endpoint_down"). The default implementation does nothing.
+    fn record_inventory_event(&self, _kind: &str) {}
 }
 
 /// A no-op metrics recorder that does nothing.
@@ -201,6 +250,12 @@ pub mod prometheus_impl {
         request_latency_seconds: HistogramVec,
         active_requests: IntGauge,
         errors_total: IntCounterVec,
+        endpoint_running_requests: IntGaugeVec,
+        endpoint_waiting_requests: IntGaugeVec,
+        endpoint_kv_cache_usage: GaugeVec,
+        probes_total: IntCounterVec,
+        probe_latency_seconds: HistogramVec,
+        inventory_events_total: IntCounterVec,
     }
 
     impl PrometheusMetricsRecorder {
@@ -259,12 +314,96 @@ pub mod prometheus_impl {
                 .register(Box::new(errors_total.clone()))
                 .expect("failed to register labman_errors_total");
 
+            let endpoint_running_requests = IntGaugeVec::new(
+                Opts::new(
+                    "labman_endpoint_running_requests",
+                    "Requests running on an endpoint as reported by its runtime",
+                )
+                .namespace("labman"),
+                &["endpoint"],
+            )
+            .expect("failed to create labman_endpoint_running_requests gauge");
+            registry
+                .register(Box::new(endpoint_running_requests.clone()))
+                .expect("failed to register labman_endpoint_running_requests");
+
+            let endpoint_waiting_requests = IntGaugeVec::new(
+                Opts::new(
+                    "labman_endpoint_waiting_requests",
+                    "Requests queued on an endpoint as reported by its runtime",
+                )
+                .namespace("labman"),
+                &["endpoint"],
+            )
+            .expect("failed to create labman_endpoint_waiting_requests gauge");
+            registry
+                .register(Box::new(endpoint_waiting_requests.clone()))
+                .expect("failed to register labman_endpoint_waiting_requests");
+
+            let endpoint_kv_cache_usage = GaugeVec::new(
+                Opts::new(
+                    "labman_endpoint_kv_cache_usage_ratio",
+                    "KV-cache utilisation of an endpoint as reported by its runtime",
+                )
+                .namespace("labman"),
+                &["endpoint"],
+            )
+            .expect("failed to create labman_endpoint_kv_cache_usage_ratio gauge");
+            registry
+                .register(Box::new(endpoint_kv_cache_usage.clone()))
+                .expect("failed to register labman_endpoint_kv_cache_usage_ratio");
+
+            let probes_total = IntCounterVec::new(
+                Opts::new(
+                    "labman_probes_total",
+                    "Total number of synthetic inference probes",
+                )
+                .namespace("labman"),
+                &["endpoint", "model", "success"],
+            )
+            .expect("failed to create labman_probes_total counter");
+            registry
+                .register(Box::new(probes_total.clone()))
+                .expect("failed to register labman_probes_total");
+
+            let probe_latency_seconds = HistogramVec::new(
+                HistogramOpts::new(
+                    "labman_probe_latency_seconds",
+                    "Latency of successful synthetic inference probes in seconds",
+                )
+                .namespace("labman"),
+                &["endpoint", "model"],
+            )
+            .expect("failed to create labman_probe_latency_seconds histogram");
+            registry
+                .register(Box::new(probe_latency_seconds.clone()))
+                .expect("failed to register labman_probe_latency_seconds");
+
+            let inventory_events_total = IntCounterVec::new(
+                Opts::new(
+                    "labman_inventory_events_total",
+                    "Total number of model inventory change events",
+                )
+                .namespace("labman"),
+                &["kind"],
+            )
+            .expect("failed to create labman_inventory_events_total counter");
+            registry
+                .register(Box::new(inventory_events_total.clone()))
+                .expect("failed to register labman_inventory_events_total");
+
             Self {
                 registry,
                 requests_total,
                 request_latency_seconds,
                 active_requests,
                 errors_total,
+                endpoint_running_requests,
+                endpoint_waiting_requests,
+                endpoint_kv_cache_usage,
+                probes_total,
+                probe_latency_seconds,
+                inventory_events_total,
             }
         }
 
@@ -274,6 +413,12 @@ pub mod prometheus_impl {
         }
     }
 
+    impl Default for PrometheusMetricsRecorder {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
     impl MetricsRecorder for PrometheusMetricsRecorder {
         fn record_request_start(&self, _endpoint: Option<&str>, _model: Option<&str>) {
             // We don't change any counters here; active_requests is updated via
@@ -312,69 +457,192 @@ pub mod prometheus_impl {
         fn set_active_requests(&self, count: u64) {
             self.active_requests.set(count as i64);
         }
+
+        fn record_endpoint_load(
+            &self,
+            endpoint: &str,
+            running: Option<u64>,
+            waiting: Option<u64>,
+            kv_cache_usage: Option<f64>,
+        ) {
+            if let Some(running) = running {
+                self.endpoint_running_requests
+                    .with_label_values(&[endpoint])
+                    .set(running as i64);
+            }
+            if let Some(waiting) = waiting {
+                self.endpoint_waiting_requests
+                    .with_label_values(&[endpoint])
+                    .set(waiting as i64);
+            }
+            if let Some(usage) = kv_cache_usage {
+                self.endpoint_kv_cache_usage
+                    .with_label_values(&[endpoint])
+                    .set(usage);
+            }
+        }
+
+        fn record_probe(
+            &self,
+            endpoint: &str,
+            model: &str,
+            success: bool,
+            latency_secs: Option<f64>,
+        ) {
+            let success_label = if success { "true" } else { "false" };
+            self.probes_total
+                .with_label_values(&[endpoint, model, success_label])
+                .inc();
+
+            if let Some(lat) = latency_secs {
+                self.probe_latency_seconds
+                    .with_label_values(&[endpoint, model])
+                    .observe(lat);
+            }
+        }
+
+        fn record_inventory_event(&self, kind: &str) {
+            self.inventory_events_total.with_label_values(&[kind]).inc();
+        }
     }
 }
 
 /// Initialise the global telemetry / logging subscriber.
 ///
-/// This sets up a `tracing_subscriber` using `EnvFilter` and a formatted
-/// output layer. It is intended to be called once at process startup
-/// (typically from `main` in the daemon or CLI).
+/// This sets up a `tracing_subscriber` using `EnvFilter` and a single output
+/// layer chosen by the `[telemetry]` section. It is intended to be called
+/// once at process startup (typically from `main` in the daemon or CLI).
 ///
 /// # Parameters
 ///
-/// - `level`: Optional log level string. If `None`, the function will:
-///   - Respect `RUST_LOG` if it is set, or
-///   - Default to `"info"` otherwise.
-///   If `Some(level)` is provided, it takes precedence over `RUST_LOG`.
+/// - `config`: Optional `[telemetry]` section. Without one, logs are written
+///   to stdout as text.
+/// - `level`: Optional log level string. The filter is taken from the first
+///   of:
+///   - `level` (e.g. `--log-level`),
+///   - `RUST_LOG`,
+///   - `telemetry.log_level`,
+///   - `"info"`.
 ///
 /// # Behavior
 ///
-/// - Logs are formatted with timestamps, level, and target.
+/// - `telemetry.log_format` selects `"text"` (the default) or `"json"`
+///   lines, both with timestamps, level, and target.
+/// - `telemetry.log_output` selects `"stdout"` (the default), `"file"`
+///   (written to `telemetry.log_file` and rotated once it reaches
+///   `log_file_max_size_mb`, keeping `log_file_max_files` old files) or
+///   `"journald"` (native journal entries whose span and event fields become
+///   journal fields; `log_format` does not apply).
 /// - A single global subscriber is installed. Calling `init` more than once
-///   will be treated as a no-op by `tracing_subscriber::registry().init()`.
+///   returns [`TelemetryError::SubscriberInit`].
 ///
 /// # Examples
 ///
 /// Basic usage with default level:
 ///
 /// ```ignore
-/// labman_telemetry::init(None)?;
+/// labman_telemetry::init(None, None)?;
 /// ```
 ///
-/// Explicit level:
+/// Explicit level, overriding whatever the configuration says:
 ///
 /// ```ignore
-/// labman_telemetry::init(Some("debug"))?;
+/// labman_telemetry::init(config.telemetry.as_ref(), Some("debug"))?;
 /// ```
 ///
 /// Respect `RUST_LOG` (when `level` is `None`):
 ///
 /// ```ignore
 /// // RUST_LOG=labmand=trace labmand ...
-/// labman_telemetry::init(None)?;
+/// labman_telemetry::init(config.telemetry.as_ref(), None)?;
 /// ```
-pub fn init(level: Option<&str>) -> Result<()> {
+pub fn init(config: Option<&TelemetryConfig>, level: Option<&str>) -> Result<()> {
     // Determine the effective filter string:
     //
     // - If an explicit level is provided, use that (e.g. "info", "debug").
     // - Otherwise:
     //   - If RUST_LOG is set, let EnvFilter parse it.
-    //   - Else default to "info".
+    //   - Else use telemetry.log_level, defaulting to "info".
     let filter = if let Some(level_str) = level {
         parse_level_filter(level_str)?
     } else if env::var("RUST_LOG").is_ok() {
         EnvFilter::from_default_env()
+    } else if let Some(level_str) = config.and_then(|c| c.log_level.as_deref()) {
+        parse_level_filter(level_str)?
     } else {
         EnvFilter::new("info")
     };
 
-    // Build a text formatter with timestamps, level, and target (module path).
-    let fmt_layer = fmt::layer()
+    let output = log_output(config)?;
+
+    // Install as global subscriber.
+    tracing_subscriber::registry()
+        .with(output.with_filter(filter))
+        .try_init()
+        .map_err(|e| TelemetryError::SubscriberInit(e.to_string()))?;
+
+    Ok(())
+}
+
+type BoxedLayer = Box<dyn Layer<tracing_subscriber::Registry> + Send + Sync>;
+
+/// Build the output layer selected by `telemetry.log_output` and
+/// `telemetry.log_format`.
+fn log_output(config: Option<&TelemetryConfig>) -> Result<BoxedLayer> {
+    let json = match config.and_then(|c| c.log_format.as_deref()) {
+        None | Some("text") => false,
+        Some("json") => true,
+        Some(other) => {
+            return Err(TelemetryError::InvalidConfig(format!(
+                "unknown log_format '{}' (expected text or json)",
+                other
+            )))
+        }
+    };
+
+    match config.and_then(|c| c.log_output.as_deref()) {
+        None | Some("stdout") => Ok(fmt_layer(std::io::stdout, json, true)),
+        Some("file") => {
+            let config = config.expect("log_output is only set with a config");
+            let path = config.log_file.as_deref().ok_or_else(|| {
+                TelemetryError::InvalidConfig(
+                    "log_file is required when log_output is \"file\"".to_string(),
+                )
+            })?;
+            let file = RotatingFile::open(
+                Path::new(path),
+                config.log_file_max_size_mb.saturating_mul(1024 * 1024),
+                config.log_file_max_files,
+            )
+            .map_err(|e| TelemetryError::LogOutput(format!("{}: {}", path, e)))?;
+            Ok(fmt_layer(Mutex::new(file), json, false))
+        }
+        Some("journald") => {
+            let layer = tracing_journald::layer()
+                .map_err(|e| TelemetryError::LogOutput(format!("journald: {}", e)))?
+                .with_syslog_identifier("labmand".to_string());
+            Ok(layer.boxed())
+        }
+        Some(other) => Err(TelemetryError::InvalidConfig(format!(
+            "unknown log_output '{}' (expected stdout, file or journald)",
+            other
+        ))),
+    }
+}
+
+/// A formatted (text or JSON) layer with timestamps, level, and target
+/// (module path) writing to `writer`.
+fn fmt_layer<W>(writer: W, json: bool, ansi: bool) -> BoxedLayer
+where
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    let layer = fmt::layer()
         .with_target(true)
         .with_thread_ids(false)
         .with_thread_names(false)
         .with_level(true)
+        .with_ansi(ansi)
+        .with_writer(writer)
         .with_timer(OffsetTime::new(
             // Use local time with offset; falls back to UTC if offset cannot be determined.
             UtcOffset::current_local_offset().unwrap_or(UtcOffset::UTC),
@@ -387,15 +655,11 @@ pub fn init(level: Option<&str>) -> Result<()> {
             }),
         ));
 
-    // Compose registry + filter + formatter.
-    let subscriber = tracing_subscriber::registry().with(filter).with(fmt_layer);
-
-    // Install as global subscriber.
-    subscriber
-        .try_init()
-        .map_err(|e| TelemetryError::SubscriberInit(e.to_string()))?;
-
-    Ok(())
+    if json {
+        layer.json().boxed()
+    } else {
+        layer.boxed()
+    }
 }
 
 /// Parse a simple level string into an `EnvFilter`.
@@ -438,14 +702,54 @@ mod tests {
         let _ = f;
     }
 
+    #[test]
+    fn log_output_rejects_inconsistent_settings() {
+        let config = |toml: &str| -> TelemetryConfig { toml::from_str(toml).unwrap() };
+
+        assert!(log_output(None).is_ok());
+        assert!(log_output(Some(&config(r#"log_format = "json""#))).is_ok());
+        assert!(matches!(
+            log_output(Some(&config(r#"log_format = "yaml""#))),
+            Err(TelemetryError::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            log_output(Some(&config(r#"log_output = "file""#))),
+            Err(TelemetryError::InvalidConfig(_))
+        ));
+        assert!(matches!(
+            log_output(Some(&config(
+                r#"
+log_output = "file"
+log_file = "/nonexistent/labman/labmand.log"
+"#
+            ))),
+            Err(TelemetryError::LogOutput(_))
+        ));
+    }
+
     #[test]
     fn noop_metrics_recorder_does_not_panic() {
-        let recorder = NoopMetricsRecorder::default();
+        let recorder = NoopMetricsRecorder;
 
         recorder.record_request_start(Some("endpoint-1"), Some("model-A"));
         recorder.record_request_end(Some("endpoint-1"), Some("model-A"), true, Some(0.123));
         recorder.record_error(Some("endpoint-1"), "timeout");
         recorder.set_active_requests(5);
+        recorder.record_endpoint_load("endpoint-1", Some(2), None, Some(0.5));
+        recorder.record_probe("endpoint-1", "model-A", true, Some(0.05));
+        recorder.record_inventory_event("model_added");
+    }
+
+    #[test]
+    fn prometheus_recorder_exports_endpoint_load() {
+        let recorder = PrometheusMetricsRecorder::new();
+        recorder.record_endpoint_load("vllm-a", Some(3), Some(1), Some(0.25));
+
+        let response = prometheus_impl::prometheus_http_response(recorder.registry());
+        let body = String::from_utf8_lossy(response.body()).into_owned();
+        assert!(body.contains("labman_endpoint_running_requests{endpoint=\"vllm-a\"} 3"));
+        assert!(body.contains("labman_endpoint_waiting_requests{endpoint=\"vllm-a\"} 1"));
+        assert!(body.contains("labman_endpoint_kv_cache_usage_ratio{endpoint=\"vllm-a\"} 0.25"));
     }
 
     // Note: `EnvFilter` is intentionally permissive and accepts many strings as
@@ -0,0 +1,117 @@
+//! Size-based rotation for `telemetry.log_output = "file"`.
+//!
+//! [`RotatingFile`] appends to the configured log file and, once the next
+//! write would take it past the size limit, shifts `labmand.log` to
+//! `labmand.log.1`, `labmand.log.1` to `labmand.log.2` and so on, dropping
+//! the oldest file beyond the configured count.
+
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// A log file that rotates itself when it grows past `max_bytes`.
+#[derive(Debug)]
+pub(crate) struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    written: u64,
+    max_bytes: u64,
+    max_files: usize,
+}
+
+impl RotatingFile {
+    /// Open (or create) `path` for appending, keeping at most `max_files`
+    /// rotated files of up to `max_bytes` each.
+    pub(crate) fn open(path: &Path, max_bytes: u64, max_files: usize) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let written = file.metadata()?.len();
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            written,
+            max_bytes: max_bytes.max(1),
+            max_files: max_files.max(1),
+        })
+    }
+
+    fn rotated(&self, n: usize) -> PathBuf {
+        let mut name = OsString::from(self.path.as_os_str());
+        name.push(format!(".{}", n));
+        PathBuf::from(name)
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        for n in (1..self.max_files).rev() {
+            match fs::rename(self.rotated(n), self.rotated(n + 1)) {
+                Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+                _ => {}
+            }
+        }
+        fs::rename(&self.path, self.rotated(1))?;
+        self.file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&self.path)?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // A single line larger than the limit still gets written, to a file
+        // of its own.
+        if self.written > 0 && self.written + buf.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        let n = self.file.write(buf)?;
+        self.written += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotates_by_size_and_keeps_max_files() {
+        let dir = std::env::temp_dir().join(format!("labman-log-file-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("labmand.log");
+
+        let mut file = RotatingFile::open(&path, 16, 2).unwrap();
+        for line in [
+            "first line\n",
+            "second line\n",
+            "third line\n",
+            "fourth line\n",
+        ] {
+            file.write_all(line.as_bytes()).unwrap();
+        }
+        file.flush().unwrap();
+
+        let read = |name: &str| fs::read_to_string(dir.join(name)).unwrap();
+        assert_eq!(read("labmand.log"), "fourth line\n");
+        assert_eq!(read("labmand.log.1"), "third line\n");
+        assert_eq!(read("labmand.log.2"), "second line\n");
+        assert!(!dir.join("labmand.log.3").exists());
+
+        // Reopening picks up the current size instead of starting over.
+        let mut file = RotatingFile::open(&path, 16, 2).unwrap();
+        file.write_all(b"fifth\n").unwrap();
+        assert_eq!(read("labmand.log"), "fifth\n");
+        assert_eq!(read("labmand.log.1"), "fourth line\n");
+        assert_eq!(read("labmand.log.2"), "third line\n");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -138,6 +138,7 @@ pub trait RosenpassEngine: Send + Sync {
 /// This is intended for development and simple deployments where:
 /// - The host has standard WireGuard tooling installed,
 /// - labman is running with sufficient privileges to manage interfaces.
+#[derive(Default)]
 pub struct ShellWireGuardBackend {
     /// Optional timeout for shell commands.
     pub command_timeout: Option<Duration>,
@@ -288,6 +289,7 @@ impl WireGuardBackend for ShellWireGuardBackend {
 /// - Spawn/monitor a Rosenpass daemon,
 /// - Interact with a Rosenpass control socket,
 /// - Use richer configuration semantics.
+#[derive(Default)]
 pub struct SystemRosenpassEngine;
 
 impl SystemRosenpassEngine {